@@ -6,7 +6,7 @@ pub const BS_IDENT_CONTENT: u32 = 0x42650200;
 use std::{io, marker::Unpin};
 use futures_lite::{AsyncWrite, AsyncWriteExt, AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 use foundations::{error_enum, usize_casting::*, sha3::*};
-use zeon::{meta::{ByteRepr, Commit, CommitPtr, CommitIndexItem}, types::{Value, Schema, DecodeError}};
+use zeon::{meta::{ByteRepr, Commit, CommitPtr, CommitIndexItem}, types::{Value, Schema, EncodeError, DecodeError, DeserializeError}};
 
 pub type Hash = [u8; 32];
 
@@ -19,9 +19,13 @@ error_enum! {
         Unorder(CommitPtr, CommitPtr),
         Hash(Hash, Hash),
         Duplicate(CommitPtr),
+        // position of the commit in the index, its offset in the content as the index gives it, and
+        // the error within it
+        Decode(usize, u64, DecodeError),
         // Seek(u64, u64),
     } convert {
-        Decode => DecodeError,
+        Encode => EncodeError,
+        Deserialize => DeserializeError,
     }
 }
 
@@ -30,22 +34,24 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub struct Writer<F: AsyncWrite + Unpin> {
     index: F,
     content: F,
+    // reused across commits
+    buf: Vec<u8>,
 }
 
 impl<F: AsyncWrite + Unpin> Writer<F> {
     pub async fn init(mut index: F, mut content: F) -> Result<Writer<F>> {
         index.write_all(&BS_IDENT_INDEX.to_be_bytes()).await.map_err(Error::IndexIo)?;
         content.write_all(&BS_IDENT_CONTENT.to_be_bytes()).await.map_err(Error::ContentIo)?;
-        Ok(Writer { index, content })
+        Ok(Writer { index, content, buf: Vec::new() })
     }
 
     pub async fn write_commit(&mut self, commit: Commit) -> Result<()> {
         let ptr = commit.ptr.clone();
-        let content = commit.serialize().encode();
-        let len = usize_u64(content.len());
-        let hash = shake256_once(&content);
+        self.buf.clear();
+        let hash = commit.serialize().encode_into_hashed(&mut self.buf)?;
+        let len = usize_u64(self.buf.len());
         let index = CommitIndexItem { ptr, len, hash }.to_bytes();
-        self.content.write_all(&content).await.map_err(Error::ContentIo)?;
+        self.content.write_all(&self.buf).await.map_err(Error::ContentIo)?;
         // self.content.flush().await.map_err(Error::ContentIo)?;
         self.index.write_all(&index).await.map_err(Error::IndexIo)?;
         // self.index.flush().await.map_err(Error::IndexIo)?;
@@ -21,25 +21,25 @@ impl MemoryIndex {
         self.items
     }
 
-    pub fn find(&self, ptr: CommitPtr) -> Option<(u64, CommitIndexItem)> {
+    pub fn find(&self, ptr: CommitPtr) -> Option<(usize, u64, CommitIndexItem)> {
         let mut offset = 0;
-        for index_item in &self.items {
+        for (pos, index_item) in self.items.iter().enumerate() {
             if ptr != index_item.ptr {
                 offset += index_item.len;
             } else {
-                return Some((offset, index_item.clone()));
+                return Some((pos, offset, index_item.clone()));
             }
         }
         None
     }
 
-    pub fn find_by_hash(&self, hash: Hash) -> Option<(u64, CommitIndexItem)> {
+    pub fn find_by_hash(&self, hash: Hash) -> Option<(usize, u64, CommitIndexItem)> {
         let mut offset = 0;
-        for index_item in &self.items {
+        for (pos, index_item) in self.items.iter().enumerate() {
             if hash != index_item.hash {
                 offset += index_item.len;
             } else {
-                return Some((offset, index_item.clone()));
+                return Some((pos, offset, index_item.clone()));
             }
         }
         None
@@ -47,13 +47,14 @@ impl MemoryIndex {
 }
 
 impl Iterator for MemoryIndex {
-    type Item = (u64, CommitIndexItem);
+    // position in the index, offset in the content, and the item
+    type Item = (usize, u64, CommitIndexItem);
 
     fn next(&mut self) -> Option<Self::Item> {
         let item = self.items.get(self.index)?;
-        let offset = self.offset;
+        let (pos, offset) = (self.index, self.offset);
         self.index += 1;
         self.offset += item.len;
-        Some((offset, item.clone()))
+        Some((pos, offset, item.clone()))
     }
 }
@@ -36,12 +36,12 @@ async fn index_read_index<F: AsyncRead + Unpin>(f: &mut F) -> Option<Result<Comm
     }
 }
 
-async fn content_read_commit<F: AsyncRead + Unpin>(f: &mut F, CommitIndexItem { ptr, len, hash }: CommitIndexItem) -> Result<(Commit, Hash)> {
+async fn content_read_commit<F: AsyncRead + Unpin>(f: &mut F, (pos, offset, CommitIndexItem { ptr, len, hash }): (usize, u64, CommitIndexItem)) -> Result<(Commit, Hash)> {
     let mut content = vec![0; u64_usize(len)];
     f.read_exact(&mut content).await.map_err(Error::ContentIo)?;
     let _hash = shake256_once(&content);
     check!(hash, _hash, Hash);
-    let commit = Commit::deserialize(Value::decode(&content)?);
+    let commit = Commit::try_deserialize(Value::decode(&content).map_err(|err| Error::Decode(pos, offset, err))?)?;
     check!(ptr, commit.ptr, Unorder);
     Ok((commit, hash))
 }
@@ -55,6 +55,9 @@ async fn content_seek<F: AsyncRead + AsyncSeek + Unpin>(f: &mut F, offset: u64)
 pub struct Reader<F: AsyncRead + Unpin> {
     index: F,
     content: F,
+    // of the next commit, counted the same way as `MemoryIndex` does
+    pos: usize,
+    offset: u64,
 }
 
 impl<F: AsyncRead + Unpin> Reader<F> {
@@ -62,13 +65,18 @@ impl<F: AsyncRead + Unpin> Reader<F> {
     pub async fn init(mut index: F, mut content: F) -> Result<Reader<F>> {
         index_check_ident(&mut index).await?;
         content_check_ident(&mut content).await?;
-        Ok(Reader { index, content })
+        Ok(Reader { index, content, pos: 0, offset: 0 })
     }
 
     #[inline]
     pub async fn read_commit(&mut self) -> Option<Result<(Commit, Hash)>> {
         match index_read_index(&mut self.index).await {
-            Some(Ok(index)) => Some(content_read_commit(&mut self.content, index).await),
+            Some(Ok(index)) => {
+                let (pos, offset) = (self.pos, self.offset);
+                self.pos += 1;
+                self.offset += index.len;
+                Some(content_read_commit(&mut self.content, (pos, offset, index)).await)
+            },
             Some(Err(err)) => Some(Err(err)),
             None => None,
         }
@@ -114,7 +122,7 @@ impl<F: AsyncRead + AsyncSeek + Unpin> IndexedReader<F> {
         Ok(IndexedReader { index, content, dirty: false })
     }
 
-    async fn read_commit_inner(&mut self, dirt: bool, (offset, index): (u64, CommitIndexItem)) -> Result<(Commit, Hash)> {
+    async fn read_commit_inner(&mut self, dirt: bool, (pos, offset, index): (usize, u64, CommitIndexItem)) -> Result<(Commit, Hash)> {
         // dirt dirty -> dirty
         // T T -seek-> T
         // T F -seek-> T
@@ -128,7 +136,7 @@ impl<F: AsyncRead + AsyncSeek + Unpin> IndexedReader<F> {
         } else if self.dirty {
             self.dirty = false;
         }
-        content_read_commit(&mut self.content, index).await
+        content_read_commit(&mut self.content, (pos, offset, index)).await
     }
 
     #[inline]
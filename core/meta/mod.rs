@@ -2,7 +2,7 @@ pub use foundations::byterepr::{self, ByteRepr};
 use foundations::byterepr_struct;
 
 byterepr_struct! {
-    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub struct Timestamp {
         pub secs: i64,
         pub nanos: u32,
@@ -10,20 +10,20 @@ byterepr_struct! {
 }
 
 byterepr_struct! {
-    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub struct ObjectPtr {
         pub ot: u16,
         pub oid: u64,
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum TypePtr {
     Std(StdPtr),
     Hash([u8; 7]),
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StdPtr(u16);
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -33,7 +33,8 @@ pub struct CommitIndexItem {
     pub hash: [u8; 32],
 }
 
-pub use crate::std::codegen::meta::{Rev, RevPtr, CommitPtr, Commit, StateRevPtr};
+pub use crate::std::codegen::meta::{Rev, RevPtr, CommitPtr, Commit, StateRevPtr, Patch, PatchEdit, PatchStep, PatchOp};
 
 mod casting;
+pub use casting::check_raw_stdptr;
 mod timestamp;
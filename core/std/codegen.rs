@@ -6,8 +6,10 @@
     clippy::redundant_closure,
     clippy::redundant_field_names,
     clippy::map_identity,
+    clippy::needless_question_mark,
 )]
 pub mod types {
+    use std::collections::BTreeSet;
     use crate::{types::*, meta::{Timestamp, ObjectPtr, TypePtr}};
     #[derive(Clone, Debug, PartialEq, Eq)]
     pub enum DefType {
@@ -29,64 +31,39 @@ pub mod types {
                 },
                 Box::new(
                     match self {
-                        Self::Alias(val) => Value::Type(val),
-                        Self::CEnum(val) => {
-                            Value::List(
-                                Type::String,
-                                val.into_iter().map(|sv| Value::String(sv)).collect(),
-                            )
-                        }
-                        Self::Enum(val) => {
-                            Value::Map(
-                                (Type::String, Type::Type),
-                                val
-                                    .into_iter()
-                                    .map(|(sk, sv)| (Value::String(sk), Value::Type(sv)))
-                                    .collect(),
-                            )
-                        }
-                        Self::Struct(val) => {
-                            Value::Map(
-                                (Type::String, Type::Type),
-                                val
-                                    .into_iter()
-                                    .map(|(sk, sv)| (Value::String(sk), Value::Type(sv)))
-                                    .collect(),
-                            )
-                        }
+                        Self::Alias(val) => val.into_value(),
+                        Self::CEnum(val) => val.into_value(),
+                        Self::Enum(val) => into_map_value(val),
+                        Self::Struct(val) => into_map_value(val),
                     },
                 ),
             )
         }
-        fn deserialize(val: Value) -> Self {
-            let (variant, val) = val.into_enum();
-            match variant {
-                0 => Self::Alias(val.into_type()),
-                1 => {
-                    Self::CEnum(
-                        val.into_list().into_iter().map(|sv| sv.into_string()).collect(),
-                    )
-                }
-                2 => {
-                    Self::Enum(
-                        val
-                            .into_map()
-                            .into_iter()
-                            .map(|(sk, sv)| (sk.into_string(), sv.into_type()))
-                            .collect(),
-                    )
-                }
-                3 => {
-                    Self::Struct(
-                        val
-                            .into_map()
-                            .into_iter()
-                            .map(|(sk, sv)| (sk.into_string(), sv.into_type()))
-                            .collect(),
-                    )
-                }
-                _ => unreachable!(),
-            }
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
+            let (variant, val) = val.try_into_enum()?;
+            Ok(
+                match variant {
+                    0 => Self::Alias(FromValue::try_from_value(val)?),
+                    1 => Self::CEnum(FromValue::try_from_value(val)?),
+                    2 => Self::Enum(try_from_map_value(val)?),
+                    3 => Self::Struct(try_from_map_value(val)?),
+                    _ => return Err(DeserializeError::Variant(variant)),
+                },
+            )
+        }
+    }
+    impl IntoValue for DefType {
+        fn ty() -> Type {
+            Type::Enum(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for DefType {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
         }
     }
     #[derive(Clone, Debug, PartialEq, Eq)]
@@ -101,21 +78,33 @@ pub mod types {
             Value::Struct(
                 TypePtr::from_u16_unchecked(2),
                 vec![
-                    self.attr_type.serialize(), Value::String(self.attr_name),
-                    Value::Type(self.val_type),
+                    self.attr_type.into_value(), self.attr_name.into_value(), self
+                    .val_type.into_value(),
                 ],
             )
         }
-        fn deserialize(val: Value) -> Self {
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
             let [attr_type, attr_name, val_type]: [Value; 3usize] = val
-                .into_struct()
-                .try_into()
-                .unwrap();
-            Self {
-                attr_type: attr_type.deserialize_into(),
-                attr_name: attr_name.into_string(),
-                val_type: val_type.into_type(),
-            }
+                .try_into_struct_sized()?;
+            Ok(Self {
+                attr_type: FromValue::try_from_value(attr_type)?,
+                attr_name: FromValue::try_from_value(attr_name)?,
+                val_type: FromValue::try_from_value(val_type)?,
+            })
+        }
+    }
+    impl IntoValue for CommitAttr {
+        fn ty() -> Type {
+            Type::Struct(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for CommitAttr {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
         }
     }
     #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -140,23 +129,39 @@ pub mod types {
                 },
             )
         }
-        fn deserialize(val: Value) -> Self {
-            let variant = val.into_c_enum();
-            match variant {
-                0 => Self::Const,
-                1 => Self::Mut,
-                2 => Self::IterList,
-                3 => Self::IterSet,
-                4 => Self::Complex,
-                _ => unreachable!(),
-            }
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
+            let variant = val.try_into_c_enum()?;
+            Ok(
+                match variant {
+                    0 => Self::Const,
+                    1 => Self::Mut,
+                    2 => Self::IterList,
+                    3 => Self::IterSet,
+                    4 => Self::Complex,
+                    _ => return Err(DeserializeError::Variant(variant)),
+                },
+            )
+        }
+    }
+    impl IntoValue for CommitAttrType {
+        fn ty() -> Type {
+            Type::CEnum(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for CommitAttrType {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
         }
     }
     #[derive(Clone, Debug, PartialEq, Eq)]
     pub struct Trait {
         pub commit_attrs: Vec<super::types::CommitAttr>,
         pub state_attrs: Vec<super::types::StateAttr>,
-        pub extends: Vec<TypePtr>,
+        pub extends: BTreeSet<TypePtr>,
         pub validators: Vec<super::types::Validator>,
     }
     impl Schema for Trait {
@@ -165,44 +170,34 @@ pub mod types {
             Value::Struct(
                 TypePtr::from_u16_unchecked(5),
                 vec![
-                    Value::List(Type::Enum(TypePtr::from_u16_unchecked(2)), self
-                    .commit_attrs.into_iter().map(| sv | sv.serialize()).collect()),
-                    Value::List(Type::Enum(TypePtr::from_u16_unchecked(10)), self
-                    .state_attrs.into_iter().map(| sv | sv.serialize()).collect()),
-                    Value::List(Type::TypePtr, self.extends.into_iter().map(| sv |
-                    Value::TypePtr(sv)).collect()),
-                    Value::List(Type::Struct(TypePtr::from_u16_unchecked(11)), self
-                    .validators.into_iter().map(| sv | sv.serialize()).collect()),
+                    self.commit_attrs.into_value(), self.state_attrs.into_value(), self
+                    .extends.into_value(), self.validators.into_value(),
                 ],
             )
         }
-        fn deserialize(val: Value) -> Self {
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
             let [commit_attrs, state_attrs, extends, validators]: [Value; 4usize] = val
-                .into_struct()
-                .try_into()
-                .unwrap();
-            Self {
-                commit_attrs: commit_attrs
-                    .into_list()
-                    .into_iter()
-                    .map(|sv| sv.deserialize_into())
-                    .collect(),
-                state_attrs: state_attrs
-                    .into_list()
-                    .into_iter()
-                    .map(|sv| sv.deserialize_into())
-                    .collect(),
-                extends: extends
-                    .into_list()
-                    .into_iter()
-                    .map(|sv| sv.into_type_ptr())
-                    .collect(),
-                validators: validators
-                    .into_list()
-                    .into_iter()
-                    .map(|sv| sv.deserialize_into())
-                    .collect(),
-            }
+                .try_into_struct_sized()?;
+            Ok(Self {
+                commit_attrs: FromValue::try_from_value(commit_attrs)?,
+                state_attrs: FromValue::try_from_value(state_attrs)?,
+                extends: FromValue::try_from_value(extends)?,
+                validators: FromValue::try_from_value(validators)?,
+            })
+        }
+    }
+    impl IntoValue for Trait {
+        fn ty() -> Type {
+            Type::Struct(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for Trait {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
         }
     }
     #[derive(Clone, Debug, PartialEq, Eq)]
@@ -215,18 +210,29 @@ pub mod types {
         fn serialize(self) -> Value {
             Value::Struct(
                 TypePtr::from_u16_unchecked(10),
-                vec![Value::String(self.attr_name), Value::Type(self.val_type),],
+                vec![self.attr_name.into_value(), self.val_type.into_value(),],
             )
         }
-        fn deserialize(val: Value) -> Self {
-            let [attr_name, val_type]: [Value; 2usize] = val
-                .into_struct()
-                .try_into()
-                .unwrap();
-            Self {
-                attr_name: attr_name.into_string(),
-                val_type: val_type.into_type(),
-            }
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
+            let [attr_name, val_type]: [Value; 2usize] = val.try_into_struct_sized()?;
+            Ok(Self {
+                attr_name: FromValue::try_from_value(attr_name)?,
+                val_type: FromValue::try_from_value(val_type)?,
+            })
+        }
+    }
+    impl IntoValue for StateAttr {
+        fn ty() -> Type {
+            Type::Struct(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for StateAttr {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
         }
     }
     #[derive(Clone, Debug, PartialEq, Eq)]
@@ -241,36 +247,48 @@ pub mod types {
             Value::Struct(
                 TypePtr::from_u16_unchecked(11),
                 vec![
-                    Value::String(self.name), Value::String(self.attr_name),
-                    Value::Option(Type::TypePtr, Box::new(self.parent.map(| sv |
-                    Value::TypePtr(sv)))),
+                    self.name.into_value(), self.attr_name.into_value(), self.parent
+                    .into_value(),
                 ],
             )
         }
-        fn deserialize(val: Value) -> Self {
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
             let [name, attr_name, parent]: [Value; 3usize] = val
-                .into_struct()
-                .try_into()
-                .unwrap();
-            Self {
-                name: name.into_string(),
-                attr_name: attr_name.into_string(),
-                parent: parent.into_option().map(|sv| sv.into_type_ptr()),
-            }
+                .try_into_struct_sized()?;
+            Ok(Self {
+                name: FromValue::try_from_value(name)?,
+                attr_name: FromValue::try_from_value(attr_name)?,
+                parent: FromValue::try_from_value(parent)?,
+            })
+        }
+    }
+    impl IntoValue for Validator {
+        fn ty() -> Type {
+            Type::Struct(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for Validator {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
         }
     }
 }
 pub mod prim {
+    use std::collections::BTreeSet;
     use crate::{types::*, meta::{Timestamp, ObjectPtr, TypePtr}};
     #[derive(Clone, Debug, PartialEq, Eq)]
     pub struct UnixTs(pub u64);
     impl Schema for UnixTs {
         const PTR: TypePtr = TypePtr::from_u16_unchecked(1);
         fn serialize(self) -> Value {
-            Value::UInt(self.0)
+            self.0.into_value()
         }
-        fn deserialize(val: Value) -> Self {
-            Self(val.into_uint())
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            Ok(Self(FromValue::try_from_value(val)?))
         }
     }
     impl From<u64> for UnixTs {
@@ -283,15 +301,28 @@ pub mod prim {
             val.0
         }
     }
+    impl IntoValue for UnixTs {
+        fn ty() -> Type {
+            Type::UInt
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for UnixTs {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
+        }
+    }
     #[derive(Clone, Debug, PartialEq, Eq)]
     pub struct SimpleName(pub String);
     impl Schema for SimpleName {
         const PTR: TypePtr = TypePtr::from_u16_unchecked(4);
         fn serialize(self) -> Value {
-            Value::String(self.0)
+            self.0.into_value()
         }
-        fn deserialize(val: Value) -> Self {
-            Self(val.into_string())
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            Ok(Self(FromValue::try_from_value(val)?))
         }
     }
     impl From<String> for SimpleName {
@@ -304,8 +335,90 @@ pub mod prim {
             val.0
         }
     }
+    impl IntoValue for SimpleName {
+        fn ty() -> Type {
+            Type::String
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for SimpleName {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
+        }
+    }
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Money(pub Decimal);
+    impl Schema for Money {
+        const PTR: TypePtr = TypePtr::from_u16_unchecked(17);
+        fn serialize(self) -> Value {
+            self.0.into_value()
+        }
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            Ok(Self(FromValue::try_from_value(val)?))
+        }
+    }
+    impl From<Decimal> for Money {
+        fn from(val: Decimal) -> Self {
+            Self(val)
+        }
+    }
+    impl From<Money> for Decimal {
+        fn from(val: Money) -> Decimal {
+            val.0
+        }
+    }
+    impl IntoValue for Money {
+        fn ty() -> Type {
+            Type::Decimal
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for Money {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
+        }
+    }
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Counter(pub BigInt);
+    impl Schema for Counter {
+        const PTR: TypePtr = TypePtr::from_u16_unchecked(18);
+        fn serialize(self) -> Value {
+            self.0.into_value()
+        }
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            Ok(Self(FromValue::try_from_value(val)?))
+        }
+    }
+    impl From<BigInt> for Counter {
+        fn from(val: BigInt) -> Self {
+            Self(val)
+        }
+    }
+    impl From<Counter> for BigInt {
+        fn from(val: Counter) -> BigInt {
+            val.0
+        }
+    }
+    impl IntoValue for Counter {
+        fn ty() -> Type {
+            Type::BigInt
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for Counter {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
+        }
+    }
 }
 pub mod meta {
+    use std::collections::BTreeSet;
     use crate::{types::*, meta::{Timestamp, ObjectPtr, TypePtr}};
     #[derive(Clone, Debug, PartialEq, Eq)]
     pub enum Rev {
@@ -329,50 +442,45 @@ pub mod meta {
                 },
                 Box::new(
                     match self {
-                        Self::Const(val) => val,
-                        Self::Mut(val) => val,
-                        Self::IterListAdd(val) => {
-                            Value::List(
-                                Type::Unknown,
-                                val.into_iter().map(|sv| sv).collect(),
-                            )
-                        }
-                        Self::IterSetAdd(val) => {
-                            Value::List(
-                                Type::Unknown,
-                                val.into_iter().map(|sv| sv).collect(),
-                            )
-                        }
-                        Self::IterSetRemove(val) => {
-                            Value::List(
-                                Type::Unknown,
-                                val.into_iter().map(|sv| sv).collect(),
-                            )
-                        }
+                        Self::Const(val) => val.into_value(),
+                        Self::Mut(val) => val.into_value(),
+                        Self::IterListAdd(val) => val.into_value(),
+                        Self::IterSetAdd(val) => val.into_value(),
+                        Self::IterSetRemove(val) => val.into_value(),
                     },
                 ),
             )
         }
-        fn deserialize(val: Value) -> Self {
-            let (variant, val) = val.into_enum();
-            match variant {
-                0 => Self::Const(val),
-                1 => Self::Mut(val),
-                2 => {
-                    Self::IterListAdd(val.into_list().into_iter().map(|sv| sv).collect())
-                }
-                3 => Self::IterSetAdd(val.into_list().into_iter().map(|sv| sv).collect()),
-                4 => {
-                    Self::IterSetRemove(
-                        val.into_list().into_iter().map(|sv| sv).collect(),
-                    )
-                }
-                _ => unreachable!(),
-            }
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
+            let (variant, val) = val.try_into_enum()?;
+            Ok(
+                match variant {
+                    0 => Self::Const(FromValue::try_from_value(val)?),
+                    1 => Self::Mut(FromValue::try_from_value(val)?),
+                    2 => Self::IterListAdd(FromValue::try_from_value(val)?),
+                    3 => Self::IterSetAdd(FromValue::try_from_value(val)?),
+                    4 => Self::IterSetRemove(FromValue::try_from_value(val)?),
+                    _ => return Err(DeserializeError::Variant(variant)),
+                },
+            )
+        }
+    }
+    impl IntoValue for Rev {
+        fn ty() -> Type {
+            Type::Enum(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for Rev {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
         }
     }
     #[derive(Clone, Debug, PartialEq, Eq)]
-    #[derive(PartialOrd, Ord)]
+    #[derive(PartialOrd, Ord, Hash)]
     pub struct RevPtr {
         pub object: ObjectPtr,
         pub trait_type: TypePtr,
@@ -384,25 +492,37 @@ pub mod meta {
             Value::Struct(
                 TypePtr::from_u16_unchecked(7),
                 vec![
-                    Value::ObjectPtr(self.object), Value::TypePtr(self.trait_type),
-                    Value::UInt8(self.attr),
+                    self.object.into_value(), self.trait_type.into_value(), self.attr
+                    .into_value(),
                 ],
             )
         }
-        fn deserialize(val: Value) -> Self {
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
             let [object, trait_type, attr]: [Value; 3usize] = val
-                .into_struct()
-                .try_into()
-                .unwrap();
-            Self {
-                object: object.into_object_ptr(),
-                trait_type: trait_type.into_type_ptr(),
-                attr: attr.into_uint8(),
-            }
+                .try_into_struct_sized()?;
+            Ok(Self {
+                object: FromValue::try_from_value(object)?,
+                trait_type: FromValue::try_from_value(trait_type)?,
+                attr: FromValue::try_from_value(attr)?,
+            })
+        }
+    }
+    impl IntoValue for RevPtr {
+        fn ty() -> Type {
+            Type::Struct(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for RevPtr {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
         }
     }
     #[derive(Clone, Debug, PartialEq, Eq)]
-    #[derive(PartialOrd, Ord)]
+    #[derive(PartialOrd, Ord, Hash)]
     pub struct CommitPtr {
         pub ts: Timestamp,
         pub opr: ObjectPtr,
@@ -413,19 +533,30 @@ pub mod meta {
         fn serialize(self) -> Value {
             Value::Struct(
                 TypePtr::from_u16_unchecked(8),
-                vec![
-                    Value::Timestamp(self.ts), Value::ObjectPtr(self.opr),
-                    Value::UInt16(self.seq),
-                ],
+                vec![self.ts.into_value(), self.opr.into_value(), self.seq.into_value(),],
             )
         }
-        fn deserialize(val: Value) -> Self {
-            let [ts, opr, seq]: [Value; 3usize] = val.into_struct().try_into().unwrap();
-            Self {
-                ts: ts.into_timestamp(),
-                opr: opr.into_object_ptr(),
-                seq: seq.into_uint16(),
-            }
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
+            let [ts, opr, seq]: [Value; 3usize] = val.try_into_struct_sized()?;
+            Ok(Self {
+                ts: FromValue::try_from_value(ts)?,
+                opr: FromValue::try_from_value(opr)?,
+                seq: FromValue::try_from_value(seq)?,
+            })
+        }
+    }
+    impl IntoValue for CommitPtr {
+        fn ty() -> Type {
+            Type::Struct(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for CommitPtr {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
         }
     }
     #[derive(Clone, Debug, PartialEq, Eq)]
@@ -438,27 +569,33 @@ pub mod meta {
         fn serialize(self) -> Value {
             Value::Struct(
                 TypePtr::from_u16_unchecked(9),
-                vec![
-                    self.ptr.serialize(),
-                    Value::Map((Type::Struct(TypePtr::from_u16_unchecked(7)),
-                    Type::Struct(TypePtr::from_u16_unchecked(6))), self.revs.into_iter()
-                    .map(| (sk, sv) | (sk.serialize(), sv.serialize())).collect()),
-                ],
+                vec![self.ptr.into_value(), into_map_value(self.revs),],
             )
         }
-        fn deserialize(val: Value) -> Self {
-            let [ptr, revs]: [Value; 2usize] = val.into_struct().try_into().unwrap();
-            Self {
-                ptr: ptr.deserialize_into(),
-                revs: revs
-                    .into_map()
-                    .into_iter()
-                    .map(|(sk, sv)| (sk.deserialize_into(), sv.deserialize_into()))
-                    .collect(),
-            }
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
+            let [ptr, revs]: [Value; 2usize] = val.try_into_struct_sized()?;
+            Ok(Self {
+                ptr: FromValue::try_from_value(ptr)?,
+                revs: try_from_map_value(revs)?,
+            })
+        }
+    }
+    impl IntoValue for Commit {
+        fn ty() -> Type {
+            Type::Struct(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for Commit {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
         }
     }
     #[derive(Clone, Debug, PartialEq, Eq)]
+    #[derive(PartialOrd, Ord, Hash)]
     pub struct StateRevPtr {
         pub object: ObjectPtr,
         pub trait_type: TypePtr,
@@ -470,21 +607,207 @@ pub mod meta {
             Value::Struct(
                 TypePtr::from_u16_unchecked(12),
                 vec![
-                    Value::ObjectPtr(self.object), Value::TypePtr(self.trait_type),
-                    Value::UInt8(self.state_attr),
+                    self.object.into_value(), self.trait_type.into_value(), self
+                    .state_attr.into_value(),
                 ],
             )
         }
-        fn deserialize(val: Value) -> Self {
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
             let [object, trait_type, state_attr]: [Value; 3usize] = val
-                .into_struct()
-                .try_into()
-                .unwrap();
-            Self {
-                object: object.into_object_ptr(),
-                trait_type: trait_type.into_type_ptr(),
-                state_attr: state_attr.into_uint8(),
-            }
+                .try_into_struct_sized()?;
+            Ok(Self {
+                object: FromValue::try_from_value(object)?,
+                trait_type: FromValue::try_from_value(trait_type)?,
+                state_attr: FromValue::try_from_value(state_attr)?,
+            })
+        }
+    }
+    impl IntoValue for StateRevPtr {
+        fn ty() -> Type {
+            Type::Struct(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for StateRevPtr {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
+        }
+    }
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Patch {
+        pub edits: Vec<super::meta::PatchEdit>,
+    }
+    impl Schema for Patch {
+        const PTR: TypePtr = TypePtr::from_u16_unchecked(13);
+        fn serialize(self) -> Value {
+            Value::Struct(
+                TypePtr::from_u16_unchecked(13),
+                vec![self.edits.into_value(),],
+            )
+        }
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
+            let [edits]: [Value; 1usize] = val.try_into_struct_sized()?;
+            Ok(Self {
+                edits: FromValue::try_from_value(edits)?,
+            })
+        }
+    }
+    impl IntoValue for Patch {
+        fn ty() -> Type {
+            Type::Struct(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for Patch {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
+        }
+    }
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct PatchEdit {
+        pub path: Vec<super::meta::PatchStep>,
+        pub op: super::meta::PatchOp,
+    }
+    impl Schema for PatchEdit {
+        const PTR: TypePtr = TypePtr::from_u16_unchecked(14);
+        fn serialize(self) -> Value {
+            Value::Struct(
+                TypePtr::from_u16_unchecked(14),
+                vec![self.path.into_value(), self.op.into_value(),],
+            )
+        }
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
+            let [path, op]: [Value; 2usize] = val.try_into_struct_sized()?;
+            Ok(Self {
+                path: FromValue::try_from_value(path)?,
+                op: FromValue::try_from_value(op)?,
+            })
+        }
+    }
+    impl IntoValue for PatchEdit {
+        fn ty() -> Type {
+            Type::Struct(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for PatchEdit {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
+        }
+    }
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum PatchStep {
+        Field(u64),
+        Index(u64),
+        Key(Value),
+        Inner(()),
+    }
+    impl Schema for PatchStep {
+        const PTR: TypePtr = TypePtr::from_u16_unchecked(15);
+        fn serialize(self) -> Value {
+            Value::Enum(
+                TypePtr::from_u16_unchecked(15),
+                match &self {
+                    Self::Field(_) => 0,
+                    Self::Index(_) => 1,
+                    Self::Key(_) => 2,
+                    Self::Inner(_) => 3,
+                },
+                Box::new(
+                    match self {
+                        Self::Field(val) => val.into_value(),
+                        Self::Index(val) => val.into_value(),
+                        Self::Key(val) => val.into_value(),
+                        Self::Inner(val) => val.into_value(),
+                    },
+                ),
+            )
+        }
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
+            let (variant, val) = val.try_into_enum()?;
+            Ok(
+                match variant {
+                    0 => Self::Field(FromValue::try_from_value(val)?),
+                    1 => Self::Index(FromValue::try_from_value(val)?),
+                    2 => Self::Key(FromValue::try_from_value(val)?),
+                    3 => Self::Inner(FromValue::try_from_value(val)?),
+                    _ => return Err(DeserializeError::Variant(variant)),
+                },
+            )
+        }
+    }
+    impl IntoValue for PatchStep {
+        fn ty() -> Type {
+            Type::Enum(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for PatchStep {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
+        }
+    }
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum PatchOp {
+        Replace(Value),
+        Insert(Value),
+        Remove(()),
+    }
+    impl Schema for PatchOp {
+        const PTR: TypePtr = TypePtr::from_u16_unchecked(16);
+        fn serialize(self) -> Value {
+            Value::Enum(
+                TypePtr::from_u16_unchecked(16),
+                match &self {
+                    Self::Replace(_) => 0,
+                    Self::Insert(_) => 1,
+                    Self::Remove(_) => 2,
+                },
+                Box::new(
+                    match self {
+                        Self::Replace(val) => val.into_value(),
+                        Self::Insert(val) => val.into_value(),
+                        Self::Remove(val) => val.into_value(),
+                    },
+                ),
+            )
+        }
+        fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+            val.check_def(Self::PTR)?;
+            let (variant, val) = val.try_into_enum()?;
+            Ok(
+                match variant {
+                    0 => Self::Replace(FromValue::try_from_value(val)?),
+                    1 => Self::Insert(FromValue::try_from_value(val)?),
+                    2 => Self::Remove(FromValue::try_from_value(val)?),
+                    _ => return Err(DeserializeError::Variant(variant)),
+                },
+            )
+        }
+    }
+    impl IntoValue for PatchOp {
+        fn ty() -> Type {
+            Type::Enum(Self::PTR)
+        }
+        fn into_value(self) -> Value {
+            self.serialize()
+        }
+    }
+    impl FromValue for PatchOp {
+        fn try_from_value(val: Value) -> DeserializeResult<Self> {
+            Self::try_deserialize(val)
         }
     }
 }
@@ -26,6 +26,12 @@ macro_rules! list {
     };
 }
 
+macro_rules! set {
+    ($ty:expr) => {
+        Set(Box::new($ty))
+    };
+}
+
 macro_rules! map {
     ($tyk:expr, $tyv:expr) => {
         Map(Box::new($tyk), Box::new($tyv))
@@ -140,7 +146,7 @@ macro_rules! def_trait {
                 attr_name: $state_attr_name.to_owned(),
                 val_type: $state_val_type,
             })*)*],
-            extends: vec![$($($extend,)*)*],
+            extends: [$($($extend,)*)*].into_iter().collect(),
             validators: vec![$($(Validator {
                 name: $validator_name.to_owned(),
                 attr_name: $validator_attr_name.to_owned(),
@@ -172,7 +178,15 @@ macro_rules! def_std {
             })
         }
 
-        const fn const_path2ptr(path: StdPath) -> u16 {
+        pub fn parts2ptr(path: &str, name: &str) -> Option<u16> {
+            Some(match (path, name) {
+                $(($path, $name) => $stdptr,)*
+                $(($path2, $name2) => $stdptr2,)*
+                _ => return None,
+            })
+        }
+
+        pub const fn const_path2ptr(path: StdPath) -> u16 {
             $(if (
                 const_str_equal(path.path, $path) &
                 const_str_equal(path.name, $name)
@@ -223,9 +237,9 @@ def_std! {
         }
         0x0004 | std :"prim" :"simple-name" -> def_alias! (String)
         0x0005 | std :"types" :"trait" -> def_struct! {
-            "commit-attrs" -> list!(ref_enum!(:"types" :"commit-attr"))
-            "state-attrs"  -> list!(ref_enum!(:"types" :"state-attr"))
-            "extends"      -> list!(TypePtr)
+            "commit-attrs" -> list!(ref_struct!(:"types" :"commit-attr"))
+            "state-attrs"  -> list!(ref_struct!(:"types" :"state-attr"))
+            "extends"      -> set!(TypePtr)
             "validators"   -> list!(ref_struct!(:"types" :"validator"))
         }
         0x0006 | std :"meta" :"rev" -> def_enum! {
@@ -248,7 +262,7 @@ def_std! {
         }
         0x0009 | std :"meta" :"commit" -> def_struct! {
             "ptr" -> ref_struct!(:"meta" :"commit-ptr")
-            "revs" -> map!(ref_struct!(:"meta" :"rev-ptr"), ref_struct!(:"meta" :"rev"))
+            "revs" -> map!(ref_struct!(:"meta" :"rev-ptr"), ref_enum!(:"meta" :"rev"))
         }
         0x000A | std :"types" :"state-attr" -> def_struct! {
             "attr-name" -> String /* simple-name */
@@ -264,6 +278,26 @@ def_std! {
             "trait-type" -> TypePtr
             "state-attr" -> UInt8 /* trait-attr-id */
         }
+        0x000D | std :"meta" :"patch" -> def_struct! {
+            "edits" -> list!(ref_struct!(:"meta" :"patch-edit"))
+        }
+        0x000E | std :"meta" :"patch-edit" -> def_struct! {
+            "path" -> list!(ref_enum!(:"meta" :"patch-step"))
+            "op"   -> ref_enum!(:"meta" :"patch-op")
+        }
+        0x000F | std :"meta" :"patch-step" -> def_enum! {
+            "field" -> UInt /* struct field or tuple item */
+            "index" -> UInt
+            "key"   -> Unknown
+            "inner" -> Unit /* option, alias or enum payload */
+        }
+        0x0010 | std :"meta" :"patch-op" -> def_enum! {
+            "replace" -> Unknown
+            "insert"  -> Unknown /* before a list index, or under a new map key */
+            "remove"  -> Unit
+        }
+        0x0011 | std :"prim" :"money" -> def_alias! (Decimal)
+        0x0012 | std :"prim" :"counter" -> def_alias! (BigInt)
     }
     traits {
         0x8000 | std :"meta" :"object-meta" -> def_trait! {
@@ -300,9 +334,10 @@ mod test {
         assert_eq!(ptr2path(0x0001).unwrap().to_rust_self_path(), "super::prim::UnixTs");
         assert_eq!(ptr2path(0x0001).unwrap().to_rust_foreign_path(), "zeon::std::codegen::prim::UnixTs");
         assert_eq!(path2ptr(StdPath { path: "prim", name: "unix-ts" }).unwrap(), 0x0001);
+        assert_eq!(parts2ptr("prim", "unix-ts").unwrap(), 0x0001);
         assert_eq!(const_path2ptr(StdPath { path: "prim", name: "unix-ts" }), 0x0001);
         assert_eq!(std.types.get(&0x0001).unwrap().clone(), DefType::Alias(Type::UInt));
-        assert_eq!(format!("{:?}", std.traits.get(&0x8000).unwrap().clone()), r#"Trait { commit_attrs: [CommitAttr { attr_type: IterSet, attr_name: "traits", val_type: TypePtr }], state_attrs: [], extends: [], validators: [] }"#);
-        assert_eq!(format!("{:?}", std.traits.get(&0x8001).unwrap().clone()), r#"Trait { commit_attrs: [CommitAttr { attr_type: Mut, attr_name: "name", val_type: Alias(Std(StdPtr(4))) }], state_attrs: [], extends: [], validators: [] }"#);
+        assert_eq!(format!("{:?}", std.traits.get(&0x8000).unwrap().clone()), r#"Trait { commit_attrs: [CommitAttr { attr_type: IterSet, attr_name: "traits", val_type: TypePtr }], state_attrs: [], extends: {}, validators: [] }"#);
+        assert_eq!(format!("{:?}", std.traits.get(&0x8001).unwrap().clone()), r#"Trait { commit_attrs: [CommitAttr { attr_type: Mut, attr_name: "name", val_type: Alias(Std(StdPtr(4))) }], state_attrs: [], extends: {}, validators: [] }"#);
     }
 }
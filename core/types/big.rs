@@ -0,0 +1,223 @@
+// on the wire a bigint is the byte string of `magnitude << 1 | negative`, big-endian without
+// leading zero bytes, so zero is empty and small values of either sign take one byte. a decimal
+// has its scale in the header and the mantissa as a bigint after it.
+
+use std::cmp::Ordering;
+
+// sign and magnitude, the magnitude big-endian without leading zero bytes, so every integer has
+// exactly one representation and zero is never negative
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    neg: bool,
+    mag: Vec<u8>,
+}
+
+impl BigInt {
+    pub const ZERO: BigInt = BigInt { neg: false, mag: Vec::new() };
+
+    pub fn from_sign_magnitude(neg: bool, mag: &[u8]) -> BigInt {
+        let start = mag.iter().position(|b| *b != 0).unwrap_or(mag.len());
+        let mag = mag[start..].to_vec();
+        BigInt { neg: neg && !mag.is_empty(), mag }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.neg
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mag.is_empty()
+    }
+
+    pub fn magnitude(&self) -> &[u8] {
+        &self.mag
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        let u = self.to_u128_abs()?;
+        if self.neg {
+            // -(2^127) is the one magnitude that fits only negated
+            0i128.checked_sub_unsigned(u)
+        } else {
+            i128::try_from(u).ok()
+        }
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.neg {
+            return None;
+        }
+        self.to_u128_abs()
+    }
+
+    fn to_u128_abs(&self) -> Option<u128> {
+        if self.mag.len() > 16 {
+            return None;
+        }
+        let mut buf = [0; 16];
+        buf[16 - self.mag.len()..].copy_from_slice(&self.mag);
+        Some(u128::from_be_bytes(buf))
+    }
+
+    pub(super) fn to_wire(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.mag.len() + 1);
+        if self.mag.first().is_some_and(|b| b & 0x80 != 0) {
+            buf.push(1);
+        }
+        for (i, b) in self.mag.iter().enumerate() {
+            let next = self.mag.get(i + 1).map_or(u8::from(self.neg), |n| n >> 7);
+            buf.push(b << 1 | next);
+        }
+        buf
+    }
+
+    // also tells whether `buf` is the form `to_wire` gives
+    pub(super) fn from_wire(buf: &[u8]) -> (BigInt, bool) {
+        let canonical = buf.first() != Some(&0) && buf != [1];
+        let neg = buf.last().is_some_and(|b| b & 1 != 0);
+        let mut mag = Vec::with_capacity(buf.len());
+        let mut carry = 0;
+        for b in buf {
+            mag.push(carry << 7 | b >> 1);
+            carry = b & 1;
+        }
+        (BigInt::from_sign_magnitude(neg, &mag), canonical)
+    }
+
+    // decimal digits of the magnitude
+    pub(super) fn digits(&self) -> String {
+        const CHUNK: u64 = 1_000_000_000_000_000;
+        let mut mag = self.mag.clone();
+        let mut chunks = Vec::new();
+        while !mag.is_empty() {
+            let mut rem = 0u64;
+            for b in mag.iter_mut() {
+                let cur = rem << 8 | u64::from(*b);
+                *b = (cur / CHUNK) as u8;
+                rem = cur % CHUNK;
+            }
+            let start = mag.iter().position(|b| *b != 0).unwrap_or(mag.len());
+            mag.drain(..start);
+            chunks.push(rem);
+        }
+        let mut s = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            s.push_str(&format!("{:015}", chunk));
+        }
+        s
+    }
+
+    // `None` unless `digits` is a non-empty run of ascii digits
+    pub(super) fn from_digits(neg: bool, digits: &str) -> Option<BigInt> {
+        if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let mut mag = Vec::new();
+        for chunk in digits.as_bytes().chunks(15) {
+            let chunk = std::str::from_utf8(chunk).unwrap();
+            let mut carry: u64 = chunk.parse().unwrap();
+            let mul = 10u64.pow(chunk.len() as u32);
+            for b in mag.iter_mut().rev() {
+                let cur = u64::from(*b) * mul + carry;
+                *b = cur as u8;
+                carry = cur >> 8;
+            }
+            while carry != 0 {
+                mag.insert(0, carry as u8);
+                carry >>= 8;
+            }
+        }
+        Some(BigInt::from_sign_magnitude(neg, &mag))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        let abs = self.mag.len().cmp(&other.mag.len()).then_with(|| self.mag.cmp(&other.mag));
+        match (self.neg, other.neg) {
+            (false, false) => abs,
+            (true, true) => abs.reverse(),
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(v: i128) -> BigInt {
+        BigInt::from_sign_magnitude(v < 0, &v.unsigned_abs().to_be_bytes())
+    }
+}
+
+impl From<u128> for BigInt {
+    fn from(v: u128) -> BigInt {
+        BigInt::from_sign_magnitude(false, &v.to_be_bytes())
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(v: i64) -> BigInt {
+        i128::from(v).into()
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(v: u64) -> BigInt {
+        u128::from(v).into()
+    }
+}
+
+// `mantissa` * 10^-`scale`. the scale is kept as given, so 1.5 and 1.50 are different values, and
+// ordering is by mantissa and then scale rather than by value.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal {
+    pub mantissa: BigInt,
+    pub scale: i64,
+}
+
+#[cfg(feature = "num-bigint")]
+mod num_bigint_impls {
+    use num_bigint::Sign;
+    use super::BigInt;
+
+    impl From<num_bigint::BigInt> for BigInt {
+        fn from(v: num_bigint::BigInt) -> BigInt {
+            let (sign, mag) = v.to_bytes_be();
+            BigInt::from_sign_magnitude(sign == Sign::Minus, &mag)
+        }
+    }
+
+    impl From<BigInt> for num_bigint::BigInt {
+        fn from(v: BigInt) -> num_bigint::BigInt {
+            let sign = if v.is_negative() { Sign::Minus } else { Sign::Plus };
+            num_bigint::BigInt::from_bytes_be(sign, v.magnitude())
+        }
+    }
+}
+
+#[cfg(feature = "bigdecimal")]
+mod bigdecimal_impls {
+    use bigdecimal::{BigDecimal, num_bigint::{self, Sign}};
+    use super::{BigInt, Decimal};
+
+    impl From<BigDecimal> for Decimal {
+        fn from(v: BigDecimal) -> Decimal {
+            let (mantissa, scale) = v.into_bigint_and_scale();
+            let (sign, mag) = mantissa.to_bytes_be();
+            Decimal { mantissa: BigInt::from_sign_magnitude(sign == Sign::Minus, &mag), scale }
+        }
+    }
+
+    impl From<Decimal> for BigDecimal {
+        fn from(v: Decimal) -> BigDecimal {
+            let sign = if v.mantissa.is_negative() { Sign::Minus } else { Sign::Plus };
+            BigDecimal::new(num_bigint::BigInt::from_bytes_be(sign, v.mantissa.magnitude()), v.scale)
+        }
+    }
+}
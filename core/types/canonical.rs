@@ -0,0 +1,99 @@
+use std::mem;
+use super::*;
+
+// std:meta:rev variants whose list is a set
+const REV_SET_VARIANTS: [EnumVariantId; 2] = [3, 4];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DupKeys {
+    Reject,
+    // the entry that comes last wins, as with repeated inserts
+    KeepLast,
+}
+
+fn keyed<T>(items: Vec<T>, key: impl Fn(&T) -> &Value) -> EncodeResult<Vec<(Vec<u8>, T)>> {
+    let mut items = items.into_iter().map(|item| Ok((key(&item).try_encode()?, item))).collect::<EncodeResult<Vec<_>>>()?;
+    // stable, so equal keys stay in their original order
+    items.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(items)
+}
+
+fn set_items(mut items: Vec<Value>) -> EncodeResult<Vec<Value>> {
+    sort_set_items(&mut items)?;
+    Ok(items)
+}
+
+// sorts and dedups `s` as `try_new_set` does, leaving it untouched if an item does not encode
+pub(super) fn sort_set_items(s: &mut Vec<Value>) -> EncodeResult<()> {
+    let keys = s.iter().map(Value::try_encode).collect::<EncodeResult<Vec<_>>>()?;
+    let mut items = keys.into_iter().zip(mem::take(s)).collect::<Vec<_>>();
+    items.sort_by(|(a, _), (b, _)| a.cmp(b));
+    items.dedup_by(|(a, _), (b, _)| a == b);
+    *s = items.into_iter().map(|(_, v)| v).collect();
+    Ok(())
+}
+
+impl Value {
+    // a set of `items`, sorted by encoding with repeats dropped
+    pub fn try_new_set(t: Type, items: Vec<Value>) -> EncodeResult<Value> {
+        Ok(Value::Set(t, set_items(items)?))
+    }
+
+    pub fn new_set(t: Type, items: Vec<Value>) -> Value {
+        Value::try_new_set(t, items).unwrap()
+    }
+}
+
+impl Value {
+    // sorts map entries by encoded key and sorts and dedups the items of sets and set revs, all the
+    // way down, so equal maps and sets encode (and hash) the same
+    pub fn canonicalize(&mut self, dup: DupKeys) -> EncodeResult<()> {
+        match self {
+            Value::Option(_, v) => {
+                if let Some(v) = v.as_mut() {
+                    v.canonicalize(dup)?;
+                }
+            },
+            Value::List(_, s) | Value::Tuple(s) | Value::Struct(_, s) => {
+                for v in s {
+                    v.canonicalize(dup)?;
+                }
+            },
+            Value::Map(_, s) => {
+                for (k, v) in s.iter_mut() {
+                    k.canonicalize(dup)?;
+                    v.canonicalize(dup)?;
+                }
+                let mut out: Vec<(Vec<u8>, (Value, Value))> = Vec::with_capacity(s.len());
+                for (kb, kv) in keyed(mem::take(s), |(k, _)| k)? {
+                    match out.last_mut() {
+                        Some((last, entry)) if *last == kb => match dup {
+                            DupKeys::Reject => return Err(EncodeError::DupKey(kv.0)),
+                            DupKeys::KeepLast => *entry = kv,
+                        },
+                        _ => out.push((kb, kv)),
+                    }
+                }
+                *s = out.into_iter().map(|(_, kv)| kv).collect();
+            },
+            Value::Set(_, s) => {
+                for v in s.iter_mut() {
+                    v.canonicalize(dup)?;
+                }
+                // canonicalizing the items may have changed their order
+                *s = set_items(mem::take(s))?;
+            },
+            Value::Alias(_, v) => v.canonicalize(dup)?,
+            Value::Enum(ptr, ev, v) => {
+                v.canonicalize(dup)?;
+                if *ptr == crate::meta::Rev::PTR && REV_SET_VARIANTS.contains(ev) {
+                    if let Value::List(_, s) = v.as_mut() {
+                        *s = set_items(mem::take(s))?;
+                    }
+                }
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+}
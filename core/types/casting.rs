@@ -26,6 +26,13 @@ impl Type {
             Type::UInt8     => Tag::UInt8,
             Type::UInt16    => Tag::UInt16,
             Type::UInt32    => Tag::UInt32,
+            Type::Int8      => Tag::Int8,
+            Type::Int16     => Tag::Int16,
+            Type::Int32     => Tag::Int32,
+            Type::Float32   => Tag::Float32,
+            Type::BigInt    => Tag::BigInt,
+            Type::Decimal   => Tag::Decimal,
+            Type::Set(_)    => Tag::Set,
         }
     }
 }
@@ -55,6 +62,13 @@ impl Value {
             Value::UInt8(_)      => Tag::UInt8,
             Value::UInt16(_)     => Tag::UInt16,
             Value::UInt32(_)     => Tag::UInt32,
+            Value::Int8(_)       => Tag::Int8,
+            Value::Int16(_)      => Tag::Int16,
+            Value::Int32(_)      => Tag::Int32,
+            Value::Float32(_)    => Tag::Float32,
+            Value::BigInt(_)     => Tag::BigInt,
+            Value::Decimal(_)    => Tag::Decimal,
+            Value::Set(_, _)     => Tag::Set,
         }
     }
 
@@ -83,11 +97,45 @@ impl Value {
             Value::UInt8(_)      |
             Value::UInt16(_)     |
             Value::UInt32(_)     => HTag::L4,
+            Value::Int8(_)       => HTag::L4,
+            Value::Int16(_)      => HTag::L4,
+            Value::Int32(_)      => HTag::L4,
+            Value::Float32(_)    => HTag::L4,
+            Value::BigInt(_)     => HTag::BigInt,
+            Value::Decimal(_)    => HTag::Decimal,
+            Value::Set(_, _)     => HTag::Set,
         }
     }
 
+    // only tuples nest in their type, so those still being built are kept on a heap stack
     pub fn as_type(&self) -> Type {
-        match self {
+        let mut open: Vec<(std::slice::Iter<Value>, Vec<Type>)> = Vec::new();
+        let mut val = self;
+        loop {
+            let mut done = val.as_type_head(&mut open);
+            loop {
+                let Some((items, types)) = open.last_mut() else {
+                    // the outermost value is done once nothing is open
+                    return done.unwrap();
+                };
+                types.extend(done.take());
+                match items.next() {
+                    Some(item) => {
+                        val = item;
+                        break;
+                    },
+                    None => {
+                        let (_, types) = open.pop().unwrap();
+                        done = Some(Type::Tuple(types));
+                    },
+                }
+            }
+        }
+    }
+
+    // `None` for a tuple, which is left open for its items
+    fn as_type_head<'v>(&'v self, open: &mut Vec<(std::slice::Iter<'v, Value>, Vec<Type>)>) -> Option<Type> {
+        Some(match self {
             Value::Unit => Type::Unit,
             Value::Bool(_) => Type::Bool,
             Value::Int(_) => Type::Int,
@@ -98,7 +146,10 @@ impl Value {
             Value::Option(t, _) => Type::Option(Box::new(t.clone())),
             Value::List(t, _) => Type::List(Box::new(t.clone())),
             Value::Map((tk, tv), _) => Type::Map(Box::new(tk.clone()), Box::new(tv.clone())),
-            Value::Tuple(seq) => Type::Tuple(seq.iter().map(|v| v.as_type()).collect()),
+            Value::Tuple(seq) => {
+                open.push((seq.iter(), Vec::with_capacity(seq.len())));
+                return None;
+            },
             Value::Alias(ptr, _) => Type::Alias(*ptr),
             Value::CEnum(ptr, _) => Type::CEnum(*ptr),
             Value::Enum(ptr, _, _) => Type::Enum(*ptr),
@@ -110,7 +161,163 @@ impl Value {
             Value::UInt8(_) => Type::UInt8,
             Value::UInt16(_) => Type::UInt16,
             Value::UInt32(_) => Type::UInt32,
+            Value::Int8(_) => Type::Int8,
+            Value::Int16(_) => Type::Int16,
+            Value::Int32(_) => Type::Int32,
+            Value::Float32(_) => Type::Float32,
+            Value::BigInt(_) => Type::BigInt,
+            Value::Decimal(_) => Type::Decimal,
+            Value::Set(t, _) => Type::Set(Box::new(t.clone())),
+        })
+    }
+}
+
+impl<'a> ValueRef<'a> {
+    pub const fn as_tag(&self) -> Tag {
+        match self {
+            ValueRef::Unit          => Tag::Unit,
+            ValueRef::Bool(_)       => Tag::Bool,
+            ValueRef::Int(_)        => Tag::Int,
+            ValueRef::UInt(_)       => Tag::UInt,
+            ValueRef::Float(_)      => Tag::Float,
+            ValueRef::String(_)     => Tag::String,
+            ValueRef::Bytes(_)      => Tag::Bytes,
+            ValueRef::Option(_, _)  => Tag::Option,
+            ValueRef::List(_, _)    => Tag::List,
+            ValueRef::Map(_, _)     => Tag::Map,
+            ValueRef::Tuple(_)      => Tag::Tuple,
+            ValueRef::Alias(_, _)   => Tag::Alias,
+            ValueRef::CEnum(_, _)   => Tag::CEnum,
+            ValueRef::Enum(_, _, _) => Tag::Enum,
+            ValueRef::Struct(_, _)  => Tag::Struct,
+            ValueRef::Type(_)       => Tag::Type,
+            ValueRef::TypePtr(_)    => Tag::TypePtr,
+            ValueRef::ObjectPtr(_)  => Tag::ObjectPtr,
+            ValueRef::Timestamp(_)  => Tag::Timestamp,
+            ValueRef::UInt8(_)      => Tag::UInt8,
+            ValueRef::UInt16(_)     => Tag::UInt16,
+            ValueRef::UInt32(_)     => Tag::UInt32,
+            ValueRef::Int8(_)       => Tag::Int8,
+            ValueRef::Int16(_)      => Tag::Int16,
+            ValueRef::Int32(_)      => Tag::Int32,
+            ValueRef::Float32(_)    => Tag::Float32,
+            ValueRef::BigInt(_)     => Tag::BigInt,
+            ValueRef::Decimal(_)    => Tag::Decimal,
+            ValueRef::Set(_, _)     => Tag::Set,
+        }
+    }
+
+    // with the values still to convert on a heap stack, so a value of any depth takes constant
+    // native stack, and so does dropping the `ValueRef` along the way
+    pub fn into_owned(self) -> Value {
+        enum Task<'a> {
+            Val(ValueRef<'a>),
+            // the last `n` converted values are the inner ones of this
+            Some(Type),
+            Alias(TypePtr),
+            Enum(TypePtr, EnumVariantId),
+            List(Type, usize),
+            Map((Type, Type), usize),
+            Tuple(usize),
+            Struct(TypePtr, usize),
+            Set(Type, usize),
+        }
+
+        fn seq<'a>(tasks: &mut Vec<Task<'a>>, task: Task<'a>, s: Vec<ValueRef<'a>>) {
+            tasks.push(task);
+            tasks.extend(s.into_iter().rev().map(Task::Val));
+        }
+
+        let mut tasks = vec![Task::Val(self)];
+        let mut done: Vec<Value> = Vec::new();
+        while let Some(task) = tasks.pop() {
+            let v = match task {
+                Task::Val(v) => match v {
+                    ValueRef::Unit => Value::Unit,
+                    ValueRef::Bool(b) => Value::Bool(b),
+                    ValueRef::Int(i) => Value::Int(i),
+                    ValueRef::UInt(u) => Value::UInt(u),
+                    ValueRef::Float(f) => Value::Float(f),
+                    ValueRef::String(b) => Value::String(b.to_owned()),
+                    ValueRef::Bytes(b) => Value::Bytes(b.to_vec()),
+                    ValueRef::Option(t, opt) => match *opt {
+                        Some(v) => {
+                            tasks.push(Task::Some(t));
+                            tasks.push(Task::Val(v));
+                            continue;
+                        },
+                        None => Value::Option(t, Box::new(None)),
+                    },
+                    ValueRef::List(t, s) => {
+                        seq(&mut tasks, Task::List(t, s.len()), s);
+                        continue;
+                    },
+                    ValueRef::Map(t, s) => {
+                        tasks.push(Task::Map(t, s.len()));
+                        for (k, v) in s.into_iter().rev() {
+                            tasks.push(Task::Val(v));
+                            tasks.push(Task::Val(k));
+                        }
+                        continue;
+                    },
+                    ValueRef::Tuple(s) => {
+                        seq(&mut tasks, Task::Tuple(s.len()), s);
+                        continue;
+                    },
+                    ValueRef::Alias(ptr, v) => {
+                        tasks.push(Task::Alias(ptr));
+                        tasks.push(Task::Val(*v));
+                        continue;
+                    },
+                    ValueRef::CEnum(ptr, ev) => Value::CEnum(ptr, ev),
+                    ValueRef::Enum(ptr, ev, v) => {
+                        tasks.push(Task::Enum(ptr, ev));
+                        tasks.push(Task::Val(*v));
+                        continue;
+                    },
+                    ValueRef::Struct(ptr, s) => {
+                        seq(&mut tasks, Task::Struct(ptr, s.len()), s);
+                        continue;
+                    },
+                    ValueRef::Type(t) => Value::Type(t),
+                    ValueRef::TypePtr(ptr) => Value::TypePtr(ptr),
+                    ValueRef::ObjectPtr(ptr) => Value::ObjectPtr(ptr),
+                    ValueRef::Timestamp(ts) => Value::Timestamp(ts),
+                    ValueRef::UInt8(u) => Value::UInt8(u),
+                    ValueRef::UInt16(u) => Value::UInt16(u),
+                    ValueRef::UInt32(u) => Value::UInt32(u),
+                    ValueRef::Int8(i) => Value::Int8(i),
+                    ValueRef::Int16(i) => Value::Int16(i),
+                    ValueRef::Int32(i) => Value::Int32(i),
+                    ValueRef::Float32(f) => Value::Float32(f),
+                    ValueRef::BigInt(b) => Value::BigInt(b),
+                    ValueRef::Decimal(d) => Value::Decimal(d),
+                    ValueRef::Set(t, s) => {
+                        seq(&mut tasks, Task::Set(t, s.len()), s);
+                        continue;
+                    },
+                },
+                Task::Some(t) => Value::Option(t, Box::new(done.pop())),
+                Task::Alias(ptr) => Value::Alias(ptr, Box::new(done.pop().unwrap())),
+                Task::Enum(ptr, ev) => Value::Enum(ptr, ev, Box::new(done.pop().unwrap())),
+                Task::List(t, n) => Value::List(t, done.split_off(done.len() - n)),
+                Task::Map(t, n) => {
+                    let mut s = done.split_off(done.len() - n * 2).into_iter();
+                    Value::Map(t, (0..n).map(|_| (s.next().unwrap(), s.next().unwrap())).collect())
+                },
+                Task::Tuple(n) => Value::Tuple(done.split_off(done.len() - n)),
+                Task::Struct(ptr, n) => Value::Struct(ptr, done.split_off(done.len() - n)),
+                Task::Set(t, n) => Value::Set(t, done.split_off(done.len() - n)),
+            };
+            done.push(v);
         }
+        done.pop().unwrap()
+    }
+}
+
+impl<'a> From<ValueRef<'a>> for Value {
+    fn from(val: ValueRef<'a>) -> Value {
+        val.into_owned()
     }
 }
 
@@ -122,166 +329,368 @@ impl Value {
     pub fn deserialize_into<T: Schema>(self) -> T {
         T::deserialize(self)
     }
+
+    pub fn try_deserialize_into<T: Schema>(self) -> DeserializeResult<T> {
+        T::try_deserialize(self)
+    }
 }
 
 impl Value {
     pub fn from_float(v: f64) -> Value {
         Value::Float(v.to_bits())
     }
+
+    pub fn from_float32(v: f32) -> Value {
+        Value::Float32(v.to_bits())
+    }
+}
+
+fn sized<const N: usize>(s: Vec<Value>) -> DeserializeResult<[Value; N]> {
+    s.try_into().map_err(|s: Vec<Value>| DeserializeError::Len((N, s.len())))
 }
 
 impl Value {
-    pub fn into_unit(self) {
+    fn mismatch<T>(&self, expected: Tag) -> DeserializeResult<T> {
+        Err(DeserializeError::Mismatch((expected, self.as_tag())))
+    }
+
+    pub fn try_into_unit(self) -> DeserializeResult<()> {
         if let Value::Unit = self {
-            return;
+            return Ok(());
         }
-        unreachable!()
+        self.mismatch(Tag::Unit)
     }
 
-    pub fn into_bool(self) -> bool {
+    pub fn try_into_bool(self) -> DeserializeResult<bool> {
         if let Value::Bool(v) = self {
-            return v;
+            return Ok(v);
         }
-        unreachable!()
+        self.mismatch(Tag::Bool)
     }
 
-    pub fn into_int(self) -> i64 {
+    pub fn try_into_int(self) -> DeserializeResult<i64> {
         if let Value::Int(v) = self {
-            return v;
+            return Ok(v);
         }
-        unreachable!()
+        self.mismatch(Tag::Int)
     }
 
-    pub fn into_uint(self) -> u64 {
+    pub fn try_into_uint(self) -> DeserializeResult<u64> {
         if let Value::UInt(v) = self {
-            return v;
+            return Ok(v);
         }
-        unreachable!()
+        self.mismatch(Tag::UInt)
     }
 
-    pub fn into_float(self) -> f64 {
+    pub fn try_into_float(self) -> DeserializeResult<f64> {
         if let Value::Float(v) = self {
-            return f64::from_bits(v);
+            return Ok(f64::from_bits(v));
         }
-        unreachable!()
+        self.mismatch(Tag::Float)
     }
 
-    pub fn into_string(self) -> String {
-        if let Value::String(v) = self {
-            return v;
+    pub fn try_into_string(mut self) -> DeserializeResult<String> {
+        if let Value::String(v) = &mut self {
+            return Ok(std::mem::take(v));
         }
-        unreachable!()
+        self.mismatch(Tag::String)
     }
 
-    pub fn into_bytes(self) -> Vec<u8> {
-        if let Value::Bytes(v) = self {
-            return v;
+    pub fn try_into_bytes(mut self) -> DeserializeResult<Vec<u8>> {
+        if let Value::Bytes(v) = &mut self {
+            return Ok(std::mem::take(v));
         }
-        unreachable!()
+        self.mismatch(Tag::Bytes)
     }
 
-    pub fn into_option(self) -> Option<Value> {
-        if let Value::Option(_t, v) = self {
-            return *v;
+    pub fn try_into_option(mut self) -> DeserializeResult<Option<Value>> {
+        if let Value::Option(_t, v) = &mut self {
+            return Ok(v.take());
         }
-        unreachable!()
+        self.mismatch(Tag::Option)
     }
 
-    pub fn into_list(self) -> Vec<Value> {
-        if let Value::List(_t, s) = self {
-            return s;
+    pub fn try_into_list(mut self) -> DeserializeResult<Vec<Value>> {
+        if let Value::List(_t, s) = &mut self {
+            return Ok(std::mem::take(s));
         }
-        unreachable!()
+        self.mismatch(Tag::List)
     }
 
-    pub fn into_map(self) -> Vec<(Value, Value)> {
-        if let Value::Map(_t, s) = self {
-            return s;
+    pub fn try_into_map(mut self) -> DeserializeResult<Vec<(Value, Value)>> {
+        if let Value::Map(_t, s) = &mut self {
+            return Ok(std::mem::take(s));
         }
-        unreachable!()
+        self.mismatch(Tag::Map)
     }
 
-    pub fn into_tuple(self) -> Vec<Value> {
-        if let Value::Tuple(s) = self {
-            return s;
+    pub fn try_into_tuple(mut self) -> DeserializeResult<Vec<Value>> {
+        if let Value::Tuple(s) = &mut self {
+            return Ok(std::mem::take(s));
         }
-        unreachable!()
+        self.mismatch(Tag::Tuple)
     }
 
-    pub fn into_alias(self) -> Value {
-        if let Value::Alias(_ptr, v) = self {
-            return *v;
+    pub fn try_into_alias(mut self) -> DeserializeResult<Value> {
+        if let Value::Alias(_ptr, v) = &mut self {
+            return Ok(v.take());
         }
-        unreachable!()
+        self.mismatch(Tag::Alias)
     }
 
-    pub fn into_c_enum(self) -> EnumVariantId {
+    pub fn try_into_c_enum(self) -> DeserializeResult<EnumVariantId> {
         if let Value::CEnum(_ptr, ev) = self {
-            return ev;
+            return Ok(ev);
         }
-        unreachable!()
+        self.mismatch(Tag::CEnum)
     }
 
-    pub fn into_enum(self) -> (EnumVariantId, Value) {
-        if let Value::Enum(_ptr, ev, v) = self {
-            return (ev, *v);
+    pub fn try_into_enum(mut self) -> DeserializeResult<(EnumVariantId, Value)> {
+        if let Value::Enum(_ptr, ev, v) = &mut self {
+            return Ok((*ev, v.take()));
         }
-        unreachable!()
+        self.mismatch(Tag::Enum)
     }
 
-    pub fn into_struct(self) -> Vec<Value> {
-        if let Value::Struct(_ptr, s) = self {
-            return s;
+    // that a c-enum, enum or struct value is of the def `ptr`. other values are left to the
+    // `try_into_*` to reject.
+    pub fn check_def(&self, ptr: TypePtr) -> DeserializeResult<()> {
+        match self {
+            Value::CEnum(found, _) | Value::Enum(found, _, _) | Value::Struct(found, _) if *found != ptr => {
+                Err(DeserializeError::Ptr((ptr, *found)))
+            },
+            _ => Ok(()),
         }
-        unreachable!()
     }
 
-    pub fn into_type(self) -> Type {
-        if let Value::Type(v) = self {
-            return v;
+    pub fn try_into_struct(mut self) -> DeserializeResult<Vec<Value>> {
+        if let Value::Struct(_ptr, s) = &mut self {
+            return Ok(std::mem::take(s));
         }
-        unreachable!()
+        self.mismatch(Tag::Struct)
     }
 
-    pub fn into_type_ptr(self) -> TypePtr {
+    pub fn try_into_type(mut self) -> DeserializeResult<Type> {
+        if let Value::Type(v) = &mut self {
+            return Ok(v.take());
+        }
+        self.mismatch(Tag::Type)
+    }
+
+    pub fn try_into_type_ptr(self) -> DeserializeResult<TypePtr> {
         if let Value::TypePtr(v) = self {
-            return v;
+            return Ok(v);
         }
-        unreachable!()
+        self.mismatch(Tag::TypePtr)
     }
 
-    pub fn into_object_ptr(self) -> ObjectPtr {
+    pub fn try_into_object_ptr(self) -> DeserializeResult<ObjectPtr> {
         if let Value::ObjectPtr(v) = self {
-            return v;
+            return Ok(v);
         }
-        unreachable!()
+        self.mismatch(Tag::ObjectPtr)
     }
 
-    pub fn into_timestamp(self) -> Timestamp {
+    pub fn try_into_timestamp(self) -> DeserializeResult<Timestamp> {
         if let Value::Timestamp(v) = self {
-            return v;
+            return Ok(v);
         }
-        unreachable!()
+        self.mismatch(Tag::Timestamp)
     }
 
-    pub fn into_uint8(self) -> u8 {
+    pub fn try_into_uint8(self) -> DeserializeResult<u8> {
         if let Value::UInt8(v) = self {
-            return v;
+            return Ok(v);
         }
-        unreachable!()
+        self.mismatch(Tag::UInt8)
     }
 
-    pub fn into_uint16(self) -> u16 {
+    pub fn try_into_uint16(self) -> DeserializeResult<u16> {
         if let Value::UInt16(v) = self {
-            return v;
+            return Ok(v);
         }
-        unreachable!()
+        self.mismatch(Tag::UInt16)
     }
 
-    pub fn into_uint32(self) -> u32 {
+    pub fn try_into_uint32(self) -> DeserializeResult<u32> {
         if let Value::UInt32(v) = self {
-            return v;
+            return Ok(v);
+        }
+        self.mismatch(Tag::UInt32)
+    }
+
+    pub fn try_into_int8(self) -> DeserializeResult<i8> {
+        if let Value::Int8(v) = self {
+            return Ok(v);
+        }
+        self.mismatch(Tag::Int8)
+    }
+
+    pub fn try_into_int16(self) -> DeserializeResult<i16> {
+        if let Value::Int16(v) = self {
+            return Ok(v);
+        }
+        self.mismatch(Tag::Int16)
+    }
+
+    pub fn try_into_int32(self) -> DeserializeResult<i32> {
+        if let Value::Int32(v) = self {
+            return Ok(v);
+        }
+        self.mismatch(Tag::Int32)
+    }
+
+    pub fn try_into_float32(self) -> DeserializeResult<f32> {
+        if let Value::Float32(v) = self {
+            return Ok(f32::from_bits(v));
+        }
+        self.mismatch(Tag::Float32)
+    }
+
+    pub fn try_into_bigint(mut self) -> DeserializeResult<BigInt> {
+        if let Value::BigInt(v) = &mut self {
+            return Ok(std::mem::take(v));
+        }
+        self.mismatch(Tag::BigInt)
+    }
+
+    pub fn try_into_decimal(mut self) -> DeserializeResult<Decimal> {
+        if let Value::Decimal(v) = &mut self {
+            return Ok(std::mem::take(v));
         }
-        unreachable!()
+        self.mismatch(Tag::Decimal)
+    }
+
+    pub fn try_into_set(mut self) -> DeserializeResult<Vec<Value>> {
+        if let Value::Set(_t, s) = &mut self {
+            return Ok(std::mem::take(s));
+        }
+        self.mismatch(Tag::Set)
+    }
+
+    pub fn try_into_tuple_sized<const N: usize>(self) -> DeserializeResult<[Value; N]> {
+        sized(self.try_into_tuple()?)
+    }
+
+    pub fn try_into_struct_sized<const N: usize>(self) -> DeserializeResult<[Value; N]> {
+        sized(self.try_into_struct()?)
+    }
+}
+
+impl Value {
+    pub fn into_unit(self) {
+        self.try_into_unit().unwrap()
+    }
+
+    pub fn into_bool(self) -> bool {
+        self.try_into_bool().unwrap()
+    }
+
+    pub fn into_int(self) -> i64 {
+        self.try_into_int().unwrap()
+    }
+
+    pub fn into_uint(self) -> u64 {
+        self.try_into_uint().unwrap()
+    }
+
+    pub fn into_float(self) -> f64 {
+        self.try_into_float().unwrap()
+    }
+
+    pub fn into_string(self) -> String {
+        self.try_into_string().unwrap()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.try_into_bytes().unwrap()
+    }
+
+    pub fn into_option(self) -> Option<Value> {
+        self.try_into_option().unwrap()
+    }
+
+    pub fn into_list(self) -> Vec<Value> {
+        self.try_into_list().unwrap()
+    }
+
+    pub fn into_map(self) -> Vec<(Value, Value)> {
+        self.try_into_map().unwrap()
+    }
+
+    pub fn into_tuple(self) -> Vec<Value> {
+        self.try_into_tuple().unwrap()
+    }
+
+    pub fn into_alias(self) -> Value {
+        self.try_into_alias().unwrap()
+    }
+
+    pub fn into_c_enum(self) -> EnumVariantId {
+        self.try_into_c_enum().unwrap()
+    }
+
+    pub fn into_enum(self) -> (EnumVariantId, Value) {
+        self.try_into_enum().unwrap()
+    }
+
+    pub fn into_struct(self) -> Vec<Value> {
+        self.try_into_struct().unwrap()
+    }
+
+    pub fn into_type(self) -> Type {
+        self.try_into_type().unwrap()
+    }
+
+    pub fn into_type_ptr(self) -> TypePtr {
+        self.try_into_type_ptr().unwrap()
+    }
+
+    pub fn into_object_ptr(self) -> ObjectPtr {
+        self.try_into_object_ptr().unwrap()
+    }
+
+    pub fn into_timestamp(self) -> Timestamp {
+        self.try_into_timestamp().unwrap()
+    }
+
+    pub fn into_uint8(self) -> u8 {
+        self.try_into_uint8().unwrap()
+    }
+
+    pub fn into_uint16(self) -> u16 {
+        self.try_into_uint16().unwrap()
+    }
+
+    pub fn into_uint32(self) -> u32 {
+        self.try_into_uint32().unwrap()
+    }
+
+    pub fn into_int8(self) -> i8 {
+        self.try_into_int8().unwrap()
+    }
+
+    pub fn into_int16(self) -> i16 {
+        self.try_into_int16().unwrap()
+    }
+
+    pub fn into_int32(self) -> i32 {
+        self.try_into_int32().unwrap()
+    }
+
+    pub fn into_float32(self) -> f32 {
+        self.try_into_float32().unwrap()
+    }
+
+    pub fn into_bigint(self) -> BigInt {
+        self.try_into_bigint().unwrap()
+    }
+
+    pub fn into_decimal(self) -> Decimal {
+        self.try_into_decimal().unwrap()
+    }
+
+    pub fn into_set(self) -> Vec<Value> {
+        self.try_into_set().unwrap()
     }
 }
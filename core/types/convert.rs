@@ -0,0 +1,186 @@
+// conversions between rust values and `Value` for the types schema-derive maps definitions to.
+// `Vec<T>` is a list, so `Vec<u8>` is a list of uint8 rather than bytes, and maps, which are
+// `Vec<(K, V)>` in generated code, go through `into_map_value` and `try_from_map_value`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use crate::meta::{Timestamp, ObjectPtr, TypePtr};
+use super::*;
+
+pub trait IntoValue {
+    // type of every value `into_value` returns
+    fn ty() -> Type;
+    fn into_value(self) -> Value;
+}
+
+pub trait FromValue: Sized {
+    fn try_from_value(val: Value) -> DeserializeResult<Self>;
+
+    fn from_value(val: Value) -> Self {
+        Self::try_from_value(val).unwrap()
+    }
+}
+
+macro_rules! impl_prim {
+    ($($t:ty => $ty:ident, $into:expr, $from:ident;)*) => {$(
+        impl IntoValue for $t {
+            fn ty() -> Type {
+                Type::$ty
+            }
+
+            fn into_value(self) -> Value {
+                $into(self)
+            }
+        }
+
+        impl FromValue for $t {
+            fn try_from_value(val: Value) -> DeserializeResult<Self> {
+                val.$from()
+            }
+        }
+    )*};
+}
+
+impl_prim! {
+    () => Unit, |_| Value::Unit, try_into_unit;
+    bool => Bool, Value::Bool, try_into_bool;
+    i64 => Int, Value::Int, try_into_int;
+    u64 => UInt, Value::UInt, try_into_uint;
+    f64 => Float, Value::from_float, try_into_float;
+    String => String, Value::String, try_into_string;
+    Type => Type, Value::Type, try_into_type;
+    TypePtr => TypePtr, Value::TypePtr, try_into_type_ptr;
+    ObjectPtr => ObjectPtr, Value::ObjectPtr, try_into_object_ptr;
+    Timestamp => Timestamp, Value::Timestamp, try_into_timestamp;
+    u8 => UInt8, Value::UInt8, try_into_uint8;
+    u16 => UInt16, Value::UInt16, try_into_uint16;
+    u32 => UInt32, Value::UInt32, try_into_uint32;
+    i8 => Int8, Value::Int8, try_into_int8;
+    i16 => Int16, Value::Int16, try_into_int16;
+    i32 => Int32, Value::Int32, try_into_int32;
+    f32 => Float32, Value::from_float32, try_into_float32;
+    BigInt => BigInt, Value::BigInt, try_into_bigint;
+    Decimal => Decimal, Value::Decimal, try_into_decimal;
+}
+
+// a value of any type
+impl IntoValue for Value {
+    fn ty() -> Type {
+        Type::Unknown
+    }
+
+    fn into_value(self) -> Value {
+        self
+    }
+}
+
+impl FromValue for Value {
+    fn try_from_value(val: Value) -> DeserializeResult<Self> {
+        Ok(val)
+    }
+}
+
+impl<T: IntoValue> IntoValue for Option<T> {
+    fn ty() -> Type {
+        Type::Option(Box::new(T::ty()))
+    }
+
+    fn into_value(self) -> Value {
+        Value::Option(T::ty(), Box::new(self.map(T::into_value)))
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn try_from_value(val: Value) -> DeserializeResult<Self> {
+        val.try_into_option()?.map(T::try_from_value).transpose()
+    }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+    fn ty() -> Type {
+        Type::List(Box::new(T::ty()))
+    }
+
+    fn into_value(self) -> Value {
+        Value::List(T::ty(), self.into_iter().map(T::into_value).collect())
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn try_from_value(val: Value) -> DeserializeResult<Self> {
+        val.try_into_list()?.into_iter().map(T::try_from_value).collect()
+    }
+}
+
+pub fn into_map_value<K: IntoValue, V: IntoValue, I: IntoIterator<Item = (K, V)>>(entries: I) -> Value {
+    Value::Map((K::ty(), V::ty()), entries.into_iter().map(|(k, v)| (k.into_value(), v.into_value())).collect())
+}
+
+pub fn try_from_map_value<K: FromValue, V: FromValue, C: FromIterator<(K, V)>>(val: Value) -> DeserializeResult<C> {
+    val.try_into_map()?.into_iter().map(|(k, v)| Ok((K::try_from_value(k)?, V::try_from_value(v)?))).collect()
+}
+
+impl<K: IntoValue, V: IntoValue> IntoValue for BTreeMap<K, V> {
+    fn ty() -> Type {
+        Type::Map(Box::new(K::ty()), Box::new(V::ty()))
+    }
+
+    fn into_value(self) -> Value {
+        into_map_value(self)
+    }
+}
+
+// a repeated key keeps the last value
+impl<K: FromValue + Ord, V: FromValue> FromValue for BTreeMap<K, V> {
+    fn try_from_value(val: Value) -> DeserializeResult<Self> {
+        try_from_map_value(val)
+    }
+}
+
+impl<T: IntoValue> IntoValue for BTreeSet<T> {
+    fn ty() -> Type {
+        Type::Set(Box::new(T::ty()))
+    }
+
+    // sorted again by encoding, which need not agree with the order of `T`
+    fn into_value(self) -> Value {
+        Value::new_set(T::ty(), self.into_iter().map(T::into_value).collect())
+    }
+}
+
+impl<T: FromValue + Ord> FromValue for BTreeSet<T> {
+    fn try_from_value(val: Value) -> DeserializeResult<Self> {
+        val.try_into_set()?.into_iter().map(T::try_from_value).collect()
+    }
+}
+
+macro_rules! impl_tuple {
+    ($(($($t:ident $v:ident $i:tt)+))*) => {$(
+        impl<$($t: IntoValue),+> IntoValue for ($($t,)+) {
+            fn ty() -> Type {
+                Type::Tuple(vec![$($t::ty()),+])
+            }
+
+            fn into_value(self) -> Value {
+                Value::Tuple(vec![$(self.$i.into_value()),+])
+            }
+        }
+
+        impl<$($t: FromValue),+> FromValue for ($($t,)+) {
+            fn try_from_value(val: Value) -> DeserializeResult<Self> {
+                let [$($v),+] = val.try_into_tuple_sized()?;
+                Ok(($($t::try_from_value($v)?,)+))
+            }
+        }
+    )*};
+}
+
+impl_tuple! {
+    (A a 0)
+    (A a 0 B b 1)
+    (A a 0 B b 1 C c 2)
+    (A a 0 B b 1 C c 2 D d 3)
+    (A a 0 B b 1 C c 2 D d 3 E e 4)
+    (A a 0 B b 1 C c 2 D d 3 E e 4 F f 5)
+    (A a 0 B b 1 C c 2 D d 3 E e 4 F f 5 G g 6)
+    (A a 0 B b 1 C c 2 D d 3 E e 4 F f 5 G g 6 H h 7)
+}
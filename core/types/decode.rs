@@ -1,42 +1,322 @@
 use foundations::{num_compress::*, usize_casting::*, bytes_read::*};
-use super::*;
+use super::{*, packed::{PACKED, Packing, packs}};
 
 type Result<T> = DecodeResult<T>;
 
+impl DecodeError {
+    // the path is collected in reverse while unwinding and flipped once at the top
+    fn at(mut self, seg: PathSeg) -> DecodeError {
+        self.0.path.push(seg);
+        self
+    }
+
+    // only the innermost value sets it
+    fn starting(mut self, offset: usize) -> DecodeError {
+        if self.0.offset == usize::MAX {
+            self.0.offset = offset;
+        }
+        self
+    }
+
+    fn top(self, offset: usize) -> DecodeError {
+        let mut e = self.starting(offset);
+        e.0.path.reverse();
+        e
+    }
+
+    pub fn path_string(&self) -> String {
+        path_string(self.path())
+    }
+
+    // the error and, for the `buf` it came from, the rows of hex around the offset with the byte
+    // there marked
+    pub fn render(&self, buf: &[u8]) -> String {
+        const WIDTH: usize = 16;
+        const CONTEXT: usize = 2;
+        let mut s = self.to_string();
+        s.push('\n');
+        if self.offset() > buf.len() {
+            return s;
+        }
+        let row = self.offset() / WIDTH;
+        let last = (buf.len().saturating_sub(1) / WIDTH).clamp(row, row + CONTEXT);
+        for row in row.saturating_sub(CONTEXT)..=last {
+            let start = row * WIDTH;
+            s.push_str(&format!("{:08x} ", start));
+            for b in &buf[start.min(buf.len())..(start + WIDTH).min(buf.len())] {
+                s.push_str(&format!(" {:02x}", b));
+            }
+            s.push('\n');
+            if (start..start + WIDTH).contains(&self.offset()) {
+                s.push_str(&" ".repeat(10 + (self.offset() - start) * 3));
+                s.push_str("^^\n");
+            }
+        }
+        s
+    }
+}
+
 struct Reader<'a> {
     bytes: &'a [u8],
+    opts: DecodeOptions,
+    depth: usize,
+    alloc: usize,
+    // of the whole buffer that `bytes` is the rest of, for error offsets
+    len: usize,
+}
+
+enum Head<'a, V> {
+    Leaf(V),
+    Open(Frame<'a, V>),
+}
+
+// a value whose inner values are being read, with those read so far and how many there are. maps
+// and sets also keep the encoding of the last key or item to check the order with
+enum Frame<'a, V> {
+    Some(Type, Option<V>),
+    Alias(TypePtr, Option<V>),
+    Enum(TypePtr, EnumVariantId, Option<V>),
+    List(u8, Type, Vec<V>, usize),
+    // with the key of the entry whose value is next
+    Map((Type, Type), Vec<(V, V)>, usize, Option<V>, Option<&'a [u8]>),
+    Tuple(Vec<V>, usize),
+    Struct(TypePtr, Vec<V>, usize),
+    Set(Type, Vec<V>, usize, Option<&'a [u8]>),
+}
+
+impl<V> Frame<'_, V> {
+    fn is_full(&self) -> bool {
+        match self {
+            Frame::Some(_, v) | Frame::Alias(_, v) | Frame::Enum(_, _, v) => v.is_some(),
+            Frame::List(_, _, s, len) | Frame::Tuple(s, len) | Frame::Struct(_, s, len) | Frame::Set(_, s, len, _) => {
+                s.len() == *len
+            },
+            Frame::Map(_, s, len, ..) => s.len() == *len,
+        }
+    }
+
+    // where in this value the one being read is
+    fn seg(&self) -> Option<PathSeg> {
+        match self {
+            Frame::Some(..) => Some(PathSeg::Some),
+            Frame::Alias(..) => None,
+            Frame::Enum(_, ev, _) => Some(PathSeg::Variant(ev.to_string())),
+            Frame::List(_, _, s, _) | Frame::Set(_, s, ..) => Some(PathSeg::Index(s.len())),
+            Frame::Tuple(s, _) | Frame::Struct(_, s, _) => Some(PathSeg::Item(s.len())),
+            Frame::Map(_, s, _, None, _) => Some(PathSeg::MapKey(s.len())),
+            Frame::Map(_, s, _, Some(_), _) => Some(PathSeg::MapValue(s.len())),
+        }
+    }
+}
+
+// what the decoder builds, owned or borrowing from the buffer. both are read by the same loop
+trait Node<'a>: Sized {
+    fn leaf(reader: &mut Reader<'a>, htag: HTag, l4: u8) -> Result<Self>;
+    fn packed(t: Type, s: Vec<Value>) -> Self;
+    fn tag(&self) -> Tag;
+    fn option(t: Type, opt: Box<Option<Self>>) -> Self;
+    fn alias(ptr: TypePtr, v: Box<Self>) -> Self;
+    fn enumv(ptr: TypePtr, ev: EnumVariantId, v: Box<Self>) -> Self;
+    fn list(t: Type, s: Vec<Self>) -> Self;
+    fn map(t: (Type, Type), s: Vec<(Self, Self)>) -> Self;
+    fn tuple(s: Vec<Self>) -> Self;
+    fn structv(ptr: TypePtr, s: Vec<Self>) -> Self;
+    fn set(t: Type, s: Vec<Self>) -> Self;
+}
+
+impl<'a> Node<'a> for Value {
+    fn leaf(reader: &mut Reader<'a>, htag: HTag, l4: u8) -> Result<Value> {
+        reader.val_leaf(htag, l4)
+    }
+
+    fn packed(t: Type, s: Vec<Value>) -> Value {
+        Value::List(t, s)
+    }
+
+    fn tag(&self) -> Tag {
+        self.as_tag()
+    }
+
+    fn option(t: Type, opt: Box<Option<Value>>) -> Value {
+        Value::Option(t, opt)
+    }
+
+    fn alias(ptr: TypePtr, v: Box<Value>) -> Value {
+        Value::Alias(ptr, v)
+    }
+
+    fn enumv(ptr: TypePtr, ev: EnumVariantId, v: Box<Value>) -> Value {
+        Value::Enum(ptr, ev, v)
+    }
+
+    fn list(t: Type, s: Vec<Value>) -> Value {
+        Value::List(t, s)
+    }
+
+    fn map(t: (Type, Type), s: Vec<(Value, Value)>) -> Value {
+        Value::Map(t, s)
+    }
+
+    fn tuple(s: Vec<Value>) -> Value {
+        Value::Tuple(s)
+    }
+
+    fn structv(ptr: TypePtr, s: Vec<Value>) -> Value {
+        Value::Struct(ptr, s)
+    }
+
+    fn set(t: Type, s: Vec<Value>) -> Value {
+        Value::Set(t, s)
+    }
+}
+
+impl<'a> Node<'a> for ValueRef<'a> {
+    fn leaf(reader: &mut Reader<'a>, htag: HTag, l4: u8) -> Result<ValueRef<'a>> {
+        reader.val_ref_leaf(htag, l4)
+    }
+
+    fn packed(t: Type, s: Vec<Value>) -> ValueRef<'a> {
+        ValueRef::List(t, s.into_iter().map(packed_ref).collect())
+    }
+
+    fn tag(&self) -> Tag {
+        self.as_tag()
+    }
+
+    fn option(t: Type, opt: Box<Option<ValueRef<'a>>>) -> ValueRef<'a> {
+        ValueRef::Option(t, opt)
+    }
+
+    fn alias(ptr: TypePtr, v: Box<ValueRef<'a>>) -> ValueRef<'a> {
+        ValueRef::Alias(ptr, v)
+    }
+
+    fn enumv(ptr: TypePtr, ev: EnumVariantId, v: Box<ValueRef<'a>>) -> ValueRef<'a> {
+        ValueRef::Enum(ptr, ev, v)
+    }
+
+    fn list(t: Type, s: Vec<ValueRef<'a>>) -> ValueRef<'a> {
+        ValueRef::List(t, s)
+    }
+
+    fn map(t: (Type, Type), s: Vec<(ValueRef<'a>, ValueRef<'a>)>) -> ValueRef<'a> {
+        ValueRef::Map(t, s)
+    }
+
+    fn tuple(s: Vec<ValueRef<'a>>) -> ValueRef<'a> {
+        ValueRef::Tuple(s)
+    }
+
+    fn structv(ptr: TypePtr, s: Vec<ValueRef<'a>>) -> ValueRef<'a> {
+        ValueRef::Struct(ptr, s)
+    }
+
+    fn set(t: Type, s: Vec<ValueRef<'a>>) -> ValueRef<'a> {
+        ValueRef::Set(t, s)
+    }
 }
 
 impl<'a> Reader<'a> {
-    fn new(bytes: &'a [u8]) -> Reader<'a> {
-        Reader { bytes }
+    fn new(bytes: &'a [u8], opts: DecodeOptions) -> Reader<'a> {
+        Reader { bytes, opts, depth: 0, alloc: 0, len: bytes.len() }
+    }
+
+    fn pos(&self) -> usize {
+        self.len - self.bytes.len()
+    }
+
+    fn enter(&mut self) -> Result<()> {
+        if self.depth >= self.opts.max_depth {
+            return Err(DecodeErrorKind::DepthLimit(self.opts.max_depth).into());
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    fn alloc(&mut self, sz: usize) -> Result<()> {
+        self.alloc = self.alloc.saturating_add(sz);
+        if self.alloc > self.opts.max_alloc {
+            return Err(DecodeErrorKind::AllocLimit(self.opts.max_alloc).into());
+        }
+        Ok(())
+    }
+
+    // every item takes at least one byte, so a size larger than the rest is rejected before allocating
+    fn seq_len<T>(&mut self, len: usize) -> Result<()> {
+        self.seq_len_min::<T>(len, len)
+    }
+
+    // for items that take at least `min` bytes altogether
+    fn seq_len_min<T>(&mut self, len: usize, min: usize) -> Result<()> {
+        if len > self.opts.max_len {
+            return Err(DecodeErrorKind::LenLimit((len, self.opts.max_len)).into());
+        }
+        if min > self.bytes.len() {
+            return Err(DecodeErrorKind::TooShort((self.bytes.len(), min)).into());
+        }
+        self.alloc(len.saturating_mul(std::mem::size_of::<T>()))
+    }
+
+    fn boxed<T>(&mut self, v: T) -> Result<Box<T>> {
+        self.alloc(std::mem::size_of::<T>())?;
+        Ok(Box::new(v))
+    }
+
+    fn bytes_len(&mut self, len: usize) -> Result<()> {
+        if len > self.opts.max_bytes_len {
+            return Err(DecodeErrorKind::BytesLimit((len, self.opts.max_bytes_len)).into());
+        }
+        Ok(())
     }
 
     fn finish(self) -> Result<()> {
         if self.bytes.is_empty() {
             Ok(())
         } else {
-            Err(DecodeError::TooLong(self.bytes.len()))
+            Err(DecodeError::from(DecodeErrorKind::TooLong(self.bytes.len())).starting(self.pos()))
         }
     }
 
     #[inline]
     fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
-        self.bytes.read(buf).map_err(DecodeError::TooShort)
+        self.bytes.read(buf).map_err(|e| DecodeErrorKind::TooShort(e).into())
     }
 
     #[inline]
     fn bytes(&mut self, sz: usize) -> Result<Vec<u8>> {
-        self.bytes.read_to_vec(sz).map_err(DecodeError::TooShort)
+        self.bytes_len(sz)?;
+        self.alloc(sz)?;
+        self.bytes.read_to_vec(sz).map_err(|e| DecodeErrorKind::TooShort(e).into())
+    }
+
+    #[inline]
+    fn bytes_ref(&mut self, sz: usize) -> Result<&'a [u8]> {
+        self.bytes_len(sz)?;
+        if self.bytes.len() < sz {
+            return Err(DecodeErrorKind::TooShort((self.bytes.len(), sz)).into());
+        }
+        let (b, rest) = self.bytes.split_at(sz);
+        self.bytes = rest;
+        Ok(b)
+    }
+
+    fn str_ref(&mut self, sz: usize) -> Result<&'a str> {
+        let b = self.bytes_ref(sz)?;
+        // only allocates on the error path to keep the same error variant as owned decoding
+        std::str::from_utf8(b).map_err(|_| String::from_utf8(b.to_vec()).unwrap_err().into())
     }
 
     #[inline]
     fn bytes_sized<const N: usize>(&mut self) -> Result<[u8; N]> {
-        self.bytes.read_to_array().map_err(DecodeError::TooShort)
+        self.bytes.read_to_array().map_err(|e| DecodeErrorKind::TooShort(e).into())
     }
 
     fn u8(&mut self) -> Result<u8> {
-        self.bytes.read_byte().ok_or(DecodeError::TooShort((0, 1)))
+        self.bytes.read_byte().ok_or(DecodeErrorKind::TooShort((0, 1)).into())
     }
 
     fn u16(&mut self) -> Result<u16> {
@@ -55,6 +335,18 @@ impl<'a> Reader<'a> {
         self.bytes_sized().map(i64::from_be_bytes)
     }
 
+    fn i8(&mut self) -> Result<i8> {
+        self.bytes_sized().map(i8::from_be_bytes)
+    }
+
+    fn i16(&mut self) -> Result<i16> {
+        self.bytes_sized().map(i16::from_be_bytes)
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        self.bytes_sized().map(i32::from_be_bytes)
+    }
+
     fn typeptr(&mut self) -> Result<TypePtr> {
         let h8 = self.u8()?;
         Ok(match h8 {
@@ -70,8 +362,26 @@ impl<'a> Reader<'a> {
     }
 
     fn ty(&mut self) -> Result<Type> {
+        let mut built = TypeBuilder::default();
+        loop {
+            self.enter()?;
+            let depth = built.depth();
+            let (t, inner) = self.ty_head()?;
+            let done = built.push(t, inner);
+            // leaves the type just read unless it is open, and each one it completes
+            for _ in built.depth()..=depth {
+                self.leave();
+            }
+            if let Some(t) = done {
+                return Ok(t);
+            }
+        }
+    }
+
+    // a type with its inner types left unknown, and how many of them follow
+    fn ty_head(&mut self) -> Result<(Type, usize)> {
         let tag = self.u8()?.try_into()?;
-        Ok(match tag {
+        let t = match tag {
             Tag::Unknown => Type::Unknown,
             Tag::Unit => Type::Unit,
             Tag::Bool => Type::Bool,
@@ -87,29 +397,22 @@ impl<'a> Reader<'a> {
             Tag::UInt8 => Type::UInt8,
             Tag::UInt16 => Type::UInt16,
             Tag::UInt32 => Type::UInt32,
+            Tag::Int8 => Type::Int8,
+            Tag::Int16 => Type::Int16,
+            Tag::Int32 => Type::Int32,
+            Tag::Float32 => Type::Float32,
+            Tag::BigInt => Type::BigInt,
+            Tag::Decimal => Type::Decimal,
 
-            Tag::Option => {
-                let t = self.ty()?;
-                Type::Option(Box::new(t))
-            },
-            Tag::List => {
-                let t = self.ty()?;
-                Type::List(Box::new(t))
-            },
-            Tag::Map => {
-                let tk = self.ty()?;
-                let tv = self.ty()?;
-                Type::Map(Box::new(tk), Box::new(tv))
-            },
+            Tag::Option => return Ok((Type::Option(self.boxed(Type::Unknown)?), 1)),
+            Tag::List => return Ok((Type::List(self.boxed(Type::Unknown)?), 1)),
+            Tag::Set => return Ok((Type::Set(self.boxed(Type::Unknown)?), 1)),
+            Tag::Map => return Ok((Type::Map(self.boxed(Type::Unknown)?, self.boxed(Type::Unknown)?), 2)),
 
             Tag::Tuple => {
                 let len = self.u8()? as usize;
-                let mut s = Vec::with_capacity(len);
-                for _ in 0..len {
-                    let t = self.ty()?;
-                    s.push(t)
-                }
-                Type::Tuple(s)
+                self.seq_len::<Type>(len)?;
+                return Ok((Type::Tuple(Vec::with_capacity(len)), len));
             },
 
             Tag::Alias => {
@@ -128,21 +431,62 @@ impl<'a> Reader<'a> {
                 let ptr = self.typeptr()?;
                 Type::Struct(ptr)
             },
-        })
+        };
+        Ok((t, 0))
     }
 
     fn with_ltag(&mut self, l4: u8) -> Result<LTag> {
         Ok(l4.try_into()?)
     }
 
+    fn with_ext(&mut self) -> Result<ExtTag> {
+        self.u8()?.try_into()
+    }
+
+    fn bigint(&mut self, l4: u8) -> Result<BigInt> {
+        let len = self.with_szvar(l4)?;
+        self.bigint_bytes(l4, len)
+    }
+
+    // `l4` only goes into the error
+    fn bigint_bytes(&mut self, l4: u8, len: usize) -> Result<BigInt> {
+        let buf = self.bytes_ref(len)?;
+        self.alloc(len)?;
+        let (b, canonical) = BigInt::from_wire(buf);
+        if self.opts.canonical && !canonical {
+            return Err(DecodeErrorKind::NonCanonical((l4, usize_u64(len))).into());
+        }
+        Ok(b)
+    }
+
+    // l4 of the bigint header that has to follow a decimal's scale
+    fn mantissa(&mut self) -> Result<u8> {
+        let h8 = self.u8()?;
+        let (htag, l4) = to_h4l4(h8);
+        if htag != HTag::BigInt as u8 {
+            return Err(DecodeErrorKind::Mantissa(h8).into());
+        }
+        Ok(l4)
+    }
+
+    fn decimal(&mut self, l4: u8) -> Result<Decimal> {
+        let scale = self.with_ivar(l4)?;
+        let l4 = self.mantissa()?;
+        Ok(Decimal { mantissa: self.bigint(l4)?, scale })
+    }
+
     fn with_uvar(&mut self, l4: u8) -> Result<u64> {
-        Ok(match l4 {
-            EXT8 => self.u8()? as u64,
-            EXT16 => self.u16()? as u64,
-            EXT32 => self.u32()? as u64,
-            EXT64 => self.u64()?,
-            s => s as u64,
-        })
+        let (u, min) = match l4 {
+            EXT8 => (self.u8()? as u64, EXT8 as u64),
+            EXT16 => (self.u16()? as u64, u8::MAX as u64 + 1),
+            EXT32 => (self.u32()? as u64, u16::MAX as u64 + 1),
+            EXT64 => (self.u64()?, u32::MAX as u64 + 1),
+            s => (s as u64, 0),
+        };
+        if self.opts.canonical && u < min {
+            return Err(DecodeErrorKind::NonCanonical((l4, u)).into());
+        }
+        Ok(u)
     }
 
     fn with_ivar(&mut self, l4: u8) -> Result<i64> {
@@ -154,85 +498,334 @@ impl<'a> Reader<'a> {
     }
 
     fn with_fvar(&mut self, l4: u8) -> Result<u64> {
-        if l4 > 8 { return Err(DecodeError::FloatL4(l4)); }
+        if l4 > 8 { return Err(DecodeErrorKind::FloatL4(l4).into()); }
         let mut buf = [0; 8];
         self.read_exact(&mut buf[0..l4 as usize])?;
-        Ok(u64::from_be_bytes(buf))
+        let f = u64::from_be_bytes(buf);
+        if self.opts.canonical && float_find_zero(f) != l4 as usize {
+            return Err(DecodeErrorKind::NonCanonical((l4, f)).into());
+        }
+        Ok(f)
     }
 
-    fn val_seq(&mut self, size: usize) -> Result<Vec<Value>> {
-        let mut s = Vec::with_capacity(size);
-        for _ in 0..size {
-            let v = self.val()?;
-            s.push(v)
+    fn leb128(&mut self) -> Result<u64> {
+        let mut u = 0;
+        for (i, shift) in (0..64).step_by(7).enumerate() {
+            let b = self.u8()?;
+            let part = u64::from(b & 0x7F);
+            if shift == 63 && part > 1 {
+                return Err(DecodeErrorKind::Varint(i + 1).into());
+            }
+            u |= part << shift;
+            if b & 0x80 == 0 {
+                if self.opts.canonical && b == 0 && shift != 0 {
+                    return Err(DecodeErrorKind::Varint(i + 1).into());
+                }
+                return Ok(u);
+            }
         }
-        Ok(s)
+        Err(DecodeErrorKind::Varint(10).into())
+    }
+
+    // whether the list whose length was just read is packed
+    fn is_packed(&self) -> bool {
+        self.bytes.first().is_some_and(|b| b & PACKED != 0)
     }
 
-    fn val_seq_map(&mut self, size: usize) -> Result<Vec<(Value, Value)>> {
-        let mut s = Vec::with_capacity(size);
-        for _ in 0..size {
-            let k = self.val()?;
-            let v = self.val()?;
-            s.push((k, v))
+    fn packing(&mut self) -> Result<(Type, Packing)> {
+        let b = self.u8()?;
+        Tag::try_from(b & !PACKED).ok().and_then(Packing::of).ok_or(DecodeErrorKind::PackedTag(b).into())
+    }
+
+    #[inline(never)]
+    fn packed(&mut self, l4: u8, len: usize) -> Result<(Type, Vec<Value>)> {
+        let (t, packing) = self.packing()?;
+        self.seq_len_min::<Value>(len, packing.min_len(len))?;
+        let mut s = Vec::with_capacity(len);
+        if let Packing::Bits = packing {
+            let bits = self.bytes_ref(packing.min_len(len))?;
+            for i in 0..len {
+                s.push(Value::Bool(bits[i / 8] & 0x80 >> (i % 8) != 0));
+            }
+            // the bits after the last item are zero
+            let pad = bits.len() * 8 - len;
+            if self.opts.canonical && bits.last().is_some_and(|b| b & ((1 << pad) - 1) != 0) {
+                return Err(DecodeErrorKind::NonCanonical((l4, usize_u64(len))).into());
+            }
+            return Ok((t, s));
         }
-        Ok(s)
+        let tag = t.as_tag();
+        for _ in 0..len {
+            s.push(self.packed_item(tag, 0)?);
+        }
+        Ok((t, s))
+    }
+
+    // one item of a packed list, where a bool is bit `bit` of the next byte
+    fn packed_item(&mut self, tag: Tag, bit: u8) -> Result<Value> {
+        Ok(match tag {
+            Tag::Bool => Value::Bool(self.u8()? & 0x80 >> bit != 0),
+            Tag::UInt8 => Value::UInt8(self.u8()?),
+            Tag::Int8 => Value::Int8(self.i8()?),
+            Tag::UInt16 => Value::UInt16(self.u16()?),
+            Tag::Int16 => Value::Int16(self.i16()?),
+            Tag::UInt32 => Value::UInt32(self.u32()?),
+            Tag::Int32 => Value::Int32(self.i32()?),
+            Tag::Float32 => Value::Float32(self.u32()?),
+            Tag::Float => Value::Float(self.u64()?),
+            Tag::UInt => Value::UInt(self.leb128()?),
+            Tag::Int => Value::Int(zigzag_decode(self.leb128()?)),
+            _ => unreachable!(),
+        })
+    }
+
+    // a list that `Value::encode` would have packed
+    fn unpacked(&self, l4: u8, t: &Type, tags: impl Iterator<Item = Tag>) -> Result<()> {
+        if self.opts.canonical && packs(t, tags) {
+            return Err(DecodeErrorKind::NonCanonical((l4, 0)).into());
+        }
+        Ok(())
+    }
+
+    // room for the items of a container, checked against the limits before allocating
+    fn open_seq<V>(&mut self, len: usize) -> Result<Vec<V>> {
+        self.seq_len::<V>(len)?;
+        Ok(Vec::with_capacity(len))
+    }
+
+    // `start` is where the item just read began, `prev` the bytes of the item before it
+    fn ascends(&self, prev: &mut Option<&'a [u8]>, start: &'a [u8]) -> bool {
+        let cur = &start[..start.len() - self.bytes.len()];
+        let ascends = !prev.is_some_and(|prev| prev >= cur);
+        *prev = Some(cur);
+        ascends
+    }
+
+    fn key_order(&self, prev: &mut Option<&'a [u8]>, start: &'a [u8], i: usize) -> Result<()> {
+        if self.opts.sorted_keys && !self.ascends(prev, start) {
+            return Err(DecodeErrorKind::KeyOrder(i).into());
+        }
+        Ok(())
+    }
+
+    // set items have to ascend whatever the options, or the decoded set would not be one
+    fn set_order(&self, prev: &mut Option<&'a [u8]>, start: &'a [u8], i: usize) -> Result<()> {
+        if !self.ascends(prev, start) {
+            return Err(DecodeErrorKind::SetOrder(i).into());
+        }
+        Ok(())
     }
 
     fn val(&mut self) -> Result<Value> {
+        self.node()
+    }
+
+    fn val_ref(&mut self) -> Result<ValueRef<'a>> {
+        self.node()
+    }
+
+    // the values still being read are kept on a heap stack rather than in nested calls, so any
+    // depth `max_depth` lets through takes constant native stack
+    fn node<V: Node<'a>>(&mut self) -> Result<V> {
+        let mut open: Vec<(&'a [u8], Frame<'a, V>)> = Vec::new();
+        loop {
+            let start = self.bytes;
+            let head = self.enter().and_then(|()| self.val_head::<V>());
+            let mut done = match head.map_err(|e| self.locate(e, start, &open))? {
+                Head::Leaf(v) => {
+                    self.leave();
+                    Some((v, start))
+                },
+                Head::Open(frame) => {
+                    open.push((start, frame));
+                    None
+                },
+            };
+            while let Some((start, mut frame)) = open.pop() {
+                if let Some((v, vstart)) = done.take() {
+                    self.push(&mut frame, v, vstart).map_err(|e| self.locate(e, start, &open))?;
+                }
+                if !frame.is_full() {
+                    open.push((start, frame));
+                    break;
+                }
+                let v = self.close(frame).map_err(|e| self.locate(e, start, &open))?;
+                self.leave();
+                done = Some((v, start));
+            }
+            // only left over once the outermost value is done
+            if let Some((v, _)) = done {
+                return Ok(v);
+            }
+        }
+    }
+
+    // puts `e` at the value starting at `start`, inside the open ones
+    fn locate<V>(&self, e: DecodeError, start: &'a [u8], open: &[(&'a [u8], Frame<'a, V>)]) -> DecodeError {
+        let mut e = e.starting(self.len - start.len());
+        for (_, frame) in open.iter().rev() {
+            if let Some(seg) = frame.seg() {
+                e = e.at(seg);
+            }
+        }
+        e
+    }
+
+    // `v` started at `start` and ended where the reader is
+    fn push<V>(&self, frame: &mut Frame<'a, V>, v: V, start: &'a [u8]) -> Result<()> {
+        match frame {
+            Frame::Some(_, slot) | Frame::Alias(_, slot) | Frame::Enum(_, _, slot) => *slot = Some(v),
+            Frame::List(_, _, s, _) | Frame::Tuple(s, _) | Frame::Struct(_, s, _) => s.push(v),
+            Frame::Map(_, s, _, key, prev) => match key.take() {
+                Some(k) => s.push((k, v)),
+                None => {
+                    self.key_order(prev, start, s.len())?;
+                    *key = Some(v);
+                },
+            },
+            Frame::Set(_, s, _, prev) => {
+                self.set_order(prev, start, s.len())?;
+                s.push(v);
+            },
+        }
+        Ok(())
+    }
+
+    fn close<V: Node<'a>>(&mut self, frame: Frame<'a, V>) -> Result<V> {
+        Ok(match frame {
+            Frame::Some(t, opt) => V::option(t, self.boxed(opt)?),
+            Frame::Alias(ptr, Some(v)) => V::alias(ptr, self.boxed(v)?),
+            Frame::Enum(ptr, ev, Some(v)) => V::enumv(ptr, ev, self.boxed(v)?),
+            Frame::Alias(_, None) | Frame::Enum(_, _, None) => unreachable!(),
+            Frame::List(l4, t, s, _) => {
+                self.unpacked(l4, &t, s.iter().map(V::tag))?;
+                V::list(t, s)
+            },
+            Frame::Map(t, s, ..) => V::map(t, s),
+            Frame::Tuple(s, _) => V::tuple(s),
+            Frame::Struct(ptr, s, _) => V::structv(ptr, s),
+            Frame::Set(t, s, _, _) => V::set(t, s),
+        })
+    }
+
+    // reads up to the inner values, if any
+    fn val_head<V: Node<'a>>(&mut self) -> Result<Head<'a, V>> {
         let (htag, l4) = to_h4l4(self.u8()?);
-        Ok(match htag.try_into()? {
+        let htag = htag.try_into()?;
+        Ok(Head::Open(match htag {
             HTag::L4 => {
                 let ltag = self.with_ltag(l4)?;
                 match ltag {
-                    LTag::Unit => Value::Unit,
-                    LTag::True => Value::Bool(true),
-                    LTag::False => Value::Bool(false),
-                    opt @ (LTag::None | LTag::Some) => {
+                    LTag::None => {
                         let t = self.ty()?;
-                        let opt = match opt {
-                            LTag::None => None,
-                            LTag::Some => Some(self.val()?),
-                            _ => unreachable!(), // waiting for flow-sensitive typing implemented
-                        };
-                        Value::Option(t, Box::new(opt))
-                    },
-                    LTag::Alias => {
-                        let ptr = self.typeptr()?;
-                        let v = self.val()?;
-                        Value::Alias(ptr, Box::new(v))
+                        return Ok(Head::Leaf(V::option(t, self.boxed(None)?)));
                     },
-                    LTag::Type => {
+                    LTag::Some => {
                         let t = self.ty()?;
-                        Value::Type(t)
+                        Frame::Some(t, None)
                     },
-                    LTag::TypePtr => {
+                    LTag::Alias => {
                         let ptr = self.typeptr()?;
-                        Value::TypePtr(ptr)
-                    },
-                    LTag::ObjectPtr => {
-                        let ot = self.u16()?;
-                        let oid = self.u64()?;
-                        Value::ObjectPtr(ObjectPtr { ot, oid })
+                        Frame::Alias(ptr, None)
                     },
-                    LTag::Timestamp => {
-                        let secs = self.i64()?;
-                        let nanos = self.u32()?;
-                        Value::Timestamp(Timestamp { secs, nanos })
-                    },
-                    LTag::UInt8 => {
-                        let u = self.u8()?;
-                        Value::UInt8(u)
-                    },
-                    LTag::UInt16 => {
-                        let u = self.u16()?;
-                        Value::UInt16(u)
+                    _ => return Ok(Head::Leaf(V::leaf(self, htag, l4)?)),
+                }
+            },
+            HTag::List => {
+                let len = self.with_szvar(l4)?;
+                if self.is_packed() {
+                    let (t, s) = self.packed(l4, len)?;
+                    return Ok(Head::Leaf(V::packed(t, s)));
+                }
+                let t = self.ty()?;
+                Frame::List(l4, t, self.open_seq(len)?, len)
+            },
+            HTag::Map => {
+                let len = self.with_szvar(l4)?;
+                let tk = self.ty()?;
+                let tv = self.ty()?;
+                self.seq_len::<(V, V)>(len)?;
+                Frame::Map((tk, tv), Vec::with_capacity(len), len, None, None)
+            },
+            HTag::Tuple => {
+                let len = self.with_szvar(l4)?;
+                Frame::Tuple(self.open_seq(len)?, len)
+            },
+            HTag::Enum => {
+                let ev = self.with_uvar(l4)?;
+                let ptr = self.typeptr()?;
+                Frame::Enum(ptr, ev, None)
+            },
+            HTag::Struct => {
+                let len = self.with_szvar(l4)?;
+                let ptr = self.typeptr()?;
+                Frame::Struct(ptr, self.open_seq(len)?, len)
+            },
+            HTag::Set => {
+                let len = self.with_szvar(l4)?;
+                let t = self.ty()?;
+                Frame::Set(t, self.open_seq(len)?, len, None)
+            },
+            _ => return Ok(Head::Leaf(V::leaf(self, htag, l4)?)),
+        }))
+    }
+
+    // the kinds that do not nest, read straight into a leaf value
+    fn val_leaf(&mut self, htag: HTag, l4: u8) -> Result<Value> {
+        Ok(match htag {
+            HTag::L4 => match self.with_ltag(l4)? {
+                LTag::Unit => Value::Unit,
+                LTag::True => Value::Bool(true),
+                LTag::False => Value::Bool(false),
+                LTag::Type => {
+                    let t = self.ty()?;
+                    Value::Type(t)
+                },
+                LTag::TypePtr => {
+                    let ptr = self.typeptr()?;
+                    Value::TypePtr(ptr)
+                },
+                LTag::ObjectPtr => {
+                    let ot = self.u16()?;
+                    let oid = self.u64()?;
+                    Value::ObjectPtr(ObjectPtr { ot, oid })
+                },
+                LTag::Timestamp => {
+                    let secs = self.i64()?;
+                    let nanos = self.u32()?;
+                    Value::Timestamp(Timestamp { secs, nanos })
+                },
+                LTag::UInt8 => {
+                    let u = self.u8()?;
+                    Value::UInt8(u)
+                },
+                LTag::UInt16 => {
+                    let u = self.u16()?;
+                    Value::UInt16(u)
+                },
+                LTag::UInt32 => {
+                    let u = self.u32()?;
+                    Value::UInt32(u)
+                },
+                LTag::Int8 => {
+                    let i = self.i8()?;
+                    Value::Int8(i)
+                },
+                LTag::Int16 => {
+                    let i = self.i16()?;
+                    Value::Int16(i)
+                },
+                LTag::Ext => match self.with_ext()? {
+                    ExtTag::Int32 => {
+                        let i = self.i32()?;
+                        Value::Int32(i)
                     },
-                    LTag::UInt32 => {
-                        let u = self.u32()?;
-                        Value::UInt32(u)
+                    ExtTag::Float32 => {
+                        let f = self.u32()?;
+                        Value::Float32(f)
                     },
-                }
+                },
+                LTag::None | LTag::Some | LTag::Alias => unreachable!(),
             },
             HTag::Int => {
                 let i = self.with_ivar(l4)?;
@@ -256,50 +849,754 @@ impl<'a> Reader<'a> {
                 let b = self.bytes(len)?;
                 Value::Bytes(b)
             },
+            HTag::CEnum => {
+                let ev = self.with_uvar(l4)?;
+                let ptr = self.typeptr()?;
+                Value::CEnum(ptr, ev)
+            },
+            HTag::BigInt => Value::BigInt(self.bigint(l4)?),
+            HTag::Decimal => Value::Decimal(self.decimal(l4)?),
+            HTag::List | HTag::Map | HTag::Tuple | HTag::Enum | HTag::Struct | HTag::Set => unreachable!(),
+        })
+    }
+
+    fn skip(&mut self, sz: usize) -> Result<()> {
+        self.bytes_ref(sz).map(|_| ())
+    }
+
+    fn skip_typeptr(&mut self) -> Result<()> {
+        match self.u8()? {
+            0xFF => self.skip(7),
+            _ => self.skip(1),
+        }
+    }
+
+    fn skip_ty(&mut self) -> Result<()> {
+        // how many inner types are left of each type being skipped, innermost last
+        let mut left = Vec::new();
+        loop {
+            self.enter()?;
+            let inner = match self.u8()?.try_into()? {
+                Tag::Option | Tag::List | Tag::Set => 1,
+                Tag::Map => 2,
+                Tag::Tuple => self.u8()?,
+                Tag::Alias | Tag::CEnum | Tag::Enum | Tag::Struct => {
+                    self.skip_typeptr()?;
+                    0
+                },
+                _ => 0,
+            };
+            if inner > 0 {
+                left.push(inner);
+                continue;
+            }
+            self.leave();
+            // the type just skipped may complete the ones around it
+            loop {
+                let Some(n) = left.last_mut() else {
+                    return Ok(());
+                };
+                *n -= 1;
+                if *n > 0 {
+                    break;
+                }
+                left.pop();
+                self.leave();
+            }
+        }
+    }
+
+    fn skip_vals(&mut self, len: usize) -> Result<()> {
+        for _ in 0..len {
+            self.skip_val()?;
+        }
+        Ok(())
+    }
+
+    // walks over a value by its tags and length prefixes. strings are not checked for utf-8.
+    fn skip_val(&mut self) -> Result<()> {
+        self.enter()?;
+        // how many inner values are left of each value being skipped, innermost last
+        let mut left = vec![self.skip_head()?];
+        while let Some(n) = left.last_mut() {
+            if *n == 0 {
+                left.pop();
+                self.leave();
+            } else {
+                *n -= 1;
+                self.enter()?;
+                let inner = self.skip_head()?;
+                left.push(inner);
+            }
+        }
+        Ok(())
+    }
+
+    // walks up to the inner values and tells how many there are
+    fn skip_head(&mut self) -> Result<usize> {
+        let (htag, l4) = to_h4l4(self.u8()?);
+        match htag.try_into()? {
+            HTag::L4 => match self.with_ltag(l4)? {
+                LTag::Unit | LTag::True | LTag::False => {},
+                LTag::None => self.skip_ty()?,
+                LTag::Some => {
+                    self.skip_ty()?;
+                    return Ok(1);
+                },
+                LTag::Alias => {
+                    self.skip_typeptr()?;
+                    return Ok(1);
+                },
+                LTag::Type => self.skip_ty()?,
+                LTag::TypePtr => self.skip_typeptr()?,
+                LTag::ObjectPtr => self.skip(2 + 8)?,
+                LTag::Timestamp => self.skip(8 + 4)?,
+                LTag::UInt8 => self.skip(1)?,
+                LTag::UInt16 => self.skip(2)?,
+                LTag::UInt32 => self.skip(4)?,
+                LTag::Int8 => self.skip(1)?,
+                LTag::Int16 => self.skip(2)?,
+                LTag::Ext => match self.with_ext()? {
+                    ExtTag::Int32 | ExtTag::Float32 => self.skip(4)?,
+                },
+            },
+            HTag::Int | HTag::UInt => {
+                self.with_uvar(l4)?;
+            },
+            HTag::Float => {
+                self.with_fvar(l4)?;
+            },
+            HTag::String | HTag::Bytes => {
+                let len = self.with_szvar(l4)?;
+                self.skip(len)?;
+            },
             HTag::List => {
                 let len = self.with_szvar(l4)?;
-                let t = self.ty()?;
-                let s = self.val_seq(len)?;
-                Value::List(t, s)
+                if self.is_packed() {
+                    match self.packing()?.1 {
+                        Packing::Var => for _ in 0..len {
+                            self.leb128()?;
+                        },
+                        packing => self.skip(packing.min_len(len))?,
+                    }
+                } else {
+                    self.skip_ty()?;
+                    return Ok(len);
+                }
+            },
+            HTag::Set => {
+                let len = self.with_szvar(l4)?;
+                self.skip_ty()?;
+                return Ok(len);
             },
             HTag::Map => {
                 let len = self.with_szvar(l4)?;
-                let tk = self.ty()?;
-                let tv = self.ty()?;
-                let s = self.val_seq_map(len)?;
-                Value::Map((tk, tv), s)
+                self.skip_ty()?;
+                self.skip_ty()?;
+                return Ok(len.saturating_mul(2));
             },
             HTag::Tuple => {
                 let len = self.with_szvar(l4)?;
-                let s = self.val_seq(len)?;
-                Value::Tuple(s)
+                return Ok(len);
             },
             HTag::CEnum => {
-                let ev = self.with_uvar(l4)?;
-                let ptr = self.typeptr()?;
-                Value::CEnum(ptr, ev)
+                self.with_uvar(l4)?;
+                self.skip_typeptr()?;
             },
             HTag::Enum => {
-                let ev = self.with_uvar(l4)?;
-                let ptr = self.typeptr()?;
-                let v = self.val()?;
-                Value::Enum(ptr, ev, Box::new(v))
+                self.with_uvar(l4)?;
+                self.skip_typeptr()?;
+                return Ok(1);
             },
             HTag::Struct => {
                 let len = self.with_szvar(l4)?;
+                self.skip_typeptr()?;
+                return Ok(len);
+            },
+            HTag::BigInt => {
+                let len = self.with_szvar(l4)?;
+                self.skip(len)?;
+            },
+            HTag::Decimal => {
+                self.with_uvar(l4)?;
+                let l4 = self.mantissa()?;
+                let len = self.with_szvar(l4)?;
+                self.skip(len)?;
+            },
+        }
+        Ok(0)
+    }
+
+    // like `val_leaf`, borrowing strings and bytes
+    fn val_ref_leaf(&mut self, htag: HTag, l4: u8) -> Result<ValueRef<'a>> {
+        Ok(match htag {
+            HTag::L4 => match self.with_ltag(l4)? {
+                LTag::Unit => ValueRef::Unit,
+                LTag::True => ValueRef::Bool(true),
+                LTag::False => ValueRef::Bool(false),
+                LTag::Type => {
+                    let t = self.ty()?;
+                    ValueRef::Type(t)
+                },
+                LTag::TypePtr => {
+                    let ptr = self.typeptr()?;
+                    ValueRef::TypePtr(ptr)
+                },
+                LTag::ObjectPtr => {
+                    let ot = self.u16()?;
+                    let oid = self.u64()?;
+                    ValueRef::ObjectPtr(ObjectPtr { ot, oid })
+                },
+                LTag::Timestamp => {
+                    let secs = self.i64()?;
+                    let nanos = self.u32()?;
+                    ValueRef::Timestamp(Timestamp { secs, nanos })
+                },
+                LTag::UInt8 => {
+                    let u = self.u8()?;
+                    ValueRef::UInt8(u)
+                },
+                LTag::UInt16 => {
+                    let u = self.u16()?;
+                    ValueRef::UInt16(u)
+                },
+                LTag::UInt32 => {
+                    let u = self.u32()?;
+                    ValueRef::UInt32(u)
+                },
+                LTag::Int8 => {
+                    let i = self.i8()?;
+                    ValueRef::Int8(i)
+                },
+                LTag::Int16 => {
+                    let i = self.i16()?;
+                    ValueRef::Int16(i)
+                },
+                LTag::Ext => match self.with_ext()? {
+                    ExtTag::Int32 => {
+                        let i = self.i32()?;
+                        ValueRef::Int32(i)
+                    },
+                    ExtTag::Float32 => {
+                        let f = self.u32()?;
+                        ValueRef::Float32(f)
+                    },
+                },
+                LTag::None | LTag::Some | LTag::Alias => unreachable!(),
+            },
+            HTag::Int => {
+                let i = self.with_ivar(l4)?;
+                ValueRef::Int(i)
+            },
+            HTag::UInt => {
+                let u = self.with_uvar(l4)?;
+                ValueRef::UInt(u)
+            },
+            HTag::Float => {
+                let f = self.with_fvar(l4)?;
+                ValueRef::Float(f)
+            },
+            HTag::String => {
+                let len = self.with_szvar(l4)?;
+                let b = self.str_ref(len)?;
+                ValueRef::String(b)
+            },
+            HTag::Bytes => {
+                let len = self.with_szvar(l4)?;
+                let b = self.bytes_ref(len)?;
+                ValueRef::Bytes(b)
+            },
+            HTag::CEnum => {
+                let ev = self.with_uvar(l4)?;
                 let ptr = self.typeptr()?;
-                let s = self.val_seq(len)?;
-                Value::Struct(ptr, s)
+                ValueRef::CEnum(ptr, ev)
+            },
+            HTag::BigInt => ValueRef::BigInt(self.bigint(l4)?),
+            HTag::Decimal => ValueRef::Decimal(self.decimal(l4)?),
+            HTag::List | HTag::Map | HTag::Tuple | HTag::Enum | HTag::Struct | HTag::Set => unreachable!(),
+        })
+    }
+}
+
+fn schema(kind: TypeCheckErrorKind) -> DecodeError {
+    DecodeErrorKind::Schema(kind).into()
+}
+
+// the typed encoding, see `Value::try_encode_typed`
+impl<'a> Reader<'a> {
+    fn flag(&mut self) -> Result<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            b => Err(DecodeErrorKind::Flag(b).into()),
+        }
+    }
+
+    fn leb128_len(&mut self) -> Result<usize> {
+        Ok(u64_usize(self.leb128()?))
+    }
+
+    fn def<'r, R: Registry>(&self, ptr: TypePtr, reg: &'r R) -> Result<&'r DefType> {
+        reg.get(ptr).ok_or_else(|| schema(TypeCheckErrorKind::Unresolved(ptr)))
+    }
+
+    // the declared inner type, written only where `t` leaves it open
+    fn inner_typed(&mut self, t: &Type) -> Result<Type> {
+        match t {
+            Type::Unknown => self.ty(),
+            t => Ok(t.clone()),
+        }
+    }
+
+    fn seq_typed<R: Registry>(&mut self, len: usize, t: &Type, reg: &R) -> Result<Vec<Value>> {
+        // only the kinds that can be empty take no bytes
+        let min = match t {
+            Type::Unit | Type::Tuple(_) | Type::Alias(_) | Type::Struct(_) => 0,
+            _ => len,
+        };
+        self.seq_len_min::<Value>(len, min)?;
+        let mut s = Vec::with_capacity(len);
+        for i in 0..len {
+            let v = self.val_typed(t, reg).map_err(|e| e.at(PathSeg::Index(i)))?;
+            s.push(v)
+        }
+        Ok(s)
+    }
+
+    fn val_typed<R: Registry>(&mut self, t: &Type, reg: &R) -> Result<Value> {
+        let start = self.pos();
+        self.enter().map_err(|e| e.starting(start))?;
+        let val = self.val_typed_inner(t, reg).map_err(|e| e.starting(start))?;
+        self.leave();
+        Ok(val)
+    }
+
+    fn val_typed_inner<R: Registry>(&mut self, t: &Type, reg: &R) -> Result<Value> {
+        Ok(match t {
+            Type::Unknown => self.val()?,
+            Type::Unit => Value::Unit,
+            Type::Bool => Value::Bool(self.flag()?),
+            Type::Int => Value::Int(zigzag_decode(self.leb128()?)),
+            Type::UInt => Value::UInt(self.leb128()?),
+            Type::Float => Value::Float(self.u64()?),
+            Type::String => {
+                let len = self.leb128_len()?;
+                let b = self.bytes(len)?;
+                Value::String(String::from_utf8(b)?)
+            },
+            Type::Bytes => {
+                let len = self.leb128_len()?;
+                Value::Bytes(self.bytes(len)?)
+            },
+            Type::Option(t) => {
+                let t = self.inner_typed(t)?;
+                let opt = match self.flag()? {
+                    true => Some(self.val_typed(&t, reg).map_err(|e| e.at(PathSeg::Some))?),
+                    false => None,
+                };
+                Value::Option(t, self.boxed(opt)?)
+            },
+            Type::List(t) => {
+                let len = self.leb128_len()?;
+                let t = self.inner_typed(t)?;
+                let s = self.seq_typed(len, &t, reg)?;
+                Value::List(t, s)
+            },
+            Type::Set(t) => {
+                let len = self.leb128_len()?;
+                let t = self.inner_typed(t)?;
+                let s = self.seq_typed(len, &t, reg)?;
+                // the order is that of the self-describing encoding
+                let mut prev = None;
+                for (i, v) in s.iter().enumerate() {
+                    let buf = v.try_encode().map_err(|_| DecodeError::from(DecodeErrorKind::SetOrder(i)))?;
+                    if prev.is_some_and(|prev| prev >= buf) {
+                        return Err(DecodeErrorKind::SetOrder(i).into());
+                    }
+                    prev = Some(buf);
+                }
+                Value::Set(t, s)
+            },
+            Type::Map(tk, tv) => {
+                let len = self.leb128_len()?;
+                let tk = self.inner_typed(tk)?;
+                let tv = self.inner_typed(tv)?;
+                self.seq_len_min::<(Value, Value)>(len, 0)?;
+                let mut s = Vec::with_capacity(len);
+                for i in 0..len {
+                    let k = self.val_typed(&tk, reg).map_err(|e| e.at(PathSeg::MapKey(i)))?;
+                    let v = self.val_typed(&tv, reg).map_err(|e| e.at(PathSeg::MapValue(i)))?;
+                    s.push((k, v))
+                }
+                Value::Map((tk, tv), s)
+            },
+            Type::Tuple(ts) => {
+                let mut s = Vec::with_capacity(ts.len());
+                for (i, t) in ts.iter().enumerate() {
+                    let v = self.val_typed(t, reg).map_err(|e| e.at(PathSeg::Item(i)))?;
+                    s.push(v)
+                }
+                Value::Tuple(s)
+            },
+            // decodes to the aliased value itself, as the generated types serialize it
+            Type::Alias(ptr) => {
+                let (_, t) = resolve_alias(*ptr, reg).map_err(schema)?;
+                self.val_typed(t, reg)?
+            },
+            Type::CEnum(ptr) => {
+                let names = match self.def(*ptr, reg)? {
+                    DefType::CEnum(names) => names,
+                    _ => return Err(schema(TypeCheckErrorKind::DefKind((*ptr, Tag::CEnum)))),
+                };
+                let ev = self.leb128()?;
+                if ev >= usize_u64(names.len()) {
+                    return Err(schema(TypeCheckErrorKind::Variant((*ptr, ev))));
+                }
+                Value::CEnum(*ptr, ev)
+            },
+            Type::Enum(ptr) => {
+                let variants = match self.def(*ptr, reg)? {
+                    DefType::Enum(variants) => variants,
+                    _ => return Err(schema(TypeCheckErrorKind::DefKind((*ptr, Tag::Enum)))),
+                };
+                let ev = self.leb128()?;
+                let (name, t) = variants.get(u64_usize(ev))
+                    .ok_or_else(|| schema(TypeCheckErrorKind::Variant((*ptr, ev))))?;
+                let v = self.val_typed(t, reg).map_err(|e| e.at(PathSeg::Variant(name.clone())))?;
+                Value::Enum(*ptr, ev, self.boxed(v)?)
+            },
+            Type::Struct(ptr) => {
+                let fields = match self.def(*ptr, reg)? {
+                    DefType::Struct(fields) => fields,
+                    _ => return Err(schema(TypeCheckErrorKind::DefKind((*ptr, Tag::Struct)))),
+                };
+                let mut s = Vec::with_capacity(fields.len());
+                for (name, t) in fields {
+                    let v = self.val_typed(t, reg).map_err(|e| e.at(PathSeg::Field(name.clone())))?;
+                    s.push(v)
+                }
+                Value::Struct(*ptr, s)
+            },
+            Type::Type => Value::Type(self.ty()?),
+            Type::TypePtr => Value::TypePtr(self.typeptr()?),
+            Type::ObjectPtr => {
+                let ot = self.u16()?;
+                let oid = self.u64()?;
+                Value::ObjectPtr(ObjectPtr { ot, oid })
+            },
+            Type::Timestamp => {
+                let secs = self.i64()?;
+                let nanos = self.u32()?;
+                Value::Timestamp(Timestamp { secs, nanos })
+            },
+            Type::UInt8 => Value::UInt8(self.u8()?),
+            Type::UInt16 => Value::UInt16(self.u16()?),
+            Type::UInt32 => Value::UInt32(self.u32()?),
+            Type::Int8 => Value::Int8(self.i8()?),
+            Type::Int16 => Value::Int16(self.i16()?),
+            Type::Int32 => Value::Int32(self.i32()?),
+            Type::Float32 => Value::Float32(self.u32()?),
+            Type::BigInt => {
+                let len = self.leb128_len()?;
+                Value::BigInt(self.bigint_bytes(0, len)?)
+            },
+            Type::Decimal => {
+                let scale = zigzag_decode(self.leb128()?);
+                let len = self.leb128_len()?;
+                Value::Decimal(Decimal { mantissa: self.bigint_bytes(0, len)?, scale })
             },
         })
     }
 }
 
+// the kinds lists are packed with, which borrow nothing
+fn packed_ref<'a>(val: Value) -> ValueRef<'a> {
+    match val {
+        Value::Bool(b) => ValueRef::Bool(b),
+        Value::Int(i) => ValueRef::Int(i),
+        Value::UInt(u) => ValueRef::UInt(u),
+        Value::Float(f) => ValueRef::Float(f),
+        Value::UInt8(u) => ValueRef::UInt8(u),
+        Value::UInt16(u) => ValueRef::UInt16(u),
+        Value::UInt32(u) => ValueRef::UInt32(u),
+        Value::Int8(i) => ValueRef::Int8(i),
+        Value::Int16(i) => ValueRef::Int16(i),
+        Value::Int32(i) => ValueRef::Int32(i),
+        Value::Float32(f) => ValueRef::Float32(f),
+        _ => unreachable!(),
+    }
+}
+
 impl Value {
     pub fn decode(buf: &[u8]) -> Result<Value> {
-        let mut reader = Reader::new(buf);
-        let val = reader.val()?;
+        Value::decode_with(buf, DecodeOptions::DEFAULT)
+    }
+
+    pub fn decode_with(buf: &[u8], opts: DecodeOptions) -> Result<Value> {
+        let mut reader = Reader::new(buf, opts);
+        let val = reader.val().map_err(|e| e.top(0))?;
+        reader.finish()?;
+        Ok(val)
+    }
+
+    // decodes the value at the front of `buf`, returning it with the number of bytes it took
+    pub fn decode_prefix(buf: &[u8]) -> Result<(Value, usize)> {
+        Value::decode_prefix_with(buf, DecodeOptions::DEFAULT)
+    }
+
+    pub fn decode_prefix_with(buf: &[u8], opts: DecodeOptions) -> Result<(Value, usize)> {
+        let mut reader = Reader::new(buf, opts);
+        let val = reader.val().map_err(|e| e.top(0))?;
+        Ok((val, buf.len() - reader.bytes.len()))
+    }
+
+    // whether `buf` is exactly what `encode` produces for the value it holds, however deep or long
+    pub fn is_canonical(buf: &[u8]) -> bool {
+        Value::decode_with(buf, DecodeOptions { canonical: true, ..DecodeOptions::UNLIMITED }).is_ok()
+    }
+}
+
+impl Value {
+    // reads what `try_encode_typed` wrote for type `t`
+    pub fn decode_typed<R: Registry>(buf: &[u8], t: &Type, reg: &R) -> Result<Value> {
+        Value::decode_typed_with(buf, t, reg, DecodeOptions::DEFAULT)
+    }
+
+    // `sorted_keys` only applies to the maps written self-describing, under `Unknown`
+    pub fn decode_typed_with<R: Registry>(buf: &[u8], t: &Type, reg: &R, opts: DecodeOptions) -> Result<Value> {
+        let mut reader = Reader::new(buf, opts);
+        let val = reader.val_typed(t, reg).map_err(|e| e.top(0))?;
         reader.finish()?;
         Ok(val)
     }
 }
+
+impl<'a> ValueRef<'a> {
+    pub fn decode(buf: &'a [u8]) -> Result<ValueRef<'a>> {
+        ValueRef::decode_with(buf, DecodeOptions::DEFAULT)
+    }
+
+    pub fn decode_with(buf: &'a [u8], opts: DecodeOptions) -> Result<ValueRef<'a>> {
+        let mut reader = Reader::new(buf, opts);
+        let val = reader.val_ref().map_err(|e| e.top(0))?;
+        reader.finish()?;
+        Ok(val)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step<'k> {
+    // struct field or tuple item
+    Field(usize),
+    // list or set item, also of a packed list
+    Index(usize),
+    // compared by encoding, so it only finds keys stored in canonical form
+    Key(&'k Value),
+}
+
+// points at an encoded value. stepping into it skips over everything before the target without
+// decoding it, and only the value finally asked for is decoded.
+#[derive(Clone, Copy, Debug)]
+pub struct ValueCursor<'a> {
+    bytes: &'a [u8],
+    opts: DecodeOptions,
+    // of the buffer the first cursor was made over, which error offsets count from
+    len: usize,
+    // set at an item of a packed list, which has no header: the item tag, and for a bool the bit of
+    // the byte pointed at
+    packed: Option<(Tag, u8)>,
+}
+
+impl<'a> ValueCursor<'a> {
+    pub fn new(buf: &'a [u8]) -> ValueCursor<'a> {
+        ValueCursor::with_options(buf, DecodeOptions::DEFAULT)
+    }
+
+    pub fn with_options(buf: &'a [u8], opts: DecodeOptions) -> ValueCursor<'a> {
+        ValueCursor { bytes: buf, opts, len: buf.len(), packed: None }
+    }
+
+    fn reader(&self) -> Reader<'a> {
+        Reader { len: self.len, ..Reader::new(self.bytes, self.opts) }
+    }
+
+    fn at(&self, reader: Reader<'a>) -> ValueCursor<'a> {
+        ValueCursor { bytes: reader.bytes, opts: self.opts, len: self.len, packed: None }
+    }
+
+    // a packed item has no bytes of its own to return or to step into
+    fn header(&self) -> Result<()> {
+        match self.packed {
+            Some((tag, _)) => Err(DecodeErrorKind::PackedItem(tag as u8 | PACKED).into()),
+            None => Ok(()),
+        }
+    }
+
+    // errors skipping or stepping runs into are put at the value pointed at
+    fn located<T>(&self, res: Result<T>) -> Result<T> {
+        res.map_err(|e| e.top(self.len - self.bytes.len()))
+    }
+
+    // exactly the bytes of the value pointed at
+    pub fn raw(&self) -> Result<&'a [u8]> {
+        self.located(self.header())?;
+        let mut reader = self.reader();
+        self.located(reader.skip_val())?;
+        Ok(&self.bytes[..self.bytes.len() - reader.bytes.len()])
+    }
+
+    // the value right after this one, as in a concatenated stream
+    pub fn next(&self) -> Result<ValueCursor<'a>> {
+        self.located(self.header())?;
+        let mut reader = self.reader();
+        self.located(reader.skip_val())?;
+        Ok(self.at(reader))
+    }
+
+    pub fn decode(&self) -> Result<Value> {
+        match self.packed {
+            Some((tag, bit)) => self.located(self.reader().packed_item(tag, bit)),
+            None => self.located(self.reader().val()),
+        }
+    }
+
+    pub fn decode_ref(&self) -> Result<ValueRef<'a>> {
+        match self.packed {
+            Some((tag, bit)) => self.located(self.reader().packed_item(tag, bit)).map(packed_ref),
+            None => self.located(self.reader().val_ref()),
+        }
+    }
+
+    // `None` if the item does not exist
+    pub fn field(&self, n: usize) -> Result<Option<ValueCursor<'a>>> {
+        self.located(self.field_inner(n))
+    }
+
+    pub fn index(&self, i: usize) -> Result<Option<ValueCursor<'a>>> {
+        self.located(self.index_inner(i))
+    }
+
+    pub fn key(&self, key: &Value) -> Result<Option<ValueCursor<'a>>> {
+        self.located(self.key_inner(key))
+    }
+
+    fn field_inner(&self, n: usize) -> Result<Option<ValueCursor<'a>>> {
+        self.header()?;
+        let mut reader = self.reader();
+        let h8 = reader.u8()?;
+        let (htag, l4) = to_h4l4(h8);
+        let len = match htag.try_into()? {
+            HTag::Tuple => reader.with_szvar(l4)?,
+            HTag::Struct => {
+                let len = reader.with_szvar(l4)?;
+                reader.skip_typeptr()?;
+                len
+            },
+            _ => return Err(DecodeErrorKind::Step(h8).into()),
+        };
+        if n >= len {
+            return Ok(None);
+        }
+        reader.skip_vals(n)?;
+        Ok(Some(self.at(reader)))
+    }
+
+    fn index_inner(&self, i: usize) -> Result<Option<ValueCursor<'a>>> {
+        self.header()?;
+        let mut reader = self.reader();
+        let h8 = reader.u8()?;
+        let (htag, l4) = to_h4l4(h8);
+        if !matches!(htag.try_into()?, HTag::List | HTag::Set) {
+            return Err(DecodeErrorKind::Step(h8).into());
+        }
+        let len = reader.with_szvar(l4)?;
+        // the items of a packed list have no header, the cursor keeps their tag instead
+        if reader.is_packed() {
+            let (t, packing) = reader.packing()?;
+            if i >= len {
+                return Ok(None);
+            }
+            let bit = match packing {
+                Packing::Bits => {
+                    reader.skip(i / 8)?;
+                    (i % 8) as u8
+                },
+                Packing::Fixed(_) => {
+                    reader.skip(packing.min_len(i))?;
+                    0
+                },
+                Packing::Var => {
+                    for _ in 0..i {
+                        reader.leb128()?;
+                    }
+                    0
+                },
+            };
+            return Ok(Some(ValueCursor { packed: Some((t.as_tag(), bit)), ..self.at(reader) }));
+        }
+        if i >= len {
+            return Ok(None);
+        }
+        reader.skip_ty()?;
+        reader.skip_vals(i)?;
+        Ok(Some(self.at(reader)))
+    }
+
+    fn key_inner(&self, key: &Value) -> Result<Option<ValueCursor<'a>>> {
+        self.header()?;
+        let mut reader = self.reader();
+        let h8 = reader.u8()?;
+        let (htag, l4) = to_h4l4(h8);
+        if !matches!(htag.try_into()?, HTag::Map) {
+            return Err(DecodeErrorKind::Step(h8).into());
+        }
+        let len = reader.with_szvar(l4)?;
+        reader.skip_ty()?;
+        reader.skip_ty()?;
+        // no encoded key can match one that does not encode
+        let key = match key.try_encode() {
+            Ok(key) => key,
+            Err(_) => return Ok(None),
+        };
+        for _ in 0..len {
+            let k = reader.bytes;
+            reader.skip_val()?;
+            if k[..k.len() - reader.bytes.len()] == key[..] {
+                return Ok(Some(self.at(reader)));
+            }
+            reader.skip_val()?;
+        }
+        Ok(None)
+    }
+
+    pub fn step(&self, step: Step) -> Result<Option<ValueCursor<'a>>> {
+        match step {
+            Step::Field(n) => self.field(n),
+            Step::Index(i) => self.index(i),
+            Step::Key(k) => self.key(k),
+        }
+    }
+
+    pub fn path(&self, path: &[Step]) -> Result<Option<ValueCursor<'a>>> {
+        let mut cur = *self;
+        for step in path {
+            cur = match cur.step(*step)? {
+                Some(cur) => cur,
+                None => return Ok(None),
+            };
+        }
+        Ok(Some(cur))
+    }
+}
+
+impl Value {
+    // the encoded sub-value at `path` inside `buf`, e.g. `[Step::Field(0), Step::Field(0)]` for the
+    // timestamp of a commit
+    pub fn raw_at<'a>(buf: &'a [u8], path: &[Step]) -> Result<Option<&'a [u8]>> {
+        ValueCursor::new(buf).path(path)?.map(|cur| cur.raw()).transpose()
+    }
+
+    pub fn decode_at(buf: &[u8], path: &[Step]) -> Result<Option<Value>> {
+        ValueCursor::new(buf).path(path)?.map(|cur| cur.decode()).transpose()
+    }
+}
@@ -0,0 +1,159 @@
+// a patch is a list of edits applied in order, each at a path from the root. indices in a path
+// refer to the list as left by the edits before it.
+
+use std::collections::BTreeMap;
+use foundations::{error_enum, usize_casting::*};
+use crate::meta::{Patch, PatchEdit, PatchStep, PatchOp};
+use super::*;
+
+error_enum! {
+    #[derive(Debug)]
+    pub enum PatchError {
+        // index of the edit whose path does not lead anywhere
+        Path(usize),
+        // index of the edit whose op does not fit the place its path leads to
+        Op(usize),
+    } convert {}
+}
+
+struct Differ {
+    path: Vec<PatchStep>,
+    edits: Vec<PatchEdit>,
+}
+
+impl Differ {
+    fn edit(&mut self, step: Option<PatchStep>, op: PatchOp) {
+        let mut path = self.path.clone();
+        path.extend(step);
+        self.edits.push(PatchEdit { path, op });
+    }
+
+    fn at(&mut self, step: PatchStep, a: &Value, b: &Value) {
+        self.path.push(step);
+        self.val(a, b);
+        self.path.pop();
+    }
+
+    fn seq(&mut self, a: &[Value], b: &[Value]) {
+        for (i, (a, b)) in a.iter().zip(b).enumerate() {
+            self.at(PatchStep::Field(usize_u64(i)), a, b);
+        }
+    }
+
+    fn list(&mut self, a: &[Value], b: &[Value]) {
+        let pre = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+        let (a, b) = (&a[pre..], &b[pre..]);
+        let suf = a.iter().rev().zip(b.iter().rev()).take_while(|(a, b)| a == b).count();
+        let (a, b) = (&a[..a.len() - suf], &b[..b.len() - suf]);
+        let common = a.len().min(b.len());
+        for i in 0..common {
+            self.at(PatchStep::Index(usize_u64(pre + i)), &a[i], &b[i]);
+        }
+        for (i, v) in b.iter().enumerate().skip(common) {
+            self.edit(Some(PatchStep::Index(usize_u64(pre + i))), PatchOp::Insert(v.clone()));
+        }
+        for _ in common..a.len() {
+            self.edit(Some(PatchStep::Index(usize_u64(pre + common))), PatchOp::Remove(()));
+        }
+    }
+
+    // entries are matched by key. if removing and appending cannot reach the order of `b`, or keys
+    // repeat, the whole map is replaced.
+    fn map(&mut self, a: &[(Value, Value)], b: &[(Value, Value)]) -> bool {
+        let ai: BTreeMap<&Value, &Value> = a.iter().map(|(k, v)| (k, v)).collect();
+        let bi: BTreeMap<&Value, &Value> = b.iter().map(|(k, v)| (k, v)).collect();
+        if ai.len() != a.len() || bi.len() != b.len() {
+            return false;
+        }
+        let kept = a.iter().filter(|(k, _)| bi.contains_key(k));
+        let added = b.iter().filter(|(k, _)| !ai.contains_key(k));
+        if !kept.chain(added).map(|(k, _)| k).eq(b.iter().map(|(k, _)| k)) {
+            return false;
+        }
+        for (k, va) in a {
+            match bi.get(k) {
+                Some(vb) => self.at(PatchStep::Key(k.clone()), va, vb),
+                None => self.edit(Some(PatchStep::Key(k.clone())), PatchOp::Remove(())),
+            }
+        }
+        for (k, v) in b.iter().filter(|(k, _)| !ai.contains_key(k)) {
+            self.edit(Some(PatchStep::Key(k.clone())), PatchOp::Insert(v.clone()));
+        }
+        true
+    }
+
+    fn val(&mut self, a: &Value, b: &Value) {
+        if a == b {
+            return;
+        }
+        match (a, b) {
+            (Value::Tuple(sa), Value::Tuple(sb)) if sa.len() == sb.len() => self.seq(sa, sb),
+            (Value::Struct(pa, sa), Value::Struct(pb, sb)) if pa == pb && sa.len() == sb.len() => self.seq(sa, sb),
+            (Value::List(ta, sa), Value::List(tb, sb)) if ta == tb => self.list(sa, sb),
+            (Value::Option(ta, a), Value::Option(tb, b)) if ta == tb && a.is_some() && b.is_some() => {
+                self.at(PatchStep::Inner(()), a.as_ref().as_ref().unwrap(), b.as_ref().as_ref().unwrap())
+            },
+            (Value::Alias(pa, a), Value::Alias(pb, b)) if pa == pb => self.at(PatchStep::Inner(()), a, b),
+            (Value::Enum(pa, ea, a), Value::Enum(pb, eb, b)) if pa == pb && ea == eb => self.at(PatchStep::Inner(()), a, b),
+            (Value::Map(ta, sa), Value::Map(tb, sb)) if ta == tb && self.map(sa, sb) => {},
+            _ => self.edit(None, PatchOp::Replace(b.clone())),
+        }
+    }
+}
+
+fn step_mut<'v>(val: &'v mut Value, step: &PatchStep) -> Option<&'v mut Value> {
+    match (val, step) {
+        (Value::Tuple(s) | Value::Struct(_, s), PatchStep::Field(n)) => s.get_mut(u64_usize(*n)),
+        (Value::List(_, s), PatchStep::Index(i)) => s.get_mut(u64_usize(*i)),
+        (Value::Map(_, s), PatchStep::Key(k)) => s.iter_mut().find(|(kk, _)| kk == k).map(|(_, v)| v),
+        (Value::Option(_, v), PatchStep::Inner(())) => v.as_mut().as_mut(),
+        (Value::Alias(_, v) | Value::Enum(_, _, v), PatchStep::Inner(())) => Some(v.as_mut()),
+        _ => None,
+    }
+}
+
+fn apply(val: &mut Value, edit: &PatchEdit, i: usize) -> Result<(), PatchError> {
+    let (last, parents) = match (&edit.op, edit.path.split_last()) {
+        (PatchOp::Replace(_), _) | (_, None) => (None, &edit.path[..]),
+        (_, Some((last, parents))) => (Some(last), parents),
+    };
+    let mut val = val;
+    for step in parents {
+        val = step_mut(val, step).ok_or(PatchError::Path(i))?;
+    }
+    match (&edit.op, val, last) {
+        (PatchOp::Replace(v), val, None) => *val = v.clone(),
+        (PatchOp::Insert(v), Value::List(_, s), Some(PatchStep::Index(n))) if u64_usize(*n) <= s.len() => {
+            s.insert(u64_usize(*n), v.clone())
+        },
+        (PatchOp::Insert(v), Value::Map(_, s), Some(PatchStep::Key(k))) if !s.iter().any(|(kk, _)| kk == k) => {
+            s.push((k.clone(), v.clone()))
+        },
+        (PatchOp::Remove(()), Value::List(_, s), Some(PatchStep::Index(n))) if u64_usize(*n) < s.len() => {
+            s.remove(u64_usize(*n));
+        },
+        (PatchOp::Remove(()), Value::Map(_, s), Some(PatchStep::Key(k))) => {
+            let pos = s.iter().position(|(kk, _)| kk == k).ok_or(PatchError::Op(i))?;
+            s.remove(pos);
+        },
+        _ => return Err(PatchError::Op(i)),
+    }
+    Ok(())
+}
+
+impl Value {
+    // edits turning `a` into `b`, empty if they are equal
+    pub fn diff(a: &Value, b: &Value) -> Patch {
+        let mut differ = Differ { path: Vec::new(), edits: Vec::new() };
+        differ.val(a, b);
+        Patch { edits: differ.edits }
+    }
+
+    // stops at the first edit that does not fit, with the edits before it applied
+    pub fn apply_patch(&mut self, patch: &Patch) -> Result<(), PatchError> {
+        for (i, edit) in patch.edits.iter().enumerate() {
+            apply(self, edit, i)?;
+        }
+        Ok(())
+    }
+}
@@ -1,28 +1,105 @@
-use foundations::{num_compress::*, usize_casting::*};
-use super::*;
+use std::io;
+use foundations::{num_compress::*, usize_casting::*, sha3::{Shake, Hasher}};
+use crate::meta::check_raw_stdptr;
+use super::{*, packed::{PACKED, Packing, packs}};
 
-struct Writer {
-    bytes: Vec<u8>,
+trait Sink {
+    fn bytes(&mut self, bytes: &[u8]);
 }
 
-impl Writer {
-    fn new() -> Writer {
-        Writer { bytes: Vec::new() }
+impl Sink for Vec<u8> {
+    #[inline]
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
     }
+}
 
-    fn into_bytes(self) -> Vec<u8> {
-        let Writer { bytes } = self;
-        bytes
+impl<S: Sink> Sink for &mut S {
+    #[inline]
+    fn bytes(&mut self, bytes: &[u8]) {
+        S::bytes(self, bytes);
+    }
+}
+
+struct Len(usize);
+
+impl Sink for Len {
+    #[inline]
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.0 += bytes.len();
+    }
+}
+
+// keeps the first error and drops everything after it
+struct IoSink<W: io::Write> {
+    w: W,
+    err: Option<io::Error>,
+}
+
+impl<W: io::Write> Sink for IoSink<W> {
+    fn bytes(&mut self, bytes: &[u8]) {
+        if self.err.is_none() {
+            self.err = self.w.write_all(bytes).err();
+        }
+    }
+}
+
+struct HashSink<S: Sink> {
+    inner: S,
+    hasher: Shake,
+}
+
+impl<S: Sink> Sink for HashSink<S> {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.hasher.update(bytes);
+        self.inner.bytes(bytes);
+    }
+}
+
+struct Writer<S: Sink> {
+    sink: S,
+    // of each set being written, innermost last
+    sets: Vec<SetItems>,
+}
+
+// set items go straight to the sink, and are copied here as well to check that they ascend. the two
+// buffers are swapped between items, so a set takes no allocation per item.
+#[derive(Default)]
+struct SetItems {
+    prev: Vec<u8>,
+    cur: Vec<u8>,
+}
+
+// what is left to write of a value, kept on a heap stack so nesting takes no native stack
+enum Task<'v> {
+    Val(&'v Value),
+    // the set item `i` is written, check its order
+    SetItem(usize),
+    SetEnd,
+}
+
+impl<S: Sink> Writer<S> {
+    fn new(sink: S) -> Writer<S> {
+        Writer { sink, sets: Vec::new() }
+    }
+
+    fn into_inner(self) -> S {
+        let Writer { sink, .. } = self;
+        sink
     }
 
     #[inline]
     fn bytes<B: AsRef<[u8]>>(&mut self, bytes: B) {
-        self.bytes.extend_from_slice(bytes.as_ref());
+        let bytes = bytes.as_ref();
+        for set in &mut self.sets {
+            set.cur.extend_from_slice(bytes);
+        }
+        self.sink.bytes(bytes);
     }
 
     #[inline]
     fn u8(&mut self, n: u8) {
-        self.bytes.push(n);
+        self.bytes([n]);
     }
 
     fn u16(&mut self, n: u16) {
@@ -41,66 +118,73 @@ impl Writer {
         self.bytes(n.to_be_bytes());
     }
 
-    fn typeptr(&mut self, ptr: &TypePtr) {
+    fn typeptr(&mut self, ptr: &TypePtr) -> EncodeResult<()> {
         match ptr {
             TypePtr::Std(stdptr) => {
-                self.u16(stdptr.to_u16());
+                let n = stdptr.to_u16();
+                // a high byte of 0xFF would read back as the hash marker
+                if !check_raw_stdptr(n) {
+                    return Err(EncodeError::StdPtr(n));
+                }
+                self.u16(n);
             },
             TypePtr::Hash(hash) => {
                 self.u8(0xFF);
                 self.bytes(hash);
             }
         }
+        Ok(())
     }
 
-    fn ty(&mut self, t: &Type) {
-        self.u8(t.as_tag() as u8);
-        match t {
-            Type::Unknown |
-            Type::Unit |
-            Type::Bool |
-            Type::Int |
-            Type::UInt |
-            Type::Float |
-            Type::String |
-            Type::Bytes |
-            Type::Type |
-            Type::TypePtr |
-            Type::ObjectPtr |
-            Type::Timestamp |
-            Type::UInt8 |
-            Type::UInt16 |
-            Type::UInt32 => {},
-
-            Type::Option(t) |
-            Type::List(t) => {
-                self.ty(t);
-
-            },
-            Type::Map(tk, tv) => {
-                self.ty(tk);
-                self.ty(tv);
-
-            },
-
-            Type::Tuple(s) => {
-                // should checked in type check
-                self.u8(s.len().try_into().unwrap());
-
-                for t in s {
-                    self.ty(t);
-                }
-
+    // in prefix order, with the types still to write on a heap stack rather than in nested calls
+    fn ty(&mut self, t: &Type) -> EncodeResult<()> {
+        let mut todo = vec![t];
+        while let Some(t) = todo.pop() {
+            self.u8(t.as_tag() as u8);
+            match t {
+                Type::Unknown |
+                Type::Unit |
+                Type::Bool |
+                Type::Int |
+                Type::UInt |
+                Type::Float |
+                Type::String |
+                Type::Bytes |
+                Type::Type |
+                Type::TypePtr |
+                Type::ObjectPtr |
+                Type::Timestamp |
+                Type::UInt8 |
+                Type::UInt16 |
+                Type::UInt32 |
+                Type::Int8 |
+                Type::Int16 |
+                Type::Int32 |
+                Type::Float32 |
+                Type::BigInt |
+                Type::Decimal => {},
+
+                Type::Option(t) |
+                Type::List(t) |
+                Type::Set(t) => todo.push(t),
+                Type::Map(tk, tv) => {
+                    todo.push(tv);
+                    todo.push(tk);
+                },
+
+                Type::Tuple(s) => {
+                    let len = s.len().try_into().map_err(|_| EncodeError::TupleTypeLen(s.len()))?;
+                    self.u8(len);
+                    todo.extend(s.iter().rev());
+                },
+
+                Type::Alias(ptr) |
+                Type::CEnum(ptr) |
+                Type::Enum(ptr) |
+                Type::Struct(ptr) => self.typeptr(ptr)?,
             }
-
-            Type::Alias(ptr) |
-            Type::CEnum(ptr) |
-            Type::Enum(ptr) |
-            Type::Struct(ptr) => {
-                self.typeptr(ptr);
-
-            },
         }
+        Ok(())
     }
 
     #[inline]
@@ -112,6 +196,11 @@ impl Writer {
         self.with_l4(htag, l as u8)
     }
 
+    fn with_ext(&mut self, htag: HTag, e: ExtTag) {
+        self.with_ltag(htag, LTag::Ext);
+        self.u8(e as u8);
+    }
+
     fn with_uvar(&mut self, htag: HTag, u: u64) {
         if u < (EXT8 as u64) {
             self.with_l4(htag, u as u8);
@@ -138,6 +227,12 @@ impl Writer {
         self.with_uvar(htag, usize_u64(sz))
     }
 
+    fn with_bigint(&mut self, htag: HTag, b: &BigInt) {
+        let buf = b.to_wire();
+        self.with_szvar(htag, buf.len());
+        self.bytes(&buf);
+    }
+
     fn with_fvar(&mut self, htag: HTag, f: u64) {
         let len = float_find_zero(f);
         self.with_l4(htag, len as u8);
@@ -145,24 +240,94 @@ impl Writer {
         self.bytes(&buf[0..len]);
     }
 
-    fn val_seq(&mut self, s: &Vec<Value>) {
+    // the tasks are popped from the end, so items are pushed last first
+    fn val_seq<'v>(&mut self, s: &'v [Value], tasks: &mut Vec<Task<'v>>) {
+        tasks.extend(s.iter().rev().map(Task::Val));
+    }
 
-        for v in s {
-            self.val(v);
+    fn val_seq_map<'v>(&mut self, s: &'v [(Value, Value)], tasks: &mut Vec<Task<'v>>) {
+        for (k, v) in s.iter().rev() {
+            tasks.push(Task::Val(v));
+            tasks.push(Task::Val(k));
         }
+    }
 
+    fn leb128(&mut self, mut u: u64) {
+        while u >= 0x80 {
+            self.u8(u as u8 | 0x80);
+            u >>= 7;
+        }
+        self.u8(u as u8);
     }
 
-    fn val_seq_map(&mut self, s: &Vec<(Value, Value)>) {
+    // the caller has checked with `packs` that every item is of type `t`
+    fn packed(&mut self, htag: HTag, t: &Type, s: &[Value]) {
+        self.with_szvar(htag, s.len());
+        self.u8(t.as_tag() as u8 | PACKED);
+        if let Some((_, Packing::Bits)) = Packing::of(t.as_tag()) {
+            for chunk in s.chunks(8) {
+                let mut bits = 0;
+                for (i, v) in chunk.iter().enumerate() {
+                    if let Value::Bool(true) = v {
+                        bits |= 0x80 >> i;
+                    }
+                }
+                self.u8(bits);
+            }
+            return;
+        }
+        for v in s {
+            match v {
+                Value::UInt8(u) => self.u8(*u),
+                Value::Int8(i) => self.bytes(i.to_be_bytes()),
+                Value::UInt16(u) => self.u16(*u),
+                Value::Int16(i) => self.bytes(i.to_be_bytes()),
+                Value::UInt32(u) => self.u32(*u),
+                Value::Int32(i) => self.bytes(i.to_be_bytes()),
+                Value::Float32(f) => self.u32(*f),
+                Value::Float(f) => self.u64(*f),
+                Value::UInt(u) => self.leb128(*u),
+                Value::Int(i) => self.leb128(zigzag_encode(*i)),
+                _ => unreachable!(),
+            }
+        }
+    }
 
-        for (k, v) in s {
-            self.val(k);
-            self.val(v);
+    fn val_seq_set<'v>(&mut self, s: &'v [Value], tasks: &mut Vec<Task<'v>>) {
+        self.sets.push(SetItems::default());
+        tasks.push(Task::SetEnd);
+        for (i, v) in s.iter().enumerate().rev() {
+            tasks.push(Task::SetItem(i));
+            tasks.push(Task::Val(v));
         }
+    }
 
+    fn set_item(&mut self, i: usize) -> EncodeResult<()> {
+        let set = self.sets.last_mut().unwrap();
+        if i > 0 && set.prev >= set.cur {
+            return Err(EncodeError::SetOrder(i));
+        }
+        std::mem::swap(&mut set.prev, &mut set.cur);
+        set.cur.clear();
+        Ok(())
+    }
+
+    fn val(&mut self, val: &Value) -> EncodeResult<()> {
+        let mut tasks = vec![Task::Val(val)];
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Val(val) => self.val_head(val, &mut tasks)?,
+                Task::SetItem(i) => self.set_item(i)?,
+                Task::SetEnd => {
+                    self.sets.pop();
+                },
+            }
+        }
+        Ok(())
     }
 
-    fn val(&mut self, val: &Value) {
+    // writes what comes before the inner values of `val` and leaves those to `tasks`
+    fn val_head<'v>(&mut self, val: &'v Value, tasks: &mut Vec<Task<'v>>) -> EncodeResult<()> {
         let htag = val.as_htag();
         match val {
             Value::Unit => {
@@ -202,63 +367,67 @@ impl Writer {
             Value::Option(t, opt) => {
                 if let Some(v) = opt.as_ref() {
                     self.with_ltag(htag, LTag::Some);
-                    self.ty(t);
-                    self.val(v);
+                    self.ty(t)?;
+                    tasks.push(Task::Val(v));
                 } else {
                     self.with_ltag(htag, LTag::None);
-                    self.ty(t);
+                    self.ty(t)?;
                 }
 
+            },
+            Value::List(t, s) if packs(t, s.iter().map(Value::as_tag)) => {
+                self.packed(htag, t, s);
+
             },
             Value::List(t, s) => {
                 self.with_szvar(htag, s.len());
-                self.ty(t);
-                self.val_seq(s);
+                self.ty(t)?;
+                self.val_seq(s, tasks);
 
             },
             Value::Map((tk, tv), s) => {
                 self.with_szvar(htag, s.len());
-                self.ty(tk);
-                self.ty(tv);
-                self.val_seq_map(s);
+                self.ty(tk)?;
+                self.ty(tv)?;
+                self.val_seq_map(s, tasks);
 
             },
             Value::Tuple(s) => {
                 self.with_szvar(htag, s.len());
-                self.val_seq(s);
+                self.val_seq(s, tasks);
 
             },
             Value::Alias(ptr, v) => {
                 self.with_ltag(htag, LTag::Alias);
-                self.typeptr(ptr);
-                self.val(v);
+                self.typeptr(ptr)?;
+                tasks.push(Task::Val(v));
 
             },
             Value::CEnum(ptr, ev) => {
                 self.with_uvar(htag, *ev as u64);
-                self.typeptr(ptr);
+                self.typeptr(ptr)?;
 
             },
             Value::Enum(ptr, ev, v) => {
                 self.with_uvar(htag, *ev as u64);
-                self.typeptr(ptr);
-                self.val(v);
+                self.typeptr(ptr)?;
+                tasks.push(Task::Val(v));
 
             },
             Value::Struct(ptr, s) => {
                 self.with_szvar(htag, s.len());
-                self.typeptr(ptr);
-                self.val_seq(s);
+                self.typeptr(ptr)?;
+                self.val_seq(s, tasks);
                 
             },
             Value::Type(t) => {
                 self.with_ltag(htag, LTag::Type);
-                self.ty(t);
+                self.ty(t)?;
 
             },
             Value::TypePtr(ptr) => {
                 self.with_ltag(htag, LTag::TypePtr);
-                self.typeptr(ptr);
+                self.typeptr(ptr)?;
 
             },
             Value::ObjectPtr(ObjectPtr { ot, oid }) => {
@@ -274,25 +443,304 @@ impl Writer {
 
             },
             Value::UInt8(u) => {
+                self.with_ltag(htag, LTag::UInt8);
                 self.u8(*u);
 
             },
             Value::UInt16(u) => {
+                self.with_ltag(htag, LTag::UInt16);
                 self.u16(*u);
 
             },
             Value::UInt32(u) => {
+                self.with_ltag(htag, LTag::UInt32);
                 self.u32(*u);
 
             },
+            Value::Int8(i) => {
+                self.with_ltag(htag, LTag::Int8);
+                self.bytes(i.to_be_bytes());
+
+            },
+            Value::Int16(i) => {
+                self.with_ltag(htag, LTag::Int16);
+                self.bytes(i.to_be_bytes());
+
+            },
+            Value::Int32(i) => {
+                self.with_ext(htag, ExtTag::Int32);
+                self.bytes(i.to_be_bytes());
+
+            },
+            Value::Float32(f) => {
+                self.with_ext(htag, ExtTag::Float32);
+                self.u32(*f);
+
+            },
+            Value::BigInt(b) => {
+                self.with_bigint(htag, b);
+
+            },
+            Value::Decimal(Decimal { mantissa, scale }) => {
+                self.with_ivar(htag, *scale);
+                self.with_bigint(HTag::BigInt, mantissa);
+
+            },
+            Value::Set(t, s) => {
+                self.with_szvar(htag, s.len());
+                self.ty(t)?;
+                self.val_seq_set(s, tasks);
+
+            },
+        }
+        Ok(())
+    }
+}
+
+fn schema(kind: TypeCheckErrorKind) -> EncodeError {
+    TypeCheckError { path: Vec::new(), kind }.into()
+}
+
+fn def<R: Registry>(ptr: TypePtr, reg: &R) -> EncodeResult<&DefType> {
+    reg.get(ptr).ok_or_else(|| schema(TypeCheckErrorKind::Unresolved(ptr)))
+}
+
+// `typecheck` and `val_typed` recurse once per nesting level, so values nested deeper than
+// `decode_typed` reads back by default are turned away before either runs
+const TYPED_MAX_DEPTH: usize = DecodeOptions::DEFAULT.max_depth;
+
+fn typed_depth(val: &Value) -> EncodeResult<()> {
+    let mut todo = vec![(val, 1)];
+    while let Some((val, depth)) = todo.pop() {
+        if depth > TYPED_MAX_DEPTH {
+            return Err(EncodeError::DepthLimit(TYPED_MAX_DEPTH));
+        }
+        match val {
+            Value::Option(_, opt) => todo.extend(opt.iter().map(|v| (v, depth + 1))),
+            Value::List(_, s) | Value::Set(_, s) | Value::Tuple(s) | Value::Struct(_, s) => {
+                todo.extend(s.iter().map(|v| (v, depth + 1)));
+            },
+            Value::Map(_, s) => {
+                for (k, v) in s {
+                    todo.push((k, depth + 1));
+                    todo.push((v, depth + 1));
+                }
+            },
+            Value::Alias(_, v) | Value::Enum(_, _, v) => todo.push((&**v, depth + 1)),
+            _ => {},
+        }
+    }
+    Ok(())
+}
+
+// the typed encoding, where `t` and the definitions of the pointers in it stand in for what the
+// self-describing one spells out. `val` has passed `typecheck` against `t`, and a registry that
+// answers differently since is an error rather than a panic.
+impl<S: Sink> Writer<S> {
+    fn flag(&mut self, b: bool) {
+        self.u8(u8::from(b));
+    }
+
+    fn len(&mut self, len: usize) {
+        self.leb128(usize_u64(len));
+    }
+
+    // the declared inner type, written only where `expected` leaves it open
+    fn inner_typed<'t>(&mut self, expected: &Type, actual: &'t Type) -> EncodeResult<&'t Type> {
+        if let Type::Unknown = expected {
+            self.ty(actual)?;
+        }
+        Ok(actual)
+    }
+
+    fn val_seq_typed<R: Registry>(&mut self, s: &[Value], t: &Type, reg: &R) -> EncodeResult<()> {
+        for v in s {
+            self.val_typed(v, t, reg)?;
+        }
+        Ok(())
+    }
+
+    fn val_typed<R: Registry>(&mut self, val: &Value, t: &Type, reg: &R) -> EncodeResult<()> {
+        match (t, val) {
+            (Type::Unknown, val) => self.val(val)?,
+            // only the aliased value is written, as the generated types serialize it, so a wrapper
+            // would not come back from `decode_typed`
+            (Type::Alias(_), Value::Alias(vptr, _)) => return Err(EncodeError::AliasWrapper(*vptr)),
+            (Type::Alias(ptr), val) => {
+                let (_, t) = resolve_alias(*ptr, reg).map_err(schema)?;
+                self.val_typed(val, t, reg)?;
+            },
+            (_, Value::Unit) => {},
+            (_, Value::Bool(b)) => self.flag(*b),
+            (_, Value::Int(i)) => self.leb128(zigzag_encode(*i)),
+            (_, Value::UInt(u)) => self.leb128(*u),
+            (_, Value::Float(f)) => self.u64(*f),
+            (_, Value::String(b)) => {
+                self.len(b.len());
+                self.bytes(b);
+            },
+            (_, Value::Bytes(b)) => {
+                self.len(b.len());
+                self.bytes(b);
+            },
+            (Type::Option(t), Value::Option(vt, opt)) => {
+                let t = self.inner_typed(t, vt)?;
+                self.flag(opt.is_some());
+                if let Some(v) = opt.as_ref() {
+                    self.val_typed(v, t, reg)?;
+                }
+            },
+            (Type::List(t), Value::List(vt, s)) => {
+                self.len(s.len());
+                let t = self.inner_typed(t, vt)?;
+                self.val_seq_typed(s, t, reg)?;
+            },
+            (Type::Set(t), Value::Set(vt, s)) => {
+                self.len(s.len());
+                let t = self.inner_typed(t, vt)?;
+                // the order is still that of the self-describing encoding, as `set_item` checks it
+                let mut prev: Option<Vec<u8>> = None;
+                for (i, v) in s.iter().enumerate() {
+                    let buf = v.try_encode()?;
+                    if prev.as_ref().is_some_and(|prev| *prev >= buf) {
+                        return Err(EncodeError::SetOrder(i));
+                    }
+                    self.val_typed(v, t, reg)?;
+                    prev = Some(buf);
+                }
+            },
+            (Type::Map(tk, tv), Value::Map((vtk, vtv), s)) => {
+                self.len(s.len());
+                let tk = self.inner_typed(tk, vtk)?;
+                let tv = self.inner_typed(tv, vtv)?;
+                for (k, v) in s {
+                    self.val_typed(k, tk, reg)?;
+                    self.val_typed(v, tv, reg)?;
+                }
+            },
+            (Type::Tuple(ts), Value::Tuple(s)) => {
+                for (t, v) in ts.iter().zip(s) {
+                    self.val_typed(v, t, reg)?;
+                }
+            },
+            (_, Value::CEnum(_, ev)) => self.leb128(*ev),
+            (Type::Enum(ptr), Value::Enum(_, ev, v)) => {
+                let variants = match def(*ptr, reg)? {
+                    DefType::Enum(variants) => variants,
+                    _ => return Err(schema(TypeCheckErrorKind::DefKind((*ptr, Tag::Enum)))),
+                };
+                let (_, t) = variants.get(u64_usize(*ev)).ok_or_else(|| schema(TypeCheckErrorKind::Variant((*ptr, *ev))))?;
+                self.leb128(*ev);
+                self.val_typed(v, t, reg)?;
+            },
+            (Type::Struct(ptr), Value::Struct(_, s)) => {
+                let fields = match def(*ptr, reg)? {
+                    DefType::Struct(fields) => fields,
+                    _ => return Err(schema(TypeCheckErrorKind::DefKind((*ptr, Tag::Struct)))),
+                };
+                for ((_, t), v) in fields.iter().zip(s) {
+                    self.val_typed(v, t, reg)?;
+                }
+            },
+            (_, Value::Type(t)) => self.ty(t)?,
+            (_, Value::TypePtr(ptr)) => self.typeptr(ptr)?,
+            (_, Value::ObjectPtr(ObjectPtr { ot, oid })) => {
+                self.u16(*ot);
+                self.u64(*oid);
+            },
+            (_, Value::Timestamp(Timestamp { secs, nanos })) => {
+                self.i64(*secs);
+                self.u32(*nanos);
+            },
+            (_, Value::UInt8(u)) => self.u8(*u),
+            (_, Value::UInt16(u)) => self.u16(*u),
+            (_, Value::UInt32(u)) => self.u32(*u),
+            (_, Value::Int8(i)) => self.bytes(i.to_be_bytes()),
+            (_, Value::Int16(i)) => self.bytes(i.to_be_bytes()),
+            (_, Value::Int32(i)) => self.bytes(i.to_be_bytes()),
+            (_, Value::Float32(f)) => self.u32(*f),
+            (_, Value::BigInt(b)) => {
+                let buf = b.to_wire();
+                self.len(buf.len());
+                self.bytes(&buf);
+            },
+            (_, Value::Decimal(Decimal { mantissa, scale })) => {
+                self.leb128(zigzag_encode(*scale));
+                let buf = mantissa.to_wire();
+                self.len(buf.len());
+                self.bytes(&buf);
+            },
+            (t, val) => return Err(schema(TypeCheckErrorKind::Mismatch((t.as_tag(), val.as_tag())))),
         }
+        Ok(())
     }
 }
 
 impl Value {
+    pub fn try_encode(&self) -> EncodeResult<Vec<u8>> {
+        let mut buf = Vec::new();
+        self.encode_into(&mut buf)?;
+        Ok(buf)
+    }
+
     pub fn encode(&self) -> Vec<u8> {
-        let mut writer = Writer::new();
-        writer.val(self);
-        writer.into_bytes()
+        self.try_encode().unwrap()
+    }
+
+    pub fn encoded_len(&self) -> EncodeResult<usize> {
+        let mut writer = Writer::new(Len(0));
+        writer.val(self)?;
+        Ok(writer.into_inner().0)
+    }
+
+    // appends to `buf`, or leaves it as it was on error
+    pub fn encode_into(&self, buf: &mut Vec<u8>) -> EncodeResult<()> {
+        let start = buf.len();
+        let res = Writer::new(&mut *buf).val(self);
+        if res.is_err() {
+            buf.truncate(start);
+        }
+        res
+    }
+
+    // writes in many small pieces, so `w` should be buffered
+    pub fn encode_to<W: io::Write>(&self, w: W) -> EncodeResult<()> {
+        let mut writer = Writer::new(IoSink { w, err: None });
+        writer.val(self)?;
+        match writer.into_inner().err {
+            Some(err) => Err(err.into()),
+            None => Ok(()),
+        }
+    }
+
+    // appends to `buf` and returns the shake256 digest of the appended bytes. like `encode_into`,
+    // leaves `buf` as it was on error.
+    pub fn encode_into_hashed<const N: usize>(&self, buf: &mut Vec<u8>) -> EncodeResult<[u8; N]> {
+        let start = buf.len();
+        let mut writer = Writer::new(HashSink { inner: &mut *buf, hasher: Shake::v256() });
+        if let Err(err) = writer.val(self) {
+            buf.truncate(start);
+            return Err(err);
+        }
+        let mut hash = [0; N];
+        writer.into_inner().hasher.finalize(&mut hash);
+        Ok(hash)
+    }
+}
+
+impl Value {
+    // leaves out what `t` and the definitions in `reg` already say: the declared types `t` names,
+    // the pointers of structs and enums, and the lengths of tuples and structs. only
+    // `decode_typed` with the same `t` and definitions reads it back.
+    pub fn try_encode_typed<R: Registry>(&self, t: &Type, reg: &R) -> EncodeResult<Vec<u8>> {
+        typed_depth(self)?;
+        typecheck(self, t, reg)?;
+        let mut buf = Vec::new();
+        Writer::new(&mut buf).val_typed(self, t, reg)?;
+        Ok(buf)
+    }
+
+    pub fn encode_typed<R: Registry>(&self, t: &Type, reg: &R) -> Vec<u8> {
+        self.try_encode_typed(t, reg).unwrap()
     }
 }
@@ -0,0 +1,321 @@
+// every value is a single-key object tagged with its kind, types and pointers use the text notation:
+//
+// {"unit": null}                         {"bool": true}
+// {"int": "-5"}                          {"uint": "5"}           64-bit integers are strings
+// {"uint8": 5} {"uint16": 5} {"uint32": 5}  {"int8": -5} {"int16": -5} {"int32": -5}
+// {"float": 1.5}                         {"float": "0x7ff8000000000000"}   non-finite as raw bits
+// {"float32": 1.5}                       {"float32": "0x7fc00000"}
+// {"bigint": "-5"}                       {"decimal": "1.50"}     in the text notation, unsuffixed
+// {"string": "a"}                        {"bytes": "AP8="}       standard base64
+// {"option": ["string", null]}           {"option": ["string", {"string": "a"}]}
+// {"list": ["uint", [..]]}               {"map": ["uint", "string", [[k, v], ..]]}
+// {"set": ["uint", [..]]}                items in any order, repeats dropped
+// {"tuple": [..]}                        {"alias": ["std:prim:unix-ts", v]}
+// {"c-enum": ["std:types:commit-attr-type", 1]}
+// {"enum": ["std:meta:rev", 2, v]}       {"struct": ["std:meta:commit-ptr", [..]]}
+// {"type": "list<string>"}               {"type-ptr": "#fedcba98765432"}
+// {"object-ptr": [1, "2"]}               {"timestamp": "2023-11-14T22:13:20.000000005Z"}
+//
+// the pretty variant writes variant names instead of ids and struct fields as an object keyed by
+// field name, for every pointer the registry resolves. it reads back through `from_json_pretty`.
+
+use serde_json::{Value as Json, Map, json};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use foundations::{error_enum, usize_casting::*};
+use crate::meta::{ObjectPtr, TypePtr};
+use super::{*, text::{TimestampText, parse_timestamp_text}};
+
+error_enum! {
+    #[derive(Debug)]
+    pub enum JsonError {
+        Shape(&'static str),
+        Unresolved(TypePtr),
+        Name((TypePtr, String)),
+    } convert {
+        Text => TextError,
+        Base64 => base64::DecodeError,
+        Encode => EncodeError,
+    }
+}
+
+pub type JsonResult<T> = Result<T, JsonError>;
+
+fn tagged(tag: &str, payload: Json) -> Json {
+    let mut m = Map::new();
+    m.insert(tag.to_owned(), payload);
+    Json::Object(m)
+}
+
+fn shape<T>(msg: &'static str) -> JsonResult<T> {
+    Err(JsonError::Shape(msg))
+}
+
+struct Converter<'r> {
+    reg: Option<&'r dyn Registry>,
+}
+
+impl Converter<'_> {
+    fn def(&self, ptr: TypePtr) -> Option<&DefType> {
+        self.reg?.get(ptr)
+    }
+
+    fn write_seq(&self, s: &[Value]) -> Json {
+        Json::Array(s.iter().map(|v| self.write(v)).collect())
+    }
+
+    fn write(&self, val: &Value) -> Json {
+        match val {
+            Value::Unit => tagged("unit", Json::Null),
+            Value::Bool(b) => tagged("bool", json!(b)),
+            Value::Int(i) => tagged("int", json!(i.to_string())),
+            Value::UInt(u) => tagged("uint", json!(u.to_string())),
+            Value::Float(bits) => {
+                let f = f64::from_bits(*bits);
+                if f.is_finite() {
+                    tagged("float", json!(f))
+                } else {
+                    tagged("float", json!(format!("0x{:016x}", bits)))
+                }
+            },
+            Value::String(s) => tagged("string", json!(s)),
+            Value::Bytes(b) => tagged("bytes", json!(BASE64.encode(b))),
+            Value::Option(t, opt) => {
+                let v = match opt.as_ref() {
+                    Some(v) => self.write(v),
+                    None => Json::Null,
+                };
+                tagged("option", json!([t.to_string(), v]))
+            },
+            Value::List(t, s) => tagged("list", json!([t.to_string(), self.write_seq(s)])),
+            Value::Map((tk, tv), s) => {
+                let s: Vec<Json> = s.iter().map(|(k, v)| json!([self.write(k), self.write(v)])).collect();
+                tagged("map", json!([tk.to_string(), tv.to_string(), s]))
+            },
+            Value::Tuple(s) => tagged("tuple", self.write_seq(s)),
+            Value::Alias(ptr, v) => tagged("alias", json!([ptr.to_string(), self.write(v)])),
+            Value::CEnum(ptr, ev) => {
+                let name = match self.def(*ptr) {
+                    Some(DefType::CEnum(names)) => names.get(u64_usize(*ev)),
+                    _ => None,
+                };
+                let ev = name.map_or_else(|| json!(ev), |name| json!(name));
+                tagged("c-enum", json!([ptr.to_string(), ev]))
+            },
+            Value::Enum(ptr, ev, v) => {
+                let name = match self.def(*ptr) {
+                    Some(DefType::Enum(variants)) => variants.get(u64_usize(*ev)).map(|(name, _)| name),
+                    _ => None,
+                };
+                let ev = name.map_or_else(|| json!(ev), |name| json!(name));
+                tagged("enum", json!([ptr.to_string(), ev, self.write(v)]))
+            },
+            Value::Struct(ptr, s) => {
+                let s = match self.def(*ptr) {
+                    Some(DefType::Struct(fields)) if fields.len() == s.len() => {
+                        Json::Object(fields.iter().zip(s).map(|((name, _), v)| (name.clone(), self.write(v))).collect())
+                    },
+                    _ => self.write_seq(s),
+                };
+                tagged("struct", json!([ptr.to_string(), s]))
+            },
+            Value::Type(t) => tagged("type", json!(t.to_string())),
+            Value::TypePtr(ptr) => tagged("type-ptr", json!(ptr.to_string())),
+            Value::ObjectPtr(ObjectPtr { ot, oid }) => tagged("object-ptr", json!([ot, oid.to_string()])),
+            Value::Timestamp(ts) => tagged("timestamp", json!(TimestampText(ts).to_string())),
+            Value::UInt8(u) => tagged("uint8", json!(u)),
+            Value::UInt16(u) => tagged("uint16", json!(u)),
+            Value::UInt32(u) => tagged("uint32", json!(u)),
+            Value::Int8(i) => tagged("int8", json!(i)),
+            Value::Int16(i) => tagged("int16", json!(i)),
+            Value::Int32(i) => tagged("int32", json!(i)),
+            Value::Float32(bits) => {
+                let f = f32::from_bits(*bits);
+                if f.is_finite() {
+                    tagged("float32", json!(f))
+                } else {
+                    tagged("float32", json!(format!("0x{:08x}", bits)))
+                }
+            },
+            Value::BigInt(b) => tagged("bigint", json!(b.to_string())),
+            Value::Decimal(d) => tagged("decimal", json!(d.to_string())),
+            Value::Set(t, s) => tagged("set", json!([t.to_string(), self.write_seq(s)])),
+        }
+    }
+
+    fn read_seq(&self, j: &Json) -> JsonResult<Vec<Value>> {
+        arr(j)?.iter().map(|j| self.read(j)).collect()
+    }
+
+    // a variant id, or its name resolved through the registry
+    fn variant(&self, ptr: TypePtr, j: &Json, names: impl FnOnce(&DefType) -> Option<Vec<&String>>) -> JsonResult<EnumVariantId> {
+        let name = match j {
+            Json::String(name) => name,
+            j => return num(j),
+        };
+        let names = self.def(ptr).and_then(names).ok_or(JsonError::Unresolved(ptr))?;
+        match names.iter().position(|n| *n == name) {
+            Some(ev) => Ok(usize_u64(ev)),
+            None => Err(JsonError::Name((ptr, name.clone()))),
+        }
+    }
+
+    fn read(&self, j: &Json) -> JsonResult<Value> {
+        let (tag, p) = match j {
+            Json::Object(m) if m.len() == 1 => m.iter().next().unwrap(),
+            _ => return shape("expected single-key object"),
+        };
+        Ok(match tag.as_str() {
+            "unit" => match p {
+                Json::Null => Value::Unit,
+                _ => return shape("expected null"),
+            },
+            "bool" => Value::Bool(p.as_bool().ok_or(JsonError::Shape("expected bool"))?),
+            "int" => Value::Int(parse(p)?),
+            "uint" => Value::UInt(parse(p)?),
+            "float" => match p {
+                Json::Number(n) => Value::from_float(n.as_f64().ok_or(JsonError::Shape("expected float"))?),
+                Json::String(s) => {
+                    let bits = s.strip_prefix("0x").and_then(|hex| u64::from_str_radix(hex, 16).ok());
+                    Value::Float(bits.ok_or(JsonError::Shape("expected float bits"))?)
+                },
+                _ => return shape("expected float"),
+            },
+            "bigint" => Value::BigInt(str(p)?.parse()?),
+            "decimal" => Value::Decimal(str(p)?.parse()?),
+            "string" => Value::String(str(p)?.to_owned()),
+            "bytes" => Value::Bytes(BASE64.decode(str(p)?)?),
+            "option" => {
+                let [t, v] = tuple(p)?;
+                let v = match v {
+                    Json::Null => None,
+                    v => Some(self.read(v)?),
+                };
+                Value::Option(str(t)?.parse()?, Box::new(v))
+            },
+            "list" => {
+                let [t, s] = tuple(p)?;
+                Value::List(str(t)?.parse()?, self.read_seq(s)?)
+            },
+            "set" => {
+                let [t, s] = tuple(p)?;
+                Value::try_new_set(str(t)?.parse()?, self.read_seq(s)?)?
+            },
+            "map" => {
+                let [tk, tv, s] = tuple(p)?;
+                let s = arr(s)?.iter().map(|kv| {
+                    let [k, v] = tuple(kv)?;
+                    Ok((self.read(k)?, self.read(v)?))
+                }).collect::<JsonResult<_>>()?;
+                Value::Map((str(tk)?.parse()?, str(tv)?.parse()?), s)
+            },
+            "tuple" => Value::Tuple(self.read_seq(p)?),
+            "alias" => {
+                let [ptr, v] = tuple(p)?;
+                Value::Alias(str(ptr)?.parse()?, Box::new(self.read(v)?))
+            },
+            "c-enum" => {
+                let [ptr, ev] = tuple(p)?;
+                let ptr = str(ptr)?.parse()?;
+                let ev = self.variant(ptr, ev, |def| match def {
+                    DefType::CEnum(names) => Some(names.iter().collect()),
+                    _ => None,
+                })?;
+                Value::CEnum(ptr, ev)
+            },
+            "enum" => {
+                let [ptr, ev, v] = tuple(p)?;
+                let ptr = str(ptr)?.parse()?;
+                let ev = self.variant(ptr, ev, |def| match def {
+                    DefType::Enum(variants) => Some(variants.iter().map(|(name, _)| name).collect()),
+                    _ => None,
+                })?;
+                Value::Enum(ptr, ev, Box::new(self.read(v)?))
+            },
+            "struct" => {
+                let [ptr, s] = tuple(p)?;
+                let ptr = str(ptr)?.parse()?;
+                let s = match s {
+                    Json::Object(m) => {
+                        let fields = match self.def(ptr) {
+                            Some(DefType::Struct(fields)) => fields,
+                            _ => return Err(JsonError::Unresolved(ptr)),
+                        };
+                        if let Some(name) = m.keys().find(|name| !fields.iter().any(|(n, _)| n == *name)) {
+                            return Err(JsonError::Name((ptr, name.clone())));
+                        }
+                        fields.iter().map(|(name, _)| match m.get(name) {
+                            Some(v) => self.read(v),
+                            None => Err(JsonError::Name((ptr, name.clone()))),
+                        }).collect::<JsonResult<_>>()?
+                    },
+                    s => self.read_seq(s)?,
+                };
+                Value::Struct(ptr, s)
+            },
+            "type" => Value::Type(str(p)?.parse()?),
+            "type-ptr" => Value::TypePtr(str(p)?.parse()?),
+            "object-ptr" => {
+                let [ot, oid] = tuple(p)?;
+                Value::ObjectPtr(ObjectPtr { ot: num(ot)?, oid: parse(oid)? })
+            },
+            "timestamp" => Value::Timestamp(parse_timestamp_text(str(p)?).ok_or(JsonError::Shape("expected timestamp"))?),
+            "uint8" => Value::UInt8(num(p)?),
+            "uint16" => Value::UInt16(num(p)?),
+            "uint32" => Value::UInt32(num(p)?),
+            "int8" => Value::Int8(inum(p)?),
+            "int16" => Value::Int16(inum(p)?),
+            "int32" => Value::Int32(inum(p)?),
+            // an f32 widens to f64 exactly, so narrowing back restores it
+            "float32" => match p {
+                Json::Number(n) => Value::from_float32(n.as_f64().ok_or(JsonError::Shape("expected float"))? as f32),
+                Json::String(s) => {
+                    let bits = s.strip_prefix("0x").and_then(|hex| u32::from_str_radix(hex, 16).ok());
+                    Value::Float32(bits.ok_or(JsonError::Shape("expected float bits"))?)
+                },
+                _ => return shape("expected float"),
+            },
+            _ => return shape("unknown tag"),
+        })
+    }
+}
+
+fn str(j: &Json) -> JsonResult<&str> {
+    j.as_str().ok_or(JsonError::Shape("expected string"))
+}
+
+fn arr(j: &Json) -> JsonResult<&Vec<Json>> {
+    j.as_array().ok_or(JsonError::Shape("expected array"))
+}
+
+fn tuple<const N: usize>(j: &Json) -> JsonResult<&[Json; N]> {
+    arr(j)?.as_slice().try_into().map_err(|_| JsonError::Shape("unexpected array length"))
+}
+
+// integers that must stay exact in javascript travel as strings
+fn parse<T: std::str::FromStr>(j: &Json) -> JsonResult<T> {
+    str(j)?.parse().map_err(|_| JsonError::Shape("expected integer string"))
+}
+
+fn num<T: TryFrom<u64>>(j: &Json) -> JsonResult<T> {
+    j.as_u64().and_then(|u| u.try_into().ok()).ok_or(JsonError::Shape("expected integer"))
+}
+
+fn inum<T: TryFrom<i64>>(j: &Json) -> JsonResult<T> {
+    j.as_i64().and_then(|i| i.try_into().ok()).ok_or(JsonError::Shape("expected integer"))
+}
+
+pub fn to_json(val: &Value) -> Json {
+    Converter { reg: None }.write(val)
+}
+
+pub fn to_json_pretty<R: Registry>(val: &Value, reg: &R) -> Json {
+    Converter { reg: Some(reg) }.write(val)
+}
+
+pub fn from_json(json: &Json) -> JsonResult<Value> {
+    Converter { reg: None }.read(json)
+}
+
+pub fn from_json_pretty<R: Registry>(json: &Json, reg: &R) -> JsonResult<Value> {
+    Converter { reg: Some(reg) }.read(json)
+}
@@ -0,0 +1,310 @@
+// `value!` and `ty!` follow the text notation, with square brackets for type arguments (so that
+// `>>` never has to be split) and `=>` between map keys and values:
+//
+// ty!(map[uint, list[string]])                    ty!((int, option[unit],))
+// ty!(struct std:"meta":"commit-ptr")             ty!(alias {ptr})
+//
+// value!(())  value!((,))  value!((1, "a", b"\0"))   value!(true)  value!(-5)  value!(5u8)  value!(1.5)
+// value!(none[uint])                              value!(some[uint](5))
+// value!(list[uint] [1, 2])                       value!(map[string, uint] {"a" => 1})
+// value!(set[uint] [2, 1, 2])                    sorted and deduplicated as by `Value::new_set`
+// value!(alias std:"prim":"unix-ts" (5))          value!(c-enum std:"types":"commit-attr-type" (1))
+// value!(enum std:"meta":"rev" (0, ()))           value!(struct {ptr} (1, 2))
+// value!(type(list[uint]))  value!(type-ptr(std:"meta":"rev"))
+// value!(object-ptr(1, 2))  value!(timestamp(secs, nanos))
+//
+// unsuffixed integers are uint, or int when negative, floats are float, strings and byte strings
+// are string and bytes. unsuffixed integers are `i32` to rust, so `5i32` is uint too and an int32
+// goes through `{Value::Int32(5)}`. std paths resolve at compile time. `{expr}` takes a `Value`,
+// `Type` or `TypePtr` from an expression.
+
+use super::*;
+
+pub trait Lit {
+    fn lit(self) -> Value;
+}
+
+impl Lit for bool {
+    fn lit(self) -> Value {
+        Value::Bool(self)
+    }
+}
+
+// unsuffixed integer literals
+impl Lit for i32 {
+    fn lit(self) -> Value {
+        if self < 0 {
+            Value::Int(self.into())
+        } else {
+            Value::UInt(self as u64)
+        }
+    }
+}
+
+impl Lit for i64 {
+    fn lit(self) -> Value {
+        Value::Int(self)
+    }
+}
+
+impl Lit for u64 {
+    fn lit(self) -> Value {
+        Value::UInt(self)
+    }
+}
+
+impl Lit for f64 {
+    fn lit(self) -> Value {
+        Value::from_float(self)
+    }
+}
+
+impl Lit for u8 {
+    fn lit(self) -> Value {
+        Value::UInt8(self)
+    }
+}
+
+impl Lit for u16 {
+    fn lit(self) -> Value {
+        Value::UInt16(self)
+    }
+}
+
+impl Lit for u32 {
+    fn lit(self) -> Value {
+        Value::UInt32(self)
+    }
+}
+
+impl Lit for i8 {
+    fn lit(self) -> Value {
+        Value::Int8(self)
+    }
+}
+
+impl Lit for i16 {
+    fn lit(self) -> Value {
+        Value::Int16(self)
+    }
+}
+
+impl Lit for f32 {
+    fn lit(self) -> Value {
+        Value::from_float32(self)
+    }
+}
+
+impl Lit for &str {
+    fn lit(self) -> Value {
+        Value::String(self.to_owned())
+    }
+}
+
+impl<const N: usize> Lit for &[u8; N] {
+    fn lit(self) -> Value {
+        Value::Bytes(self.to_vec())
+    }
+}
+
+#[macro_export]
+macro_rules! ty {
+    (@ptr std : $p:literal : $n:literal) => {{
+        const P: $crate::meta::TypePtr = $crate::meta::TypePtr::from_u16_unchecked(
+            $crate::std::const_path2ptr($crate::std::path::StdPath { path: $p, name: $n })
+        );
+        P
+    }};
+    (@ptr {$ptr:expr}) => {
+        $ptr
+    };
+
+    // key and value types
+    (@pair [$($k:tt)+] , $($v:tt)+) => {
+        ($crate::ty!($($k)+), $crate::ty!($($v)+))
+    };
+    (@pair [$($k:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::ty!(@pair [$($k)* $next] $($rest)*)
+    };
+
+    (@seq [$($done:expr,)*] []) => {
+        vec![$($done,)*]
+    };
+    (@seq [$($done:expr,)*] [$($cur:tt)+]) => {
+        vec![$($done,)* $crate::ty!($($cur)+)]
+    };
+    (@seq [$($done:expr,)*] [$($cur:tt)+] , $($rest:tt)*) => {
+        $crate::ty!(@seq [$($done,)* $crate::ty!($($cur)+),] [] $($rest)*)
+    };
+    (@seq [$($done:expr,)*] [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::ty!(@seq [$($done,)*] [$($cur)* $next] $($rest)*)
+    };
+
+    (unknown) => { $crate::types::Type::Unknown };
+    (unit) => { $crate::types::Type::Unit };
+    (bool) => { $crate::types::Type::Bool };
+    (int) => { $crate::types::Type::Int };
+    (uint) => { $crate::types::Type::UInt };
+    (float) => { $crate::types::Type::Float };
+    (string) => { $crate::types::Type::String };
+    (bytes) => { $crate::types::Type::Bytes };
+    (type) => { $crate::types::Type::Type };
+    (type-ptr) => { $crate::types::Type::TypePtr };
+    (object-ptr) => { $crate::types::Type::ObjectPtr };
+    (timestamp) => { $crate::types::Type::Timestamp };
+    (uint8) => { $crate::types::Type::UInt8 };
+    (uint16) => { $crate::types::Type::UInt16 };
+    (uint32) => { $crate::types::Type::UInt32 };
+    (int8) => { $crate::types::Type::Int8 };
+    (int16) => { $crate::types::Type::Int16 };
+    (int32) => { $crate::types::Type::Int32 };
+    (float32) => { $crate::types::Type::Float32 };
+    (bigint) => { $crate::types::Type::BigInt };
+    (decimal) => { $crate::types::Type::Decimal };
+
+    (option [$($t:tt)+]) => {
+        $crate::types::Type::Option(Box::new($crate::ty!($($t)+)))
+    };
+    (list [$($t:tt)+]) => {
+        $crate::types::Type::List(Box::new($crate::ty!($($t)+)))
+    };
+    (set [$($t:tt)+]) => {
+        $crate::types::Type::Set(Box::new($crate::ty!($($t)+)))
+    };
+    (map [$($t:tt)+]) => {{
+        let (tk, tv) = $crate::ty!(@pair [] $($t)+);
+        $crate::types::Type::Map(Box::new(tk), Box::new(tv))
+    }};
+    (($($t:tt)*)) => {
+        $crate::types::Type::Tuple($crate::ty!(@seq [] [] $($t)*))
+    };
+
+    (alias $($p:tt)+) => {
+        $crate::types::Type::Alias($crate::ty!(@ptr $($p)+))
+    };
+    (c-enum $($p:tt)+) => {
+        $crate::types::Type::CEnum($crate::ty!(@ptr $($p)+))
+    };
+    (enum $($p:tt)+) => {
+        $crate::types::Type::Enum($crate::ty!(@ptr $($p)+))
+    };
+    (struct $($p:tt)+) => {
+        $crate::types::Type::Struct($crate::ty!(@ptr $($p)+))
+    };
+
+    ({$ty:expr}) => {
+        $ty
+    };
+}
+
+#[macro_export]
+macro_rules! value {
+    (@seq [$($done:expr,)*] []) => {
+        vec![$($done,)*]
+    };
+    (@seq [$($done:expr,)*] [$($cur:tt)+]) => {
+        vec![$($done,)* $crate::value!($($cur)+)]
+    };
+    (@seq [$($done:expr,)*] [$($cur:tt)+] , $($rest:tt)*) => {
+        $crate::value!(@seq [$($done,)* $crate::value!($($cur)+),] [] $($rest)*)
+    };
+    (@seq [$($done:expr,)*] [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::value!(@seq [$($done,)*] [$($cur)* $next] $($rest)*)
+    };
+
+    // key tokens up to `=>`, then value tokens up to `,`
+    (@map [$($done:expr,)*] []) => {
+        vec![$($done,)*]
+    };
+    (@map [$($done:expr,)*] [$($k:tt)+] => $($rest:tt)*) => {
+        $crate::value!(@map_val [$($done,)*] [$($k)+] [] $($rest)*)
+    };
+    (@map [$($done:expr,)*] [$($k:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::value!(@map [$($done,)*] [$($k)* $next] $($rest)*)
+    };
+    (@map_val [$($done:expr,)*] [$($k:tt)+] [$($v:tt)+]) => {
+        vec![$($done,)* ($crate::value!($($k)+), $crate::value!($($v)+))]
+    };
+    (@map_val [$($done:expr,)*] [$($k:tt)+] [$($v:tt)+] , $($rest:tt)*) => {
+        $crate::value!(@map [$($done,)* ($crate::value!($($k)+), $crate::value!($($v)+)),] [] $($rest)*)
+    };
+    (@map_val [$($done:expr,)*] [$($k:tt)+] [$($v:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::value!(@map_val [$($done,)*] [$($k)+] [$($v)* $next] $($rest)*)
+    };
+
+    // pointer tokens up to the final group
+    (@named alias [$($p:tt)+] ($($v:tt)+)) => {
+        $crate::types::Value::Alias($crate::ty!(@ptr $($p)+), Box::new($crate::value!($($v)+)))
+    };
+    (@named c_enum [$($p:tt)+] ($ev:expr)) => {
+        $crate::types::Value::CEnum($crate::ty!(@ptr $($p)+), $ev)
+    };
+    (@named enum [$($p:tt)+] ($ev:expr, $($v:tt)+)) => {
+        $crate::types::Value::Enum($crate::ty!(@ptr $($p)+), $ev, Box::new($crate::value!($($v)+)))
+    };
+    (@named struct [$($p:tt)+] ($($v:tt)*)) => {
+        $crate::types::Value::Struct($crate::ty!(@ptr $($p)+), $crate::value!(@seq [] [] $($v)*))
+    };
+    (@named $kind:tt [$($p:tt)*] $next:tt $($rest:tt)+) => {
+        $crate::value!(@named $kind [$($p)* $next] $($rest)+)
+    };
+
+    (()) => {
+        $crate::types::Value::Unit
+    };
+    ((,)) => {
+        $crate::types::Value::Tuple(Vec::new())
+    };
+    (($($v:tt)+)) => {
+        $crate::types::Value::Tuple($crate::value!(@seq [] [] $($v)+))
+    };
+
+    (none [$($t:tt)+]) => {
+        $crate::types::Value::Option($crate::ty!($($t)+), Box::new(None))
+    };
+    (some [$($t:tt)+] ($($v:tt)+)) => {
+        $crate::types::Value::Option($crate::ty!($($t)+), Box::new(Some($crate::value!($($v)+))))
+    };
+    (list [$($t:tt)+] [$($v:tt)*]) => {
+        $crate::types::Value::List($crate::ty!($($t)+), $crate::value!(@seq [] [] $($v)*))
+    };
+    (set [$($t:tt)+] [$($v:tt)*]) => {
+        $crate::types::Value::new_set($crate::ty!($($t)+), $crate::value!(@seq [] [] $($v)*))
+    };
+    (map [$($t:tt)+] {$($v:tt)*}) => {
+        $crate::types::Value::Map($crate::ty!(@pair [] $($t)+), $crate::value!(@map [] [] $($v)*))
+    };
+
+    (alias $($rest:tt)+) => {
+        $crate::value!(@named alias [] $($rest)+)
+    };
+    (c-enum $($rest:tt)+) => {
+        $crate::value!(@named c_enum [] $($rest)+)
+    };
+    (enum $($rest:tt)+) => {
+        $crate::value!(@named enum [] $($rest)+)
+    };
+    (struct $($rest:tt)+) => {
+        $crate::value!(@named struct [] $($rest)+)
+    };
+
+    (type ($($t:tt)+)) => {
+        $crate::types::Value::Type($crate::ty!($($t)+))
+    };
+    (type-ptr ($($p:tt)+)) => {
+        $crate::types::Value::TypePtr($crate::ty!(@ptr $($p)+))
+    };
+    (object-ptr ($ot:expr, $oid:expr)) => {
+        $crate::types::Value::ObjectPtr($crate::meta::ObjectPtr { ot: $ot, oid: $oid })
+    };
+    (timestamp ($secs:expr, $nanos:expr)) => {
+        $crate::types::Value::Timestamp($crate::meta::Timestamp { secs: $secs, nanos: $nanos })
+    };
+
+    ({$val:expr}) => {
+        $val
+    };
+    ($lit:literal) => {
+        $crate::types::macros::Lit::lit($lit)
+    };
+}
@@ -30,7 +30,14 @@ num_enum! {
         UInt8     = 0x14,
         UInt16    = 0x15,
         UInt32    = 0x16,
-    } as u8 else DecodeError::Tag
+        Int8      = 0x17,
+        Int16     = 0x18,
+        Int32     = 0x19,
+        Float32   = 0x1A,
+        BigInt    = 0x1B,
+        Decimal   = 0x1C,
+        Set       = 0x1D,
+    } as u8 else DecodeError::tag
 }
 
 num_enum! {
@@ -47,7 +54,10 @@ num_enum! {
         CEnum     = 0x9,
         Enum      = 0xA,
         Struct    = 0xB,
-    } as u8 else DecodeError::HTag
+        BigInt    = 0xC,
+        Decimal   = 0xD,
+        Set       = 0xE,
+    } as u8 else DecodeError::htag
 }
 
 num_enum! {
@@ -65,10 +75,23 @@ num_enum! {
         UInt8     = 0xA,
         UInt16    = 0xB,
         UInt32    = 0xC,
-    } as u8 else DecodeError::LTag
+        Int8      = 0xD,
+        Int16     = 0xE,
+        // followed by an `ExtTag` byte
+        Ext       = 0xF,
+    } as u8 else DecodeError::ltag
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+num_enum! {
+    pub enum ExtTag {
+        Int32     = 0x00,
+        Float32   = 0x01,
+    } as u8 else DecodeError::exttag
+}
+
+// ordered by hand, see `ord.rs`. `Clone`, `Ord` and dropping are iterative as well, while the
+// derived `PartialEq`, `Hash` and `Debug` recurse once per level like those of `Value`
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub enum Type {
     Unknown,
 
@@ -99,9 +122,23 @@ pub enum Type {
     UInt8,
     UInt16,
     UInt32,
+
+    Int8,
+    Int16,
+    Int32,
+    Float32,
+
+    BigInt,
+    Decimal,
+
+    Set(Box<Type>),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+// ordered by hand, see `ord.rs`. encoding, decoding, `as_type`, `Ord` and dropping take constant
+// native stack however deep a value is, but the derived `Clone`, `PartialEq`, `Hash` and `Debug`
+// recurse once per level, so values nested more than a few thousand levels deep are only safe to
+// compare and copy by their encodings
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Value {
     Unit,
     Bool(bool),
@@ -130,9 +167,245 @@ pub enum Value {
     UInt8(u8),
     UInt16(u16),
     UInt32(u32),
+
+    Int8(i8),
+    Int16(i16),
+    Int32(i32),
+    Float32(u32),
+
+    BigInt(BigInt),
+    Decimal(Decimal),
+
+    // items strictly ascending by encoding, see `Value::try_new_set`
+    Set(Type, Vec<Value>),
+}
+
+// the inner values are moved out onto a heap stack and dropped from there, so dropping a value
+// takes constant native stack however deep it is. with `Drop` implemented, values and types can no
+// longer be destructured by value: match on a reference, or move fields out with `take`.
+impl Drop for Value {
+    fn drop(&mut self) {
+        let mut inner = Vec::new();
+        self.take_inner(&mut inner);
+        while let Some(mut v) = inner.pop() {
+            v.take_inner(&mut inner);
+        }
+    }
+}
+
+// like the one of `Value`. declared types nest as deep as values on the wire, and `as_type` of a
+// deep value is as deep, so either would overflow the stack when dropped otherwise. it forbids
+// destructuring types by value just the same.
+impl Drop for Type {
+    fn drop(&mut self) {
+        let mut inner = Vec::new();
+        self.take_inner(&mut inner);
+        while let Some(mut t) = inner.pop() {
+            t.take_inner(&mut inner);
+        }
+    }
+}
+
+impl Type {
+    fn take_inner(&mut self, out: &mut Vec<Type>) {
+        match self {
+            Type::Option(t) | Type::List(t) | Type::Set(t) => out.push(t.take()),
+            Type::Map(tk, tv) => {
+                out.push(tk.take());
+                out.push(tv.take());
+            },
+            Type::Tuple(s) => out.append(s),
+            _ => {},
+        }
+    }
+
+    // leaves `Unknown` behind, see `Value::take`
+    pub fn take(&mut self) -> Type {
+        std::mem::replace(self, Type::Unknown)
+    }
+}
+
+// iterative as well, so that declared types of any depth can be copied
+impl Clone for Type {
+    fn clone(&self) -> Type {
+        let mut built = TypeBuilder::default();
+        let mut todo = vec![self];
+        loop {
+            let (t, inner) = match todo.pop().unwrap() {
+                Type::Option(t) => {
+                    todo.push(t);
+                    (Type::Option(Box::new(Type::Unknown)), 1)
+                },
+                Type::List(t) => {
+                    todo.push(t);
+                    (Type::List(Box::new(Type::Unknown)), 1)
+                },
+                Type::Set(t) => {
+                    todo.push(t);
+                    (Type::Set(Box::new(Type::Unknown)), 1)
+                },
+                Type::Map(tk, tv) => {
+                    todo.push(tv);
+                    todo.push(tk);
+                    (Type::Map(Box::new(Type::Unknown), Box::new(Type::Unknown)), 2)
+                },
+                Type::Tuple(s) => {
+                    todo.extend(s.iter().rev());
+                    (Type::Tuple(Vec::with_capacity(s.len())), s.len())
+                },
+                Type::Alias(ptr) => (Type::Alias(*ptr), 0),
+                Type::CEnum(ptr) => (Type::CEnum(*ptr), 0),
+                Type::Enum(ptr) => (Type::Enum(*ptr), 0),
+                Type::Struct(ptr) => (Type::Struct(*ptr), 0),
+                Type::Unknown => (Type::Unknown, 0),
+                Type::Unit => (Type::Unit, 0),
+                Type::Bool => (Type::Bool, 0),
+                Type::Int => (Type::Int, 0),
+                Type::UInt => (Type::UInt, 0),
+                Type::Float => (Type::Float, 0),
+                Type::String => (Type::String, 0),
+                Type::Bytes => (Type::Bytes, 0),
+                Type::Type => (Type::Type, 0),
+                Type::TypePtr => (Type::TypePtr, 0),
+                Type::ObjectPtr => (Type::ObjectPtr, 0),
+                Type::Timestamp => (Type::Timestamp, 0),
+                Type::UInt8 => (Type::UInt8, 0),
+                Type::UInt16 => (Type::UInt16, 0),
+                Type::UInt32 => (Type::UInt32, 0),
+                Type::Int8 => (Type::Int8, 0),
+                Type::Int16 => (Type::Int16, 0),
+                Type::Int32 => (Type::Int32, 0),
+                Type::Float32 => (Type::Float32, 0),
+                Type::BigInt => (Type::BigInt, 0),
+                Type::Decimal => (Type::Decimal, 0),
+            };
+            if let Some(t) = built.push(t, inner) {
+                return t;
+            }
+        }
+    }
+}
+
+// puts a type together from the types in it in prefix order, the order they are encoded in. the
+// ones still missing inner types wait on a heap stack rather than in nested calls.
+#[derive(Default)]
+pub(super) struct TypeBuilder {
+    open: Vec<(Type, usize)>,
+}
+
+impl TypeBuilder {
+    // how many types are still missing inner ones
+    pub(super) fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    // `t` has its inner types left unknown for the next `inner` types to fill in. gives back the
+    // outermost type once it is complete
+    pub(super) fn push(&mut self, t: Type, inner: usize) -> Option<Type> {
+        if inner > 0 {
+            self.open.push((t, inner));
+            return None;
+        }
+        let mut done = t;
+        while let Some((t, left)) = self.open.last_mut() {
+            match t {
+                Type::Option(t) | Type::List(t) | Type::Set(t) => **t = done,
+                Type::Map(tk, _) if *left == 2 => **tk = done,
+                Type::Map(_, tv) => **tv = done,
+                Type::Tuple(s) => s.push(done),
+                _ => unreachable!(),
+            }
+            *left -= 1;
+            if *left > 0 {
+                return None;
+            }
+            done = self.open.pop().unwrap().0;
+        }
+        Some(done)
+    }
+}
+
+impl Value {
+    // leaves a unit behind, for moving out of the fields of a value, which `Drop` forbids
+    pub fn take(&mut self) -> Value {
+        std::mem::replace(self, Value::Unit)
+    }
+
+    fn take_inner(&mut self, out: &mut Vec<Value>) {
+        match self {
+            Value::Option(_, opt) => out.extend(opt.take()),
+            Value::List(_, s) | Value::Tuple(s) | Value::Struct(_, s) | Value::Set(_, s) => out.append(s),
+            Value::Map(_, s) => {
+                for (k, v) in s.drain(..) {
+                    out.push(k);
+                    out.push(v);
+                }
+            },
+            Value::Alias(_, v) | Value::Enum(_, _, v) => out.push(v.take()),
+            _ => {},
+        }
+    }
+}
+
+// String and Bytes borrow from the decoded buffer. it is decoded in constant native stack as
+// `Value` is, but has no `Drop` of its own, so dropping a deep one recurses where `into_owned`
+// takes it apart level by level
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValueRef<'a> {
+    Unit,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(u64),
+    String(&'a str),
+    Bytes(&'a [u8]),
+
+    Option(Type, Box<Option<ValueRef<'a>>>),
+    List(Type, Vec<ValueRef<'a>>),
+    Map((Type, Type), Vec<(ValueRef<'a>, ValueRef<'a>)>),
+
+    Tuple(Vec<ValueRef<'a>>),
+
+    Alias(TypePtr, Box<ValueRef<'a>>),
+    CEnum(TypePtr, EnumVariantId),
+    Enum(TypePtr, EnumVariantId, Box<ValueRef<'a>>),
+    Struct(TypePtr, Vec<ValueRef<'a>>),
+
+    Type(Type),
+    TypePtr(TypePtr),
+    ObjectPtr(ObjectPtr),
+    Timestamp(Timestamp),
+
+    UInt8(u8),
+    UInt16(u16),
+    UInt32(u32),
+
+    Int8(i8),
+    Int16(i16),
+    Int32(i32),
+    Float32(u32),
+
+    BigInt(BigInt),
+    Decimal(Decimal),
+
+    Set(Type, Vec<ValueRef<'a>>),
 }
 
 mod casting;
+mod packed;
+mod big;
+pub use big::*;
+mod ord;
+mod canonical;
+pub use canonical::*;
+mod diff;
+pub use diff::*;
+mod visit;
+pub use visit::*;
+mod convert;
+pub use convert::*;
+#[doc(hidden)]
+pub mod macros;
 
 pub(self) const EXT8: u8 = 0xC;
 pub(self) const EXT16: u8 = 0xD;
@@ -141,29 +414,232 @@ pub(self) const EXT64: u8 = 0xF;
 
 error_enum! {
     #[derive(Debug)]
-    pub enum DecodeError {
+    pub enum DecodeErrorKind {
         FloatL4(u8),
         TooShort((usize, usize)),
         TooLong(usize),
         Tag(u8),
         HTag(u8),
         LTag(u8),
+        ExtTag(u8),
+        DepthLimit(usize),
+        LenLimit((usize, usize)),
+        BytesLimit((usize, usize)),
+        AllocLimit(usize),
+        NonCanonical((u8, u64)),
+        // index of the map entry whose key is not above the previous one
+        KeyOrder(usize),
+        // header byte of a value the path step cannot enter
+        Step(u8),
+        // header byte found where a decimal's mantissa should be
+        Mantissa(u8),
+        // index of the set item that is not above the previous one
+        SetOrder(usize),
+        // type byte of a packed list whose item type is not packable
+        PackedTag(u8),
+        // type byte of the packed list a cursor points into, whose items have no bytes of their own
+        PackedItem(u8),
+        // bytes read of a leb128 varint, in a packed list or the typed encoding, that overflows 64
+        // bits, or is longer than needed when canonical
+        Varint(usize),
+        // byte other than 0 or 1 where the typed encoding has a bool or an option's presence
+        Flag(u8),
+        // what the typed encoding refers to is missing from the registry or does not fit it
+        Schema(TypeCheckErrorKind),
     } convert {
         Utf8 => std::string::FromUtf8Error,
+        Io => std::io::Error,
+    }
+}
+
+// where decoding failed: the offset is where the innermost value that could not be decoded starts,
+// counted from the start of the buffer, and the path leads to that value from the top one. boxed to
+// keep the results the decoder passes around small.
+#[derive(Debug)]
+pub struct DecodeError(Box<DecodeErrorInner>);
+
+#[derive(Debug)]
+struct DecodeErrorInner {
+    kind: DecodeErrorKind,
+    offset: usize,
+    path: Vec<PathSeg>,
+}
+
+impl DecodeError {
+    pub fn kind(&self) -> &DecodeErrorKind {
+        &self.0.kind
+    }
+
+    pub fn into_kind(self) -> DecodeErrorKind {
+        self.0.kind
+    }
+
+    pub fn offset(&self) -> usize {
+        self.0.offset
+    }
+
+    pub fn path(&self) -> &[PathSeg] {
+        &self.0.path
+    }
+}
+
+impl From<DecodeErrorKind> for DecodeError {
+    fn from(kind: DecodeErrorKind) -> DecodeError {
+        // the reader fills in the location while unwinding
+        DecodeError(Box::new(DecodeErrorInner { kind, offset: usize::MAX, path: Vec::new() }))
+    }
+}
+
+impl From<std::string::FromUtf8Error> for DecodeError {
+    fn from(e: std::string::FromUtf8Error) -> DecodeError {
+        DecodeErrorKind::from(e).into()
+    }
+}
+
+impl From<std::io::Error> for DecodeError {
+    fn from(e: std::io::Error) -> DecodeError {
+        DecodeErrorKind::from(e).into()
+    }
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} at offset {}", self.kind(), self.offset())?;
+        if !self.path().is_empty() {
+            write!(f, " in {}", self.path_string())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+// for the `num_enum`s above
+impl DecodeError {
+    fn tag(b: u8) -> DecodeError {
+        DecodeErrorKind::Tag(b).into()
+    }
+
+    fn htag(b: u8) -> DecodeError {
+        DecodeErrorKind::HTag(b).into()
+    }
+
+    fn ltag(b: u8) -> DecodeError {
+        DecodeErrorKind::LTag(b).into()
+    }
+
+    fn exttag(b: u8) -> DecodeError {
+        DecodeErrorKind::ExtTag(b).into()
     }
 }
 
 pub type DecodeResult<T> = Result<T, DecodeError>;
 
+error_enum! {
+    #[derive(Debug)]
+    pub enum EncodeError {
+        // tuple types store their length in one byte
+        TupleTypeLen(usize),
+        // repeated map key found by `canonicalize`
+        DupKey(Value),
+        StdPtr(u16),
+        // index of the set item that is not above the previous one
+        SetOrder(usize),
+        // `Value::Alias` where the typed encoding takes the aliased value only
+        AliasWrapper(TypePtr),
+        // nesting deeper than the typed encoding takes
+        DepthLimit(usize),
+    } convert {
+        Io => std::io::Error,
+        TypeCheck => TypeCheckError,
+    }
+}
+
+pub type EncodeResult<T> = Result<T, EncodeError>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeOptions {
+    // nesting of values and types
+    pub max_depth: usize,
+    // items of a single list, map, tuple or struct
+    pub max_len: usize,
+    // length of a single string or bytes
+    pub max_bytes_len: usize,
+    // approximate heap bytes of the whole decoded value
+    pub max_alloc: usize,
+    // reject every encoding that `Value::encode` would not produce
+    pub canonical: bool,
+    // reject maps whose keys are not strictly ascending by encoding, as `Value::canonicalize` leaves them
+    pub sorted_keys: bool,
+}
+
+impl DecodeOptions {
+    pub const DEFAULT: DecodeOptions = DecodeOptions {
+        max_depth: 128,
+        max_len: 1 << 20,
+        max_bytes_len: 1 << 26,
+        max_alloc: 1 << 28,
+        canonical: false,
+        sorted_keys: false,
+    };
+
+    pub const UNLIMITED: DecodeOptions = DecodeOptions {
+        max_depth: usize::MAX,
+        max_len: usize::MAX,
+        max_bytes_len: usize::MAX,
+        max_alloc: usize::MAX,
+        canonical: false,
+        sorted_keys: false,
+    };
+}
+
+impl Default for DecodeOptions {
+    fn default() -> DecodeOptions {
+        DecodeOptions::DEFAULT
+    }
+}
+
+error_enum! {
+    #[derive(Debug)]
+    pub enum DeserializeError {
+        Mismatch((Tag, Tag)),
+        Len((usize, usize)),
+        Variant(EnumVariantId),
+        // expected and found pointer of a def value
+        Ptr((TypePtr, TypePtr)),
+    } convert {}
+}
+
+pub type DeserializeResult<T> = Result<T, DeserializeError>;
+
 mod encode;
 mod decode;
+pub use decode::{Step, ValueCursor};
+mod stream;
+pub use stream::*;
 
-pub trait Schema {
+pub trait Schema: Sized {
     const PTR: TypePtr;
     fn serialize(self) -> Value;
-    fn deserialize(val: Value) -> Self;
+    fn try_deserialize(val: Value) -> DeserializeResult<Self>;
+
+    fn deserialize(val: Value) -> Self {
+        Self::try_deserialize(val).unwrap()
+    }
 }
 
+mod typecheck;
+pub use typecheck::*;
+
+mod text;
+pub use text::*;
+
+#[cfg(feature = "serde")]
+pub mod serde;
+
+#[cfg(feature = "json")]
+pub mod json;
+
 pub use crate::std::codegen::types::{DefType, Trait, CommitAttr, CommitAttrType, StateAttr, Validator};
 
 #[cfg(test)]
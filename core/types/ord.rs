@@ -0,0 +1,119 @@
+// values order by their canonical encodings, the order sets and sorted maps keep on the wire. so
+// kinds order by the header byte they encode to, i.e. by `HTag` and then by `LTag` for the L4 kinds,
+// false < true and none < some, and then by the `ExtTag` byte that follows `LTag::Ext`. within a
+// kind that is the order of the encoded bytes, not of the numbers or strings: ints by their zigzag
+// varint, strings and bytes shorter first, floats by their trimmed bits. as every value that encodes
+// decodes back to itself, equal encodings mean equal values. values that do not encode, such as
+// sets with unsorted items, come after those of their kind that do and compare their fields in
+// declaration order among themselves.
+//
+// types order by their encodings as well: by `Tag`, tuples by their length first, and then by
+// the types in them in prefix order.
+
+use std::cmp::Ordering;
+use super::*;
+
+fn header(val: &Value) -> (u8, u8) {
+    let ext = match val {
+        Value::Int32(_) => ExtTag::Int32,
+        Value::Float32(_) => ExtTag::Float32,
+        _ => return (header_byte(val), 0),
+    };
+    (LTag::Ext as u8, ext as u8)
+}
+
+fn header_byte(val: &Value) -> u8 {
+    let ltag = match val {
+        Value::Unit => LTag::Unit,
+        Value::Bool(false) => LTag::False,
+        Value::Bool(true) => LTag::True,
+        Value::Option(_, opt) if opt.is_none() => LTag::None,
+        Value::Option(_, _) => LTag::Some,
+        Value::Alias(_, _) => LTag::Alias,
+        Value::Type(_) => LTag::Type,
+        Value::TypePtr(_) => LTag::TypePtr,
+        Value::ObjectPtr(_) => LTag::ObjectPtr,
+        Value::Timestamp(_) => LTag::Timestamp,
+        Value::UInt8(_) => LTag::UInt8,
+        Value::UInt16(_) => LTag::UInt16,
+        Value::UInt32(_) => LTag::UInt32,
+        Value::Int8(_) => LTag::Int8,
+        Value::Int16(_) => LTag::Int16,
+        _ => return (val.as_htag() as u8) << 4,
+    };
+    ltag as u8
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Value) -> Ordering {
+        header(self).cmp(&header(other)).then_with(|| match (self.try_encode(), other.try_encode()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => fields(self, other),
+        })
+    }
+}
+
+// the order among values of the same kind that do not encode
+fn fields(val: &Value, other: &Value) -> Ordering {
+    match (val, other) {
+        (Value::Option(ta, a), Value::Option(tb, b)) => ta.cmp(tb).then_with(|| a.cmp(b)),
+        (Value::List(ta, a), Value::List(tb, b)) => ta.cmp(tb).then_with(|| a.cmp(b)),
+        (Value::Map(ta, a), Value::Map(tb, b)) => ta.cmp(tb).then_with(|| a.cmp(b)),
+        (Value::Tuple(a), Value::Tuple(b)) => a.cmp(b),
+        (Value::Alias(pa, a), Value::Alias(pb, b)) => pa.cmp(pb).then_with(|| a.cmp(b)),
+        (Value::CEnum(pa, ea), Value::CEnum(pb, eb)) => pa.cmp(pb).then_with(|| ea.cmp(eb)),
+        (Value::Enum(pa, ea, a), Value::Enum(pb, eb, b)) => pa.cmp(pb).then_with(|| ea.cmp(eb)).then_with(|| a.cmp(b)),
+        (Value::Struct(pa, a), Value::Struct(pb, b)) => pa.cmp(pb).then_with(|| a.cmp(b)),
+        (Value::Type(a), Value::Type(b)) => a.cmp(b),
+        (Value::TypePtr(a), Value::TypePtr(b)) => a.cmp(b),
+        (Value::Set(ta, a), Value::Set(tb, b)) => ta.cmp(tb).then_with(|| a.cmp(b)),
+        // only kinds with inner values, types or pointers fail to encode
+        _ => Ordering::Equal,
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Value) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// iterative, so types of any depth compare in constant native stack
+impl Ord for Type {
+    fn cmp(&self, other: &Type) -> Ordering {
+        let mut todo = vec![(self, other)];
+        while let Some((a, b)) = todo.pop() {
+            let ord = (a.as_tag() as u8).cmp(&(b.as_tag() as u8)).then_with(|| match (a, b) {
+                (Type::Tuple(a), Type::Tuple(b)) => a.len().cmp(&b.len()),
+                (Type::Alias(a), Type::Alias(b)) |
+                (Type::CEnum(a), Type::CEnum(b)) |
+                (Type::Enum(a), Type::Enum(b)) |
+                (Type::Struct(a), Type::Struct(b)) => a.cmp(b),
+                _ => Ordering::Equal,
+            });
+            if ord != Ordering::Equal {
+                return ord;
+            }
+            match (a, b) {
+                (Type::Option(a), Type::Option(b)) |
+                (Type::List(a), Type::List(b)) |
+                (Type::Set(a), Type::Set(b)) => todo.push((a, b)),
+                (Type::Map(ak, av), Type::Map(bk, bv)) => {
+                    todo.push((av, bv));
+                    todo.push((ak, bk));
+                },
+                (Type::Tuple(a), Type::Tuple(b)) => todo.extend(a.iter().zip(b).rev()),
+                _ => {},
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+impl PartialOrd for Type {
+    fn partial_cmp(&self, other: &Type) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -0,0 +1,50 @@
+// a list whose item type is a fixed-width integer, a float, int, uint or bool, and whose items all
+// are of that type, is written packed: the list header, the item type's tag with `PACKED` set, and
+// then the items without headers. bools take a bit each, most significant first, fixed-width
+// numbers their big-endian bytes, and int and uint a leb128 varint, zigzagged for int. it decodes
+// back into the same `Value::List`, so the packing is only visible on the wire.
+
+use super::*;
+
+// no tag has the high bit set
+pub(super) const PACKED: u8 = 0x80;
+
+pub(super) enum Packing {
+    Bits,
+    Fixed(usize),
+    Var,
+}
+
+impl Packing {
+    // the type `tag` stands for, if lists of it are packed
+    pub(super) fn of(tag: Tag) -> Option<(Type, Packing)> {
+        Some(match tag {
+            Tag::Bool => (Type::Bool, Packing::Bits),
+            Tag::UInt8 => (Type::UInt8, Packing::Fixed(1)),
+            Tag::Int8 => (Type::Int8, Packing::Fixed(1)),
+            Tag::UInt16 => (Type::UInt16, Packing::Fixed(2)),
+            Tag::Int16 => (Type::Int16, Packing::Fixed(2)),
+            Tag::UInt32 => (Type::UInt32, Packing::Fixed(4)),
+            Tag::Int32 => (Type::Int32, Packing::Fixed(4)),
+            Tag::Float32 => (Type::Float32, Packing::Fixed(4)),
+            Tag::Float => (Type::Float, Packing::Fixed(8)),
+            Tag::Int => (Type::Int, Packing::Var),
+            Tag::UInt => (Type::UInt, Packing::Var),
+            _ => return None,
+        })
+    }
+
+    // fewest bytes `len` items can take
+    pub(super) fn min_len(&self, len: usize) -> usize {
+        match self {
+            Packing::Bits => len.div_ceil(8),
+            Packing::Fixed(width) => len.saturating_mul(*width),
+            Packing::Var => len,
+        }
+    }
+}
+
+// whether a list of type `t` with items of kinds `tags` is written packed
+pub(super) fn packs(t: &Type, mut tags: impl Iterator<Item = Tag>) -> bool {
+    Packing::of(t.as_tag()).is_some() && tags.all(|tag| tag == t.as_tag())
+}
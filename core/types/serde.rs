@@ -0,0 +1,644 @@
+//! Bridge between serde's data model and [`Value`].
+//!
+//! [`Serializer`] (and [`to_value`]) maps serde types as follows:
+//!
+//! | serde                               | Value                                                 |
+//! |-------------------------------------|-------------------------------------------------------|
+//! | `bool`                              | `Bool`                                                |
+//! | `i8` `i16` `i32`                    | `Int8` `Int16` `Int32`                                |
+//! | `i64`                               | `Int`                                                 |
+//! | `i128` `u128`                       | `BigInt`                                              |
+//! | `u8` `u16` `u32`                    | `UInt8` `UInt16` `UInt32`                             |
+//! | `u64`                               | `UInt`                                                |
+//! | `f32`                               | `Float32`                                             |
+//! | `f64`                               | `Float`                                               |
+//! | `char` `str`                        | `String`                                              |
+//! | bytes                               | `Bytes`                                               |
+//! | `none` / `some`                     | `Option(Unknown, _)`                                  |
+//! | unit, unit struct                   | `Unit`                                                |
+//! | newtype struct                      | the inner value                                       |
+//! | seq                                 | `List(Unknown, _)`                                    |
+//! | tuple, tuple struct                 | `Tuple`                                               |
+//! | map                                 | `Map((Unknown, Unknown), _)`                          |
+//! | struct `Name`                       | `Struct(TypePtr::from_path("Name"), fields)`          |
+//! | unit variant of `Name`              | `CEnum(TypePtr::from_path("Name"), index)`            |
+//! | newtype variant of `Name`           | `Enum(TypePtr::from_path("Name"), index, value)`      |
+//! | tuple or struct variant of `Name`   | `Enum(TypePtr::from_path("Name"), index, Tuple(_))`   |
+//!
+//! Struct fields and variants are positional, their names are not stored, so
+//! `#[serde(skip_serializing_if)]` and similar attributes that drop fields cannot be read back.
+//!
+//! [`Deserializer`] (and [`from_value`]) reverses the mapping. Struct, tuple, list and set values
+//! are all read as sequences, integers are range-checked into the requested width, and `Alias`
+//! values are unwrapped transparently.
+//!
+//! `Value`, `Type`, `TypePtr`, `ObjectPtr` and `Timestamp` serialize as a newtype struct holding
+//! the encoding of the corresponding `Value`. Through [`Serializer`] and [`Deserializer`] that
+//! is resolved back to the value itself, so they embed in serde types without loss; other
+//! formats see a byte string.
+
+use std::{fmt, mem};
+use ::serde::{ser, de, forward_to_deserialize_any};
+use crate::meta::{Timestamp, ObjectPtr, TypePtr};
+use super::{Value, Type, DecodeOptions};
+
+const VALUE_TOKEN: &str = "$zeon::private::Value";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Error {
+        Error(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Error {
+        Error(msg.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+pub fn to_value<T: ser::Serialize + ?Sized>(val: &T) -> Result<Value> {
+    val.serialize(Serializer)
+}
+
+pub fn from_value<T: de::DeserializeOwned>(val: Value) -> Result<T> {
+    T::deserialize(Deserializer::new(val))
+}
+
+// region: serializer
+
+pub struct Serializer;
+
+pub struct SerializeSeq {
+    tuple: bool,
+    s: Vec<Value>,
+}
+
+pub struct SerializeMap {
+    s: Vec<(Value, Value)>,
+    key: Option<Value>,
+}
+
+pub struct SerializeStruct {
+    ptr: TypePtr,
+    s: Vec<Value>,
+}
+
+pub struct SerializeVariant {
+    ptr: TypePtr,
+    ev: u64,
+    s: Vec<Value>,
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SerializeSeq;
+    type SerializeTuple = SerializeSeq;
+    type SerializeTupleStruct = SerializeSeq;
+    type SerializeTupleVariant = SerializeVariant;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeStruct;
+    type SerializeStructVariant = SerializeVariant;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<Value> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value> {
+        Ok(Value::Int8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value> {
+        Ok(Value::Int16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value> {
+        Ok(Value::Int32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value> {
+        Ok(Value::Int(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value> {
+        Ok(Value::BigInt(v.into()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value> {
+        Ok(Value::UInt8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value> {
+        Ok(Value::UInt16(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value> {
+        Ok(Value::UInt32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value> {
+        Ok(Value::UInt(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value> {
+        Ok(Value::BigInt(v.into()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value> {
+        Ok(Value::from_float32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value> {
+        Ok(Value::from_float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value> {
+        Ok(Value::String(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value> {
+        Ok(Value::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Value> {
+        Ok(Value::Option(Type::Unknown, Box::new(None)))
+    }
+
+    fn serialize_some<T: ser::Serialize + ?Sized>(self, v: &T) -> Result<Value> {
+        Ok(Value::Option(Type::Unknown, Box::new(Some(v.serialize(Serializer)?))))
+    }
+
+    fn serialize_unit(self) -> Result<Value> {
+        Ok(Value::Unit)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value> {
+        Ok(Value::Unit)
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, ev: u32, _variant: &'static str) -> Result<Value> {
+        Ok(Value::CEnum(TypePtr::from_path(name), ev.into()))
+    }
+
+    fn serialize_newtype_struct<T: ser::Serialize + ?Sized>(self, name: &'static str, v: &T) -> Result<Value> {
+        let v = v.serialize(Serializer)?;
+        if name == VALUE_TOKEN {
+            if let Value::Bytes(buf) = &v {
+                return Value::decode_with(buf, DecodeOptions::UNLIMITED).map_err(|err| Error(format!("{:?}", err)));
+            }
+        }
+        Ok(v)
+    }
+
+    fn serialize_newtype_variant<T: ser::Serialize + ?Sized>(self, name: &'static str, ev: u32, _variant: &'static str, v: &T) -> Result<Value> {
+        Ok(Value::Enum(TypePtr::from_path(name), ev.into(), Box::new(v.serialize(Serializer)?)))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeSeq> {
+        Ok(SerializeSeq { tuple: false, s: Vec::with_capacity(len.unwrap_or(0)) })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeSeq> {
+        Ok(SerializeSeq { tuple: true, s: Vec::with_capacity(len) })
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SerializeSeq> {
+        Ok(SerializeSeq { tuple: true, s: Vec::with_capacity(len) })
+    }
+
+    fn serialize_tuple_variant(self, name: &'static str, ev: u32, _variant: &'static str, len: usize) -> Result<SerializeVariant> {
+        Ok(SerializeVariant { ptr: TypePtr::from_path(name), ev: ev.into(), s: Vec::with_capacity(len) })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap> {
+        Ok(SerializeMap { s: Vec::with_capacity(len.unwrap_or(0)), key: None })
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<SerializeStruct> {
+        Ok(SerializeStruct { ptr: TypePtr::from_path(name), s: Vec::with_capacity(len) })
+    }
+
+    fn serialize_struct_variant(self, name: &'static str, ev: u32, _variant: &'static str, len: usize) -> Result<SerializeVariant> {
+        Ok(SerializeVariant { ptr: TypePtr::from_path(name), ev: ev.into(), s: Vec::with_capacity(len) })
+    }
+}
+
+impl SerializeSeq {
+    fn finish(self) -> Value {
+        if self.tuple {
+            Value::Tuple(self.s)
+        } else {
+            Value::List(Type::Unknown, self.s)
+        }
+    }
+}
+
+impl ser::SerializeSeq for SerializeSeq {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: ser::Serialize + ?Sized>(&mut self, v: &T) -> Result<()> {
+        self.s.push(v.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTuple for SerializeSeq {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: ser::Serialize + ?Sized>(&mut self, v: &T) -> Result<()> {
+        self.s.push(v.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeSeq {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: ser::Serialize + ?Sized>(&mut self, v: &T) -> Result<()> {
+        self.s.push(v.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: ser::Serialize + ?Sized>(&mut self, v: &T) -> Result<()> {
+        self.s.push(v.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(Value::Enum(self.ptr, self.ev, Box::new(Value::Tuple(self.s))))
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: ser::Serialize + ?Sized>(&mut self, _key: &'static str, v: &T) -> Result<()> {
+        self.s.push(v.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(Value::Enum(self.ptr, self.ev, Box::new(Value::Tuple(self.s))))
+    }
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T: ser::Serialize + ?Sized>(&mut self, k: &T) -> Result<()> {
+        self.key = Some(k.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ser::Serialize + ?Sized>(&mut self, v: &T) -> Result<()> {
+        let k = self.key.take().ok_or_else(|| Error("map value without key".to_owned()))?;
+        self.s.push((k, v.serialize(Serializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(Value::Map((Type::Unknown, Type::Unknown), self.s))
+    }
+}
+
+impl ser::SerializeStruct for SerializeStruct {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: ser::Serialize + ?Sized>(&mut self, _key: &'static str, v: &T) -> Result<()> {
+        self.s.push(v.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(Value::Struct(self.ptr, self.s))
+    }
+}
+
+// endregion
+
+// region: deserializer
+
+pub struct Deserializer {
+    val: Value,
+}
+
+impl Deserializer {
+    pub fn new(val: Value) -> Deserializer {
+        Deserializer { val }
+    }
+}
+
+struct SeqAccess {
+    iter: std::vec::IntoIter<Value>,
+}
+
+impl<'de> de::SeqAccess<'de> for SeqAccess {
+    type Error = Error;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.iter.next() {
+            Some(v) => seed.deserialize(Deserializer::new(v)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct MapAccess {
+    iter: std::vec::IntoIter<(Value, Value)>,
+    val: Option<Value>,
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess {
+    type Error = Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.iter.next() {
+            Some((k, v)) => {
+                self.val = Some(v);
+                seed.deserialize(Deserializer::new(k)).map(Some)
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let v = self.val.take().ok_or_else(|| Error("map value without key".to_owned()))?;
+        seed.deserialize(Deserializer::new(v))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct EnumAccess {
+    ev: u64,
+    val: Option<Value>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess {
+    type Error = Error;
+    type Variant = VariantAccess;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, VariantAccess)> {
+        let variant = seed.deserialize(Deserializer::new(Value::UInt(self.ev)))?;
+        Ok((variant, VariantAccess { val: self.val }))
+    }
+}
+
+struct VariantAccess {
+    val: Option<Value>,
+}
+
+impl VariantAccess {
+    fn payload(self) -> Result<Value> {
+        self.val.ok_or_else(|| Error("expected enum with payload, found c-enum".to_owned()))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for VariantAccess {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        match self.val {
+            None | Some(Value::Unit) => Ok(()),
+            Some(v) => Err(Error(format!("expected unit variant, found {:?}", v.as_tag()))),
+        }
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(Deserializer::new(self.payload()?))
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(Deserializer::new(self.payload()?), visitor)
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(Deserializer::new(self.payload()?), visitor)
+    }
+}
+
+fn unexpected(val: &Value, exp: &str) -> Error {
+    Error(format!("expected {}, found {:?}", exp, val.as_tag()))
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let mut val = self.val;
+        match &mut val {
+            Value::Unit => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(*b),
+            Value::Int(i) => visitor.visit_i64(*i),
+            Value::UInt(u) => visitor.visit_u64(*u),
+            Value::Float(f) => visitor.visit_f64(f64::from_bits(*f)),
+            Value::String(s) => visitor.visit_string(mem::take(s)),
+            Value::Bytes(b) => visitor.visit_byte_buf(mem::take(b)),
+            Value::Option(_, opt) => match opt.take() {
+                Some(v) => visitor.visit_some(Deserializer::new(v)),
+                None => visitor.visit_none(),
+            },
+            Value::List(_, s) |
+            Value::Set(_, s) |
+            Value::Tuple(s) |
+            Value::Struct(_, s) => visitor.visit_seq(SeqAccess { iter: mem::take(s).into_iter() }),
+            Value::Map(_, s) => visitor.visit_map(MapAccess { iter: mem::take(s).into_iter(), val: None }),
+            Value::Alias(_, v) => Deserializer::new(v.take()).deserialize_any(visitor),
+            Value::CEnum(_, ev) => visitor.visit_enum(EnumAccess { ev: *ev, val: None }),
+            Value::Enum(_, ev, v) => visitor.visit_enum(EnumAccess { ev: *ev, val: Some(v.take()) }),
+            Value::Type(_) | Value::TypePtr(_) | Value::ObjectPtr(_) | Value::Timestamp(_) => {
+                visitor.visit_byte_buf(val.try_encode().map_err(|err| Error(format!("{:?}", err)))?)
+            },
+            Value::UInt8(u) => visitor.visit_u8(*u),
+            Value::UInt16(u) => visitor.visit_u16(*u),
+            Value::UInt32(u) => visitor.visit_u32(*u),
+            Value::Int8(i) => visitor.visit_i8(*i),
+            Value::Int16(i) => visitor.visit_i16(*i),
+            Value::Int32(i) => visitor.visit_i32(*i),
+            Value::Float32(f) => visitor.visit_f32(f32::from_bits(*f)),
+            // beyond 128 bits only the digits are left to hand over
+            Value::BigInt(b) => match (b.to_i128(), b.to_u128()) {
+                (Some(i), _) => visitor.visit_i128(i),
+                (None, Some(u)) => visitor.visit_u128(u),
+                (None, None) => visitor.visit_string(b.to_string()),
+            },
+            Value::Decimal(d) => visitor.visit_string(d.to_string()),
+        }
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let mut val = self.val;
+        match &mut val {
+            Value::Option(_, opt) => match opt.take() {
+                Some(v) => visitor.visit_some(Deserializer::new(v)),
+                None => visitor.visit_none(),
+            },
+            Value::Alias(_, v) => Deserializer::new(v.take()).deserialize_option(visitor),
+            _ => visitor.visit_some(Deserializer::new(val)),
+        }
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        let mut val = self.val;
+        match &mut val {
+            Value::CEnum(_, ev) => visitor.visit_enum(EnumAccess { ev: *ev, val: None }),
+            Value::Enum(_, ev, v) => visitor.visit_enum(EnumAccess { ev: *ev, val: Some(v.take()) }),
+            Value::Alias(_, v) => Deserializer::new(v.take()).deserialize_enum(_name, _variants, visitor),
+            _ => Err(unexpected(&val, "enum")),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value> {
+        if name == VALUE_TOKEN {
+            return visitor.visit_byte_buf(self.val.try_encode().map_err(|err| Error(format!("{:?}", err)))?);
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_unit_struct<V: de::Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+// endregion
+
+// region: zeon types
+
+struct Encoded(Vec<u8>);
+
+impl ser::Serialize for Encoded {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+struct EncodedVisitor;
+
+impl<'de> de::Visitor<'de> for EncodedVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("encoded zeon value")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> std::result::Result<Value, E> {
+        Value::decode(v).map_err(|err| E::custom(format!("{:?}", err)))
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<Value, A::Error> {
+        let mut buf = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(b) = seq.next_element()? {
+            buf.push(b);
+        }
+        self.visit_bytes(&buf)
+    }
+
+    fn visit_newtype_struct<D: de::Deserializer<'de>>(self, deserializer: D) -> std::result::Result<Value, D::Error> {
+        deserializer.deserialize_bytes(self)
+    }
+}
+
+fn serialize_encoded<S: ser::Serializer>(val: &Value, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    let buf = val.try_encode().map_err(|err| <S::Error as ser::Error>::custom(format!("{:?}", err)))?;
+    serializer.serialize_newtype_struct(VALUE_TOKEN, &Encoded(buf))
+}
+
+fn deserialize_encoded<'de, D: de::Deserializer<'de>>(deserializer: D) -> std::result::Result<Value, D::Error> {
+    deserializer.deserialize_newtype_struct(VALUE_TOKEN, EncodedVisitor)
+}
+
+impl ser::Serialize for Value {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serialize_encoded(self, serializer)
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Value {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> std::result::Result<Value, D::Error> {
+        deserialize_encoded(deserializer)
+    }
+}
+
+macro_rules! encoded_impl {
+    ($($ty:ident $into:ident)*) => {$(
+        impl ser::Serialize for $ty {
+            fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                serialize_encoded(&Value::$ty(self.clone()), serializer)
+            }
+        }
+
+        impl<'de> de::Deserialize<'de> for $ty {
+            fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> std::result::Result<$ty, D::Error> {
+                deserialize_encoded(deserializer)?.$into().map_err(|err| <D::Error as de::Error>::custom(format!("{:?}", err)))
+            }
+        }
+    )*};
+}
+
+encoded_impl! {
+    Type try_into_type
+    TypePtr try_into_type_ptr
+    ObjectPtr try_into_object_ptr
+    Timestamp try_into_timestamp
+}
+
+// endregion
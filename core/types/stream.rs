@@ -0,0 +1,122 @@
+use std::io;
+#[cfg(feature = "futures-lite")]
+use futures_lite::{AsyncRead, AsyncReadExt};
+use foundations::usize_casting::*;
+use super::*;
+
+const CHUNK: usize = 8192;
+
+// decodes consecutive values from bytes that arrive in pieces. bytes read past the end of a value
+// stay buffered for the next one, so keep using the same decoder for the same stream. error offsets
+// count from the start of the stream.
+pub struct StreamDecoder {
+    buf: Vec<u8>,
+    // start of the bytes not yet decoded
+    pos: usize,
+    // buffer length below which the pending value cannot be complete
+    need: usize,
+    opts: DecodeOptions,
+    consumed: u64,
+}
+
+impl Default for StreamDecoder {
+    fn default() -> Self {
+        StreamDecoder::new()
+    }
+}
+
+impl StreamDecoder {
+    pub fn new() -> StreamDecoder {
+        StreamDecoder::with_options(DecodeOptions::DEFAULT)
+    }
+
+    pub fn with_options(opts: DecodeOptions) -> StreamDecoder {
+        StreamDecoder { buf: Vec::new(), pos: 0, need: 0, opts, consumed: 0 }
+    }
+
+    // total bytes of the values returned so far
+    pub fn consumed(&self) -> u64 {
+        self.consumed
+    }
+
+    pub fn buffered(&self) -> &[u8] {
+        &self.buf[self.pos..]
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        if self.pos != 0 && self.pos >= self.buf.len() / 2 {
+            self.buf.drain(..self.pos);
+            self.need = self.need.saturating_sub(self.pos);
+            self.pos = 0;
+        }
+        self.buf.extend_from_slice(bytes);
+    }
+
+    // `Ok(None)` means more bytes have to be fed first. the value comes with its encoded length.
+    pub fn next_value(&mut self) -> DecodeResult<Option<(Value, usize)>> {
+        if self.pos == self.buf.len() || self.buf.len() < self.need {
+            return Ok(None);
+        }
+        match Value::decode_prefix_with(&self.buf[self.pos..], self.opts) {
+            Ok((val, len)) => {
+                self.pos += len;
+                self.need = 0;
+                self.consumed += usize_u64(len);
+                Ok(Some((val, len)))
+            },
+            Err(mut err) => {
+                // the reader stopped `have` bytes before the end, wanting `want`
+                if let DecodeErrorKind::TooShort((have, want)) = *err.kind() {
+                    self.need = self.buf.len() - have.min(want) + have.max(want);
+                    return Ok(None);
+                }
+                err.0.offset += u64_usize(self.consumed);
+                Err(err)
+            },
+        }
+    }
+
+    fn eof(&self) -> DecodeResult<Option<(Value, usize)>> {
+        let rest = self.buffered().len();
+        if rest == 0 {
+            return Ok(None);
+        }
+        let mut err = DecodeError::from(DecodeErrorKind::TooShort((rest, self.need - self.pos)));
+        err.0.offset = u64_usize(self.consumed);
+        Err(err)
+    }
+
+    // `Ok(None)` on a clean end of stream, `TooShort` if it ends inside a value
+    pub fn read_from<R: io::Read>(&mut self, r: &mut R) -> DecodeResult<Option<(Value, usize)>> {
+        let mut chunk = [0; CHUNK];
+        loop {
+            if let Some(next) = self.next_value()? {
+                return Ok(Some(next));
+            }
+            let n = match r.read(&mut chunk) {
+                Ok(0) => return self.eof(),
+                Ok(n) => n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            self.feed(&chunk[..n]);
+        }
+    }
+
+    #[cfg(feature = "futures-lite")]
+    pub async fn read_from_async<R: AsyncRead + Unpin>(&mut self, r: &mut R) -> DecodeResult<Option<(Value, usize)>> {
+        let mut chunk = [0; CHUNK];
+        loop {
+            if let Some(next) = self.next_value()? {
+                return Ok(Some(next));
+            }
+            let n = match r.read(&mut chunk).await {
+                Ok(0) => return self.eof(),
+                Ok(n) => n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            self.feed(&chunk[..n]);
+        }
+    }
+}
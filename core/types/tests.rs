@@ -14,6 +14,10 @@ fn test() {
             assert_eq!(&buf, &$exp);
             let v2 = Value::decode(&buf).unwrap();
             assert_eq!($v, v2);
+            let v3 = ValueRef::decode(&buf).unwrap();
+            assert_eq!($v, v3.into_owned());
+            assert!(Value::is_canonical(&buf));
+            assert_eq!($v, $v.to_string().parse::<Value>().unwrap());
         }};
     }
 
@@ -77,5 +81,1445 @@ fn test() {
         09 000000002920d7f6 3161fcd4
         04  0b 03 03 01 00  83 1c 12 00 02
         ")
-    )
+    );
+
+    case!(
+        Value::Tuple(vec![
+            Value::Int8(-2),
+            Value::Int16(-0x0102),
+            Value::Int32(-0x01020304),
+            Value::from_float32(1.5),
+            Value::Type(Type::Tuple(vec![Type::Int8, Type::Int16, Type::Int32, Type::Float32])),
+        ]),
+        hex!("
+        85
+        0d fe
+        0e fefe
+        0f 00 fefdfcfc
+        0f 01 3fc00000
+        06 0b 04 17 18 19 1a
+        ")
+    );
+
+    case!(
+        Value::Tuple(vec![
+            Value::BigInt(BigInt::ZERO),
+            Value::BigInt(BigInt::from(-1i64)),
+            Value::BigInt(BigInt::from(300u64)),
+            Value::BigInt(BigInt::from(1u128 << 127)),
+            Value::Decimal("1.50".parse().unwrap()),
+            Value::Decimal("-15e3".parse().unwrap()),
+            Value::Type(Type::Tuple(vec![Type::BigInt, Type::Decimal])),
+        ]),
+        hex!("
+        87
+        c0
+        c1 03
+        c2 0258
+        cc 11 0100000000000000000000000000000000
+        d4 c2 012c
+        d5 c1 1f
+        06 0b 02 1b 1c
+        ")
+    );
+
+    case!(
+        Value::Tuple(vec![
+            Value::new_set(Type::UInt, vec![Value::UInt(3), Value::UInt(1), Value::UInt(3)]),
+            Value::Type(Type::Set(Box::new(Type::UInt))),
+        ]),
+        hex!("
+        82
+        e2 04 21 23
+        06 1d 04
+        ")
+    );
+
+    let b = Value::Bool;
+    case!(
+        Value::Tuple(vec![
+            Value::List(Type::UInt8, vec![Value::UInt8(1), Value::UInt8(2), Value::UInt8(3)]),
+            Value::List(Type::Int, vec![Value::Int(-1), Value::Int(64), Value::Int(1000)]),
+            Value::List(Type::Bool, vec![b(true), b(false), b(true), b(true), b(false), b(false), b(false), b(false), b(true)]),
+            Value::List(Type::Float, vec![Value::from_float(1.5)]),
+            Value::List(Type::UInt8, vec![Value::UInt8(1), Value::UInt(2)]),
+        ]),
+        hex!("
+        85
+        63 94 01 02 03
+        63 83 01 8001 d00f
+        69 82 b0 80
+        61 85 3ff8000000000000
+        62 14 0a01 22
+        ")
+    );
+}
+
+#[test]
+fn fixed_uint_header() {
+    let v = Value::Tuple(vec![Value::UInt8(0xab), Value::UInt16(0x0102), Value::UInt32(0x01020304)]);
+    let buf = v.clone().encode();
+    assert_eq!(&buf, &hex!("
+        83
+        0a ab
+        0b 0102
+        0c 01020304
+    "));
+    assert_eq!(Value::decode(&buf).unwrap(), v);
+    assert_eq!(ValueRef::decode(&buf).unwrap().into_owned(), v);
+    assert!(Value::is_canonical(&buf));
+}
+
+#[test]
+fn deserialize() {
+    use crate::meta::{CommitPtr, Rev};
+
+    assert!(matches!(
+        Value::UInt(1).try_into_string(),
+        Err(DeserializeError::Mismatch((Tag::String, Tag::UInt)))
+    ));
+    assert!(matches!(
+        CommitAttrType::try_deserialize(Value::CEnum(CommitAttrType::PTR, 5)),
+        Err(DeserializeError::Variant(5))
+    ));
+    assert!(matches!(
+        Rev::try_deserialize(Value::Enum(Rev::PTR, 2, Box::new(Value::Unit))),
+        Err(DeserializeError::Mismatch((Tag::List, Tag::Unit)))
+    ));
+    assert!(matches!(
+        CommitPtr::try_deserialize(Value::Struct(CommitPtr::PTR, vec![Value::Unit])),
+        Err(DeserializeError::Len((3, 1)))
+    ));
+    assert!(matches!(
+        CommitPtr::try_deserialize(Value::Struct(CommitPtr::PTR, vec![
+            Value::Timestamp(Timestamp { secs: 0, nanos: 0 }),
+            Value::ObjectPtr(ObjectPtr { ot: 0, oid: 0 }),
+            Value::UInt(0),
+        ])),
+        Err(DeserializeError::Mismatch((Tag::UInt16, Tag::UInt)))
+    ));
+
+    let ptr = CommitPtr {
+        ts: Timestamp { secs: 0x2937b5bf, nanos: 0x05b242d8 },
+        opr: ObjectPtr { ot: 0x1234, oid: 0xabcdef00 },
+        seq: 0x5678,
+    };
+    assert_eq!(CommitPtr::try_deserialize(ptr.clone().serialize()).unwrap(), ptr);
+}
+
+#[test]
+fn decode_ref() {
+    let buf = Value::Tuple(vec![
+        Value::String("Berylsoft".to_owned()),
+        Value::Bytes(b"(\x00)".to_vec()),
+    ]).encode();
+    let range = buf.as_ptr_range();
+    match ValueRef::decode(&buf).unwrap() {
+        ValueRef::Tuple(s) => match s.as_slice() {
+            [ValueRef::String(s), ValueRef::Bytes(b)] => {
+                assert_eq!(*s, "Berylsoft");
+                assert_eq!(*b, b"(\x00)");
+                assert!(range.contains(&s.as_ptr()));
+                assert!(range.contains(&b.as_ptr()));
+            },
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    }
+
+    let buf = Value::String("\u{2764}".to_owned()).encode();
+    assert!(matches!(ValueRef::decode(&buf[..buf.len() - 1]).map_err(DecodeError::into_kind), Err(DecodeErrorKind::TooShort(_))));
+    let mut buf = buf;
+    buf[1] = 0xff;
+    assert!(matches!(ValueRef::decode(&buf).map_err(DecodeError::into_kind), Err(DecodeErrorKind::Utf8(_))));
+    assert!(matches!(Value::decode(&buf).map_err(DecodeError::into_kind), Err(DecodeErrorKind::Utf8(_))));
+}
+
+#[test]
+fn decode_limits() {
+    let mut deep = Vec::new();
+    for _ in 0..200 {
+        deep.extend_from_slice(&hex!("05 0001"));
+    }
+    deep.push(0x00);
+    assert!(matches!(Value::decode(&deep).map_err(DecodeError::into_kind), Err(DecodeErrorKind::DepthLimit(128))));
+    assert!(matches!(ValueRef::decode(&deep).map_err(DecodeError::into_kind), Err(DecodeErrorKind::DepthLimit(128))));
+    assert!(Value::decode_with(&deep, DecodeOptions { max_depth: 256, ..DecodeOptions::DEFAULT }).is_ok());
+
+    let long_list = hex!("6f ffffffffffffffff 00 00");
+    assert!(matches!(Value::decode(&long_list).map_err(DecodeError::into_kind), Err(DecodeErrorKind::LenLimit((usize::MAX, _)))));
+    assert!(matches!(Value::decode_with(&long_list, DecodeOptions::UNLIMITED).map_err(DecodeError::into_kind), Err(DecodeErrorKind::TooShort(_))));
+    assert!(matches!(ValueRef::decode_with(&long_list, DecodeOptions::UNLIMITED).map_err(DecodeError::into_kind), Err(DecodeErrorKind::TooShort(_))));
+
+    let long_string = hex!("4e ffffffff 00");
+    let opts = DecodeOptions { max_bytes_len: 1024, ..DecodeOptions::DEFAULT };
+    assert!(matches!(Value::decode_with(&long_string, opts).map_err(DecodeError::into_kind), Err(DecodeErrorKind::BytesLimit((0xffffffff, 1024)))));
+    assert!(matches!(ValueRef::decode_with(&long_string, opts).map_err(DecodeError::into_kind), Err(DecodeErrorKind::BytesLimit((0xffffffff, 1024)))));
+
+    let units = Value::List(Type::Unit, vec![Value::Unit; 100]).encode();
+    let opts = DecodeOptions { max_alloc: 1024, ..DecodeOptions::DEFAULT };
+    assert!(matches!(Value::decode_with(&units, opts).map_err(DecodeError::into_kind), Err(DecodeErrorKind::AllocLimit(1024))));
+    assert!(Value::decode(&units).is_ok());
+}
+
+#[test]
+fn canonical() {
+    let strict = DecodeOptions { canonical: true, ..DecodeOptions::DEFAULT };
+
+    macro_rules! case {
+        ($buf:expr, $v:expr, $err:pat) => {{
+            let buf = $buf;
+            assert_eq!(Value::decode(&buf).unwrap(), $v);
+            assert!(matches!(Value::decode_with(&buf, strict).map_err(DecodeError::into_kind), Err($err)));
+            assert!(!Value::is_canonical(&buf));
+        }};
+    }
+
+    case!(hex!("2c 0b"), Value::UInt(11), DecodeErrorKind::NonCanonical((0xC, 11)));
+    case!(hex!("2d 00ff"), Value::UInt(255), DecodeErrorKind::NonCanonical((0xD, 255)));
+    case!(hex!("2e 0000ffff"), Value::UInt(65535), DecodeErrorKind::NonCanonical((0xE, 65535)));
+    case!(hex!("2f 00000000ffffffff"), Value::UInt(0xffffffff), DecodeErrorKind::NonCanonical((0xF, 0xffffffff)));
+    case!(hex!("1c 02"), Value::Int(1), DecodeErrorKind::NonCanonical((0xC, 2)));
+    case!(hex!("4c 00"), Value::String(String::new()), DecodeErrorKind::NonCanonical((0xC, 0)));
+    case!(hex!("9c 01 0001"), Value::CEnum(TypePtr::from_u16(0x0001), 1), DecodeErrorKind::NonCanonical((0xC, 1)));
+    case!(hex!("33 404900"), Value::from_float(50.0), DecodeErrorKind::NonCanonical((3, _)));
+    case!(hex!("31 00"), Value::from_float(0.0), DecodeErrorKind::NonCanonical((1, 0)));
+
+    assert!(Value::is_canonical(&hex!("2c 0c")));
+    assert!(Value::is_canonical(&hex!("2d 0100")));
+    assert!(Value::is_canonical(&hex!("30")));
+    assert!(!Value::is_canonical(&hex!("30 00")));
+
+    // deeper than the default limits let through
+    let mut deep = Vec::new();
+    for _ in 0..200 {
+        deep.extend_from_slice(&hex!("05 0001"));
+    }
+    deep.push(0x00);
+    assert!(Value::decode(&deep).is_err());
+    assert!(Value::is_canonical(&deep));
+    deep.extend_from_slice(&hex!("00"));
+    assert!(!Value::is_canonical(&deep));
+}
+
+// std:meta:commit declares its revs as the std:meta:rev enum and std:types:trait its attributes as
+// structs. declared types are part of the encoding, so this changes the bytes, and the hashes, of
+// every std commit and trait
+#[test]
+fn std_schema() {
+    use foundations::sha3::shake256_once;
+    use crate::{std::init, meta::{Commit, CommitPtr, Rev}};
+
+    let commit = Commit {
+        ptr: CommitPtr {
+            ts: Timestamp { secs: 0x2937b5bf, nanos: 0x05b242d8 },
+            opr: ObjectPtr { ot: 0x1234, oid: 0xabcdef00 },
+            seq: 0,
+        },
+        revs: Vec::new(),
+    }.serialize();
+    let mut before = commit.clone();
+    if let Value::Struct(_, fields) = &mut before {
+        if let Value::Map((_, tv), _) = &mut fields[1] {
+            *tv = Type::Struct(Rev::PTR);
+        }
+    }
+    let (buf, buf_before) = (commit.encode(), before.encode());
+    assert!(buf.ends_with(&hex!("70 0f0007 0e0006")));
+    assert!(buf_before.ends_with(&hex!("70 0f0007 0f0006")));
+    assert_ne!(shake256_once::<32>(&buf), shake256_once::<32>(&buf_before));
+
+    let commit_attr = TypePtr::from_u16(0x0002);
+    let t = init().traits.get(&0x8001).unwrap().clone().serialize();
+    let mut before = t.clone();
+    if let Value::Struct(_, fields) = &mut before {
+        if let Value::List(t, _) = &mut fields[0] {
+            *t = Type::Enum(commit_attr);
+        }
+    }
+    assert!(t.encode().starts_with(&hex!("b4 0005 61 0f0002")));
+    assert!(before.encode().starts_with(&hex!("b4 0005 61 0e0002")));
+}
+
+#[test]
+fn typecheck() {
+    use crate::{std::init, meta::{Commit, CommitPtr, Rev, RevPtr}};
+    use super::typecheck;
+
+    let std = init();
+    let commit = Commit {
+        ptr: CommitPtr {
+            ts: Timestamp { secs: 0x2937b5bf, nanos: 0x05b242d8 },
+            opr: ObjectPtr { ot: 0x1234, oid: 0xabcdef00 },
+            seq: 0,
+        },
+        revs: vec![
+            (RevPtr { object: ObjectPtr { ot: 1, oid: 2 }, trait_type: TypePtr::from_u16(0x8001), attr: 0 }, Rev::Mut(Value::String("name".to_owned()))),
+            (RevPtr { object: ObjectPtr { ot: 1, oid: 2 }, trait_type: TypePtr::from_u16(0x8000), attr: 0 }, Rev::IterSetAdd(vec![Value::TypePtr(TypePtr::from_u16(0x8001))])),
+        ],
+    }.serialize();
+    typecheck(&commit, &Type::Struct(Commit::PTR), &std).unwrap();
+    typecheck(&commit, &Type::Unknown, &std).unwrap();
+
+    let trait_def = std.traits.get(&0x8001).unwrap().clone().serialize();
+    typecheck(&trait_def, &Type::Struct(Trait::PTR), &std).unwrap();
+
+    let mut bad = commit.clone();
+    if let Value::Struct(_, fields) = &mut bad {
+        if let Value::Map(_, revs) = &mut fields[1] {
+            if let Value::Struct(_, rev_ptr) = &mut revs[1].0 {
+                rev_ptr[2] = Value::UInt(0);
+            }
+        }
+    }
+    let err = typecheck(&bad, &Type::Struct(Commit::PTR), &std).unwrap_err();
+    assert!(matches!(err.kind, TypeCheckErrorKind::Mismatch((Tag::UInt8, Tag::UInt))));
+    assert_eq!(err.path_string(), ".revs[1].key.attr");
+
+    let err = typecheck(&Value::CEnum(CommitAttrType::PTR, 5), &Type::CEnum(CommitAttrType::PTR), &std).unwrap_err();
+    assert!(matches!(err.kind, TypeCheckErrorKind::Variant((_, 5))));
+
+    let simple_name = Type::Alias(TypePtr::from_u16(0x0004));
+    typecheck(&Value::String("name".to_owned()), &simple_name, &std).unwrap();
+    typecheck(&Value::Alias(TypePtr::from_u16(0x0004), Box::new(Value::String("name".to_owned()))), &simple_name, &std).unwrap();
+    let err = typecheck(&Value::Int(1), &simple_name, &std).unwrap_err();
+    assert!(matches!(err.kind, TypeCheckErrorKind::Mismatch((Tag::String, Tag::Int))));
+
+    let list = Value::List(Type::Int, vec![Value::Int(1), Value::String("2".to_owned())]);
+    let err = typecheck(&list, &Type::Unknown, &std).unwrap_err();
+    assert!(matches!(err.kind, TypeCheckErrorKind::Mismatch((Tag::Int, Tag::String))));
+    assert_eq!(err.path_string(), "[1]");
+    let err = typecheck(&list, &Type::List(Box::new(Type::UInt)), &std).unwrap_err();
+    assert!(matches!(err.kind, TypeCheckErrorKind::InnerType((Type::UInt, Type::Int))));
+
+    let user = TypePtr::from_path("user:test:name");
+    let user_types: std::collections::BTreeMap<_, _> = [
+        (user, DefType::Alias(Type::Alias(TypePtr::from_u16(0x0004)))),
+    ].into_iter().collect();
+    let err = typecheck(&Value::Alias(user, Box::new(Value::Unit)), &Type::Alias(user), &std).unwrap_err();
+    assert!(matches!(err.kind, TypeCheckErrorKind::Unresolved(_)));
+    typecheck(&Value::String("name".to_owned()), &Type::Alias(user), &(&std, &user_types)).unwrap();
+    let wrapped = Value::Alias(TypePtr::from_u16(0x0004), Box::new(Value::String("name".to_owned())));
+    typecheck(&wrapped, &Type::Alias(user), &(&std, &user_types)).unwrap();
+    typecheck(&Value::Alias(user, Box::new(wrapped)), &Type::Alias(user), &(&std, &user_types)).unwrap();
+    let err = typecheck(&Value::Alias(TypePtr::from_u16(0x0004), Box::new(Value::Unit)), &Type::Alias(user), &(&std, &user_types)).unwrap_err();
+    assert!(matches!(err.kind, TypeCheckErrorKind::Mismatch((Tag::String, Tag::Unit))));
+
+    let looped = TypePtr::from_path("user:test:loop");
+    let user_types: std::collections::BTreeMap<_, _> = [
+        (looped, DefType::Alias(Type::Alias(looped))),
+    ].into_iter().collect();
+    let err = typecheck(&Value::Unit, &Type::Alias(looped), &user_types).unwrap_err();
+    assert!(matches!(err.kind, TypeCheckErrorKind::AliasLoop(_)));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde() {
+    use ::serde::{Serialize, Deserialize};
+    use super::serde::{to_value, from_value};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Rect(u32, u32),
+        Named { name: String, sides: Option<u8> },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Doc {
+        id: u64,
+        delta: i32,
+        tags: Vec<String>,
+        shapes: std::collections::BTreeMap<String, Shape>,
+        raw: Value,
+        at: Timestamp,
+        obj: Option<ObjectPtr>,
+        ty: Type,
+    }
+
+    let doc = Doc {
+        id: 42,
+        delta: -3,
+        tags: vec!["a".to_owned(), "b".to_owned()],
+        shapes: [
+            ("c".to_owned(), Shape::Circle(1.5)),
+            ("e".to_owned(), Shape::Empty),
+            ("n".to_owned(), Shape::Named { name: "tri".to_owned(), sides: Some(3) }),
+            ("r".to_owned(), Shape::Rect(2, 3)),
+        ].into_iter().collect(),
+        raw: Value::Alias(TypePtr::from_u16(0x0001), Box::new(Value::UInt16(7))),
+        at: Timestamp { secs: 1700000000, nanos: 5 },
+        obj: Some(ObjectPtr { ot: 1, oid: 2 }),
+        ty: Type::List(Box::new(Type::Bytes)),
+    };
+
+    let shape = TypePtr::from_path("Shape");
+    let val = to_value(&doc).unwrap();
+    assert_eq!(val, Value::Struct(TypePtr::from_path("Doc"), vec![
+        Value::UInt(42),
+        Value::Int32(-3),
+        Value::List(Type::Unknown, vec![Value::String("a".to_owned()), Value::String("b".to_owned())]),
+        Value::Map((Type::Unknown, Type::Unknown), vec![
+            (Value::String("c".to_owned()), Value::Enum(shape, 1, Box::new(Value::from_float(1.5)))),
+            (Value::String("e".to_owned()), Value::CEnum(shape, 0)),
+            (Value::String("n".to_owned()), Value::Enum(shape, 3, Box::new(Value::Tuple(vec![
+                Value::String("tri".to_owned()),
+                Value::Option(Type::Unknown, Box::new(Some(Value::UInt8(3)))),
+            ])))),
+            (Value::String("r".to_owned()), Value::Enum(shape, 2, Box::new(Value::Tuple(vec![Value::UInt32(2), Value::UInt32(3)])))),
+        ]),
+        Value::Alias(TypePtr::from_u16(0x0001), Box::new(Value::UInt16(7))),
+        Value::Timestamp(Timestamp { secs: 1700000000, nanos: 5 }),
+        Value::Option(Type::Unknown, Box::new(Some(Value::ObjectPtr(ObjectPtr { ot: 1, oid: 2 })))),
+        Value::Type(Type::List(Box::new(Type::Bytes))),
+    ]));
+    assert_eq!(from_value::<Doc>(val.clone()).unwrap(), doc);
+
+    // zeon types pass through unchanged
+    assert_eq!(to_value(&val).unwrap(), val);
+    assert_eq!(from_value::<Value>(val.clone()).unwrap(), val);
+
+    // fixed-width numbers keep their width, as schema-derive declares them
+    assert_eq!(to_value(&(-1i8, -2i16, 1.5f32)).unwrap(), Value::Tuple(vec![Value::Int8(-1), Value::Int16(-2), Value::from_float32(1.5)]));
+    assert_eq!(from_value::<(i8, i16, f32)>(Value::Tuple(vec![Value::Int8(-1), Value::Int16(-2), Value::from_float32(1.5)])).unwrap(), (-1, -2, 1.5));
+
+    // integers are range-checked into the requested width
+    assert_eq!(from_value::<u8>(Value::Int(200)).unwrap(), 200);
+    assert!(from_value::<u8>(Value::UInt(256)).is_err());
+    assert!(from_value::<Timestamp>(Value::UInt(1)).is_err());
+}
+
+#[test]
+fn text() {
+    macro_rules! case {
+        ($s:expr, $v:expr) => {{
+            let v: Value = $v;
+            assert_eq!(v.to_string(), $s);
+            assert_eq!($s.parse::<Value>().unwrap(), v);
+        }};
+    }
+
+    case!("()", Value::Unit);
+    case!("(,)", Value::Tuple(vec![]));
+    case!("(true,)", Value::Tuple(vec![Value::Bool(true)]));
+    case!("(-7i, 7, 7u8, 7u16, 7u32, -7i8, -7i16, -7i32)", Value::Tuple(vec![
+        Value::Int(-7),
+        Value::UInt(7),
+        Value::UInt8(7),
+        Value::UInt16(7),
+        Value::UInt32(7),
+        Value::Int8(-7),
+        Value::Int16(-7),
+        Value::Int32(-7),
+    ]));
+    case!("list<float32>[float32(0.1), float32(-inf), float32(NaN), float32(nan(0x7fc00001))]", Value::List(Type::Float32, vec![
+        Value::from_float32(0.1),
+        Value::from_float32(f32::NEG_INFINITY),
+        Value::from_float32(f32::NAN),
+        Value::Float32(0x7fc00001),
+    ]));
+    case!("list<float>[0.1, -0.0, 1e300, 5e-324, inf, -inf, NaN, nan(0x7ff8000000000001)]", Value::List(Type::Float, vec![
+        Value::from_float(0.1),
+        Value::from_float(-0.0),
+        Value::from_float(1e300),
+        Value::Float(1),
+        Value::from_float(f64::INFINITY),
+        Value::from_float(f64::NEG_INFINITY),
+        Value::from_float(f64::NAN),
+        Value::Float(0x7ff8000000000001),
+    ]));
+    case!(r#""a\"b\\c\n\u{1}é""#, Value::String("a\"b\\c\n\u{1}é".to_owned()));
+    case!(r#"x"00ff28""#, Value::Bytes(vec![0x00, 0xff, 0x28]));
+    case!(
+        r#"map<uint, option<string>>{1: some<string>("a"), 2: none<string>}"#,
+        Value::Map((Type::UInt, Type::Option(Box::new(Type::String))), vec![
+            (Value::UInt(1), Value::Option(Type::String, Box::new(Some(Value::String("a".to_owned()))))),
+            (Value::UInt(2), Value::Option(Type::String, Box::new(None))),
+        ])
+    );
+    case!(
+        "struct std:meta:commit-ptr(timestamp(2023-11-14T22:13:20.000000005Z), object-ptr(1, 2), 0u16)",
+        Value::Struct(TypePtr::from_u16(0x0008), vec![
+            Value::Timestamp(Timestamp { secs: 1700000000 - Timestamp::EPOCH_AFTER_UNIX_EPOCH_SEC, nanos: 5 }),
+            Value::ObjectPtr(ObjectPtr { ot: 1, oid: 2 }),
+            Value::UInt16(0),
+        ])
+    );
+    case!("timestamp(2001-01-01T00:00:00Z)", Value::Timestamp(Timestamp { secs: 0, nanos: 0 }));
+    case!("timestamp(-5, 1000000000)", Value::Timestamp(Timestamp { secs: -5, nanos: 1000000000 }));
+    case!(
+        "enum std:meta:rev(2, list<unknown>[c-enum #fedcba98765432(3), alias std#0100(())])",
+        Value::Enum(TypePtr::from_u16(0x0006), 2, Box::new(Value::List(Type::Unknown, vec![
+            Value::CEnum(TypePtr::Hash(hex!("fedcba98765432")), 3),
+            Value::Alias(TypePtr::from_u16(0x0100), Box::new(Value::Unit)),
+        ])))
+    );
+    case!(
+        "(type(map<alias std:prim:unix-ts, (int, (), (bytes,))>), type-ptr(std:meta:name))",
+        Value::Tuple(vec![
+            Value::Type(Type::Map(
+                Box::new(Type::Alias(TypePtr::from_u16(0x0001))),
+                Box::new(Type::Tuple(vec![Type::Int, Type::Tuple(vec![]), Type::Tuple(vec![Type::Bytes])])),
+            )),
+            Value::TypePtr(TypePtr::from_u16(0x8001)),
+        ])
+    );
+
+    // whitespace is insignificant and types parse on their own
+    assert_eq!(" list < uint8 > [ 1u8 , 2u8 , ] ".parse::<Value>().unwrap(), Value::List(Type::UInt8, vec![Value::UInt8(1), Value::UInt8(2)]));
+    assert_eq!("option<struct std:meta:commit>".parse::<Type>().unwrap(), Type::Option(Box::new(Type::Struct(TypePtr::from_u16(0x0009)))));
+    assert_eq!("std:meta:commit".parse::<TypePtr>().unwrap(), TypePtr::from_u16(0x0009));
+
+    assert_eq!("(1)".parse::<Value>().unwrap_err(), TextError { pos: 2, msg: "single-item tuple needs a trailing `,`" });
+    assert_eq!("struct std:no:such()".parse::<Value>().unwrap_err(), TextError { pos: 7, msg: "invalid type pointer" });
+    assert_eq!("timestamp(2023-02-29T00:00:00Z)".parse::<Value>().unwrap_err(), TextError { pos: 10, msg: "invalid timestamp" });
+    assert_eq!("1 2".parse::<Value>().unwrap_err(), TextError { pos: 2, msg: "unexpected trailing input" });
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json() {
+    use serde_json::json;
+    use crate::meta::{Commit, CommitPtr, RevPtr, Rev};
+    use super::json::*;
+
+    let commit = Commit {
+        ptr: CommitPtr {
+            ts: Timestamp { secs: 721692800, nanos: 5 },
+            opr: ObjectPtr { ot: 1, oid: u64::MAX },
+            seq: 0,
+        },
+        revs: vec![(
+            RevPtr { object: ObjectPtr { ot: 2, oid: 3 }, trait_type: TypePtr::from_u16(0x8001), attr: 0 },
+            Rev::Mut(Value::String("name".to_owned())),
+        )],
+    }.serialize();
+
+    let plain = json!({"struct": ["std:meta:commit", [
+        {"struct": ["std:meta:commit-ptr", [
+            {"timestamp": "2023-11-14T22:13:20.000000005Z"},
+            {"object-ptr": [1, "18446744073709551615"]},
+            {"uint16": 0},
+        ]]},
+        {"map": ["struct std:meta:rev-ptr", "enum std:meta:rev", [[
+            {"struct": ["std:meta:rev-ptr", [
+                {"object-ptr": [2, "3"]},
+                {"type-ptr": "std:meta:name"},
+                {"uint8": 0},
+            ]]},
+            {"enum": ["std:meta:rev", 1, {"string": "name"}]},
+        ]]]},
+    ]]});
+    let pretty = json!({"struct": ["std:meta:commit", {
+        "ptr": {"struct": ["std:meta:commit-ptr", {
+            "ts": {"timestamp": "2023-11-14T22:13:20.000000005Z"},
+            "opr": {"object-ptr": [1, "18446744073709551615"]},
+            "seq": {"uint16": 0},
+        }]},
+        "revs": {"map": ["struct std:meta:rev-ptr", "enum std:meta:rev", [[
+            {"struct": ["std:meta:rev-ptr", {
+                "object": {"object-ptr": [2, "3"]},
+                "trait-type": {"type-ptr": "std:meta:name"},
+                "attr": {"uint8": 0},
+            }]},
+            {"enum": ["std:meta:rev", "mut", {"string": "name"}]},
+        ]]]},
+    }]});
+
+    let std = crate::std::init();
+    assert_eq!(to_json(&commit), plain);
+    assert_eq!(to_json_pretty(&commit, &std), pretty);
+    assert_eq!(from_json(&plain).unwrap(), commit);
+    assert_eq!(from_json_pretty(&pretty, &std).unwrap(), commit);
+    assert!(matches!(from_json(&pretty), Err(JsonError::Unresolved(_))));
+
+    let val = Value::Tuple(vec![
+        Value::Int(i64::MIN),
+        Value::from_float(0.1),
+        Value::from_float(-0.0),
+        Value::from_float(f64::NEG_INFINITY),
+        Value::Float(0x7ff8000000000001),
+        Value::Bytes(b"(\x00)\xff".to_vec()),
+        Value::Option(Type::List(Box::new(Type::Bytes)), Box::new(None)),
+        Value::Map((Type::String, Type::Unit), vec![]),
+        Value::CEnum(TypePtr::from_u16(0x0003), 9),
+        Value::Alias(TypePtr::Hash(hex!("fedcba98765432")), Box::new(Value::Type(Type::Tuple(vec![])))),
+        Value::Timestamp(Timestamp { secs: -5, nanos: 1000000000 }),
+        Value::Int8(-1),
+        Value::Int32(i32::MIN),
+        Value::from_float32(0.1),
+        Value::Float32(0x7fc00001),
+        Value::BigInt(BigInt::from(u128::MAX)),
+        Value::Decimal("-1.50".parse().unwrap()),
+        Value::new_set(Type::Int8, vec![Value::Int8(2), Value::Int8(1)]),
+    ]);
+    let text = serde_json::to_string(&to_json_pretty(&val, &std)).unwrap();
+    assert_eq!(text, r##"{"tuple":[{"int":"-9223372036854775808"},{"float":0.1},{"float":-0.0},{"float":"0xfff0000000000000"},{"float":"0x7ff8000000000001"},{"bytes":"KAAp/w=="},{"option":["list<bytes>",null]},{"map":["string","unit",[]]},{"c-enum":["std:types:commit-attr-type",9]},{"alias":["#fedcba98765432",{"type":"()"}]},{"timestamp":"-5, 1000000000"},{"int8":-1},{"int32":-2147483648},{"float32":0.10000000149011612},{"float32":"0x7fc00001"},{"bigint":"340282366920938463463374607431768211455"},{"decimal":"-1.50"},{"set":["int8",[{"int8":1},{"int8":2}]]}]}"##);
+    assert_eq!(from_json_pretty(&serde_json::from_str(&text).unwrap(), &std).unwrap(), val);
+
+    assert!(matches!(from_json(&json!({"uint": 5})), Err(JsonError::Shape(_))));
+    assert!(matches!(from_json(&json!({"c-enum": ["std:types:commit-attr-type", "nope"]})), Err(JsonError::Unresolved(_))));
+    assert!(matches!(from_json_pretty(&json!({"c-enum": ["std:types:commit-attr-type", "nope"]}), &std), Err(JsonError::Name(_))));
+}
+
+#[test]
+fn encode_errors() {
+    let wide = Type::Tuple(vec![Type::Unit; 256]);
+    assert!(matches!(Value::Type(wide.clone()).try_encode(), Err(EncodeError::TupleTypeLen(256))));
+    assert!(matches!(
+        Value::List(Type::Option(Box::new(wide)), vec![]).try_encode(),
+        Err(EncodeError::TupleTypeLen(256))
+    ));
+    assert!(Value::Type(Type::Tuple(vec![Type::Unit; 255])).try_encode().is_ok());
+
+    let bad = TypePtr::from_u16_unchecked(0xff01);
+    assert!(matches!(Value::TypePtr(bad).try_encode(), Err(EncodeError::StdPtr(0xff01))));
+    assert!(matches!(
+        Value::Tuple(vec![Value::Unit, Value::Struct(TypePtr::from_u16(0x0008), vec![Value::CEnum(bad, 0)])]).try_encode(),
+        Err(EncodeError::StdPtr(0xff01))
+    ));
+    assert!(matches!(Value::Type(Type::Enum(bad)).try_encode(), Err(EncodeError::StdPtr(0xff01))));
+}
+
+#[test]
+fn encode_into() {
+    use foundations::sha3::shake256_once;
+
+    let val = Value::Tuple(vec![
+        Value::String("Berylsoft".to_owned()),
+        Value::List(Type::UInt, vec![Value::UInt(1), Value::UInt(300), Value::UInt(u64::MAX)]),
+        Value::Timestamp(Timestamp { secs: 1, nanos: 2 }),
+    ]);
+    let buf = val.encode();
+    assert_eq!(val.encoded_len().unwrap(), buf.len());
+
+    let mut out = b"prefix".to_vec();
+    val.encode_into(&mut out).unwrap();
+    assert_eq!(&out[..6], b"prefix");
+    assert_eq!(&out[6..], &buf);
+
+    let mut out = Vec::new();
+    val.encode_to(&mut out).unwrap();
+    assert_eq!(out, buf);
+    assert!(matches!(val.encode_to(&mut [0u8; 4][..]), Err(EncodeError::Io(_))));
+
+    let mut out = b"prefix".to_vec();
+    let hash: [u8; 32] = val.encode_into_hashed(&mut out).unwrap();
+    assert_eq!(&out[6..], &buf);
+    assert_eq!(hash, shake256_once(&buf));
+
+    let bad = Value::TypePtr(TypePtr::from_u16_unchecked(0xff00));
+    assert!(matches!(bad.encoded_len(), Err(EncodeError::StdPtr(0xff00))));
+    assert!(matches!(bad.encode_to(Vec::new()), Err(EncodeError::StdPtr(0xff00))));
+}
+
+#[test]
+fn stream() {
+    let vals = [
+        Value::UInt(300),
+        Value::String("Berylsoft".to_owned()),
+        Value::List(Type::UInt8, vec![Value::UInt8(1), Value::UInt8(2)]),
+        Value::Unit,
+    ];
+    let bufs: Vec<Vec<u8>> = vals.iter().map(Value::encode).collect();
+    let all = bufs.concat();
+
+    let (val, len) = Value::decode_prefix(&all).unwrap();
+    assert_eq!(val, vals[0]);
+    assert_eq!(len, bufs[0].len());
+
+    // one byte at a time
+    let mut dec = StreamDecoder::new();
+    let mut out = Vec::new();
+    for b in &all {
+        dec.feed(&[*b]);
+        while let Some((val, len)) = dec.next_value().unwrap() {
+            out.push((val, len));
+        }
+    }
+    assert_eq!(out.iter().map(|(v, _)| v.clone()).collect::<Vec<_>>(), vals);
+    assert_eq!(out.iter().map(|(_, l)| *l).collect::<Vec<_>>(), bufs.iter().map(Vec::len).collect::<Vec<_>>());
+    assert_eq!(dec.consumed(), all.len() as u64);
+    assert!(dec.buffered().is_empty());
+
+    let mut dec = StreamDecoder::new();
+    let mut r = std::io::Cursor::new(&all);
+    for val in &vals {
+        assert_eq!(&dec.read_from(&mut r).unwrap().unwrap().0, val);
+    }
+    assert!(dec.read_from(&mut r).unwrap().is_none());
+
+    // ends inside the string
+    let mut dec = StreamDecoder::new();
+    let mut r = &all[..bufs[0].len() + 3];
+    assert_eq!(dec.read_from(&mut r).unwrap().unwrap().0, vals[0]);
+    assert!(matches!(dec.read_from(&mut r).map_err(DecodeError::into_kind), Err(DecodeErrorKind::TooShort(_))));
+
+    #[cfg(feature = "futures-lite")]
+    futures_lite::future::block_on(async {
+        let mut dec = StreamDecoder::new();
+        let mut r = &all[..];
+        for val in &vals {
+            assert_eq!(&dec.read_from_async(&mut r).await.unwrap().unwrap().0, val);
+        }
+        assert!(dec.read_from_async(&mut r).await.unwrap().is_none());
+    });
+}
+
+#[test]
+fn cursor() {
+    let std = |n| TypePtr::from_u16_unchecked(n);
+    let rev_ptr = |attr| Value::Struct(std(0x0007), vec![
+        Value::ObjectPtr(ObjectPtr { ot: 1, oid: 2 }),
+        Value::TypePtr(std(0x8001)),
+        Value::UInt8(attr),
+    ]);
+    let ts = Value::Timestamp(Timestamp { secs: 1700000000, nanos: 5 });
+    let commit = Value::Struct(std(0x0009), vec![
+        Value::Struct(std(0x0008), vec![ts.clone(), Value::ObjectPtr(ObjectPtr { ot: 3, oid: 4 }), Value::UInt16(0)]),
+        Value::Map((Type::Struct(std(0x0007)), Type::Enum(std(0x0006))), vec![
+            (rev_ptr(0), Value::Enum(std(0x0006), 1, Box::new(Value::String("Berylsoft".to_owned())))),
+            (rev_ptr(1), Value::Enum(std(0x0006), 2, Box::new(Value::List(Type::UInt, vec![Value::UInt(7), Value::UInt(8)])))),
+        ]),
+    ]);
+    let buf = commit.encode();
+
+    let cur = ValueCursor::new(&buf);
+    assert_eq!(cur.raw().unwrap(), &buf[..]);
+    assert_eq!(Value::decode_at(&buf, &[Step::Field(0), Step::Field(0)]).unwrap(), Some(ts.clone()));
+    assert_eq!(Value::raw_at(&buf, &[Step::Field(0), Step::Field(0)]).unwrap().unwrap(), &ts.encode()[..]);
+    let revs = cur.field(1).unwrap().unwrap();
+    assert_eq!(revs.decode().unwrap(), commit_revs(&commit));
+    let rev = revs.key(&rev_ptr(1)).unwrap().unwrap();
+    assert_eq!(rev.decode_ref().unwrap(), ValueRef::decode(&commit_rev(&commit, 1).encode()).unwrap());
+    assert!(revs.key(&rev_ptr(2)).unwrap().is_none());
+    assert!(revs.key(&Value::Set(Type::UInt, vec![Value::UInt(2), Value::UInt(1)])).unwrap().is_none());
+    assert!(cur.field(2).unwrap().is_none());
+    assert!(matches!(revs.index(0).map_err(DecodeError::into_kind), Err(DecodeErrorKind::Step(_))));
+
+    let list = Value::List(Type::String, ["a", "bc", "def"].iter().map(|s| Value::String((*s).to_owned())).collect());
+    let buf = list.encode();
+    let cur = ValueCursor::new(&buf);
+    assert_eq!(cur.index(2).unwrap().unwrap().decode().unwrap(), Value::String("def".to_owned()));
+    assert!(cur.index(3).unwrap().is_none());
+    assert_eq!(cur.index(1).unwrap().unwrap().next().unwrap().raw().unwrap(), &Value::String("def".to_owned()).encode()[..]);
+    let tuple = Value::Tuple(vec![list.clone(), Value::Unit]).encode();
+    assert_eq!(Value::decode_at(&tuple, &[Step::Field(0), Step::Index(0)]).unwrap(), Some(Value::String("a".to_owned())));
+    assert!(matches!(ValueCursor::new(&buf[..buf.len() - 1]).raw().map_err(DecodeError::into_kind), Err(DecodeErrorKind::TooShort(_))));
+}
+
+fn commit_revs(commit: &Value) -> Value {
+    match commit {
+        Value::Struct(_, s) => s[1].clone(),
+        _ => unreachable!(),
+    }
+}
+
+fn commit_rev(commit: &Value, i: usize) -> Value {
+    match &commit_revs(commit) {
+        Value::Map(_, s) => s[i].1.clone(),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn ord() {
+    use std::collections::{BTreeSet, HashSet};
+
+    let nan = Value::Float(0x7ff8000000000000);
+    let mut vals = vec![
+        Value::UInt32(1),
+        Value::Struct(TypePtr::from_u16_unchecked(1), vec![]),
+        Value::String("b".to_owned()),
+        Value::String("ab".to_owned()),
+        Value::Bool(true),
+        Value::Bool(false),
+        Value::Option(Type::UInt, Box::new(Some(Value::UInt(0)))),
+        Value::Option(Type::UInt, Box::new(None)),
+        Value::Int(-1),
+        Value::UInt(300),
+        Value::UInt(5),
+        nan.clone(),
+        Value::from_float(-0.0),
+        Value::from_float(0.0),
+        Value::from_float(f64::NEG_INFINITY),
+        Value::Unit,
+        Value::List(Type::UInt, vec![Value::UInt(1)]),
+        Value::List(Type::UInt, vec![Value::UInt(0), Value::UInt(9)]),
+    ];
+    vals.sort();
+    // kinds are in the order of their encoded headers, and values of a kind in that of their encodings
+    assert!(vals.windows(2).all(|w| w[0].encode() < w[1].encode()));
+    let pos = |v: Value| vals.iter().position(|x| *x == v).unwrap();
+    assert_eq!(pos(Value::Unit), 0);
+    assert!(pos(Value::Bool(false)) < pos(Value::Bool(true)));
+    assert!(pos(Value::Option(Type::UInt, Box::new(None))) < pos(Value::Option(Type::UInt, Box::new(Some(Value::UInt(0))))));
+    assert!(pos(Value::UInt(5)) < pos(Value::UInt(300)));
+    // the length comes first on the wire
+    assert!(pos(Value::String("b".to_owned())) < pos(Value::String("ab".to_owned())));
+    assert!(pos(Value::List(Type::UInt, vec![Value::UInt(1)])) < pos(Value::List(Type::UInt, vec![Value::UInt(0), Value::UInt(9)])));
+
+    // the order is that of the encodings for any two values, of the same kind or not
+    let ptr = TypePtr::from_u16_unchecked(1);
+    vals.extend([
+        Value::Int(5),
+        Value::Int(-7),
+        Value::Int(-1000),
+        Value::Int32(-1),
+        Value::Int32(1),
+        Value::from_float32(-1.5),
+        Value::from_float32(2.0),
+        Value::from_float(1.0),
+        Value::from_float(0.5),
+        Value::Bytes(vec![0xff]),
+        Value::Bytes(vec![0, 0]),
+        Value::new_set(Type::Int, vec![Value::Int(5), Value::Int(-7)]),
+        Value::new_set(Type::Int, vec![Value::Int(-7)]),
+        Value::Map((Type::String, Type::UInt), vec![(Value::String("b".to_owned()), Value::UInt(1))]),
+        Value::Struct(ptr, vec![Value::String("ab".to_owned())]),
+        Value::Struct(ptr, vec![Value::String("b".to_owned())]),
+        Value::Type(Type::Tuple(vec![Type::UInt])),
+        Value::Type(Type::Tuple(vec![Type::Int, Type::Int])),
+        Value::Type(Type::Map(Box::new(Type::Struct(ptr)), Box::new(Type::Unit))),
+        Value::TypePtr(TypePtr::Hash(hex!("00112233445566"))),
+        Value::TypePtr(ptr),
+    ]);
+    for a in &vals {
+        for b in &vals {
+            assert_eq!(a.cmp(b), a.encode().cmp(&b.encode()), "{:?} {:?}", a, b);
+            if let (Value::Type(ta), Value::Type(tb)) = (a, b) {
+                assert_eq!(ta.cmp(tb), a.encode().cmp(&b.encode()));
+            }
+        }
+    }
+
+    assert!(Type::Unit < Type::UInt && Type::List(Box::new(Type::Int)) < Type::List(Box::new(Type::UInt)));
+    assert!(Type::Tuple(vec![Type::UInt]) < Type::Tuple(vec![Type::Int, Type::Int]));
+    let dup = [Value::UInt(1), nan.clone(), Value::UInt(1), nan.clone(), Value::from_float(0.0)];
+    assert_eq!(dup.iter().collect::<BTreeSet<_>>().len(), 3);
+    assert_eq!(dup.iter().collect::<HashSet<_>>().len(), 3);
+}
+
+#[test]
+fn canonicalize() {
+    let s = |s: &str| Value::String(s.to_owned());
+    let map = |s: Vec<(Value, Value)>| Value::Map((Type::String, Type::UInt), s);
+    let mut a = Value::Tuple(vec![map(vec![(s("bb"), Value::UInt(1)), (s("c"), Value::UInt(2)), (s("a"), Value::UInt(3))])]);
+    let mut b = Value::Tuple(vec![map(vec![(s("a"), Value::UInt(3)), (s("bb"), Value::UInt(1)), (s("c"), Value::UInt(2))])]);
+    assert_ne!(a.encode(), b.encode());
+    a.canonicalize(DupKeys::Reject).unwrap();
+    b.canonicalize(DupKeys::Reject).unwrap();
+    // shorter strings encode lower
+    assert_eq!(a, Value::Tuple(vec![map(vec![(s("a"), Value::UInt(3)), (s("c"), Value::UInt(2)), (s("bb"), Value::UInt(1))])]));
+    assert_eq!(a.encode(), b.encode());
+
+    let strict = DecodeOptions { sorted_keys: true, ..DecodeOptions::DEFAULT };
+    assert!(Value::decode_with(&a.encode(), strict).is_ok());
+    let unsorted = map(vec![(s("b"), Value::UInt(1)), (s("a"), Value::UInt(2))]).encode();
+    assert!(Value::decode(&unsorted).is_ok());
+    assert!(matches!(Value::decode_with(&unsorted, strict).map_err(DecodeError::into_kind), Err(DecodeErrorKind::KeyOrder(1))));
+    assert!(matches!(ValueRef::decode_with(&unsorted, strict).map_err(DecodeError::into_kind), Err(DecodeErrorKind::KeyOrder(1))));
+
+    let mut dup = map(vec![(s("a"), Value::UInt(1)), (s("b"), Value::UInt(2)), (s("a"), Value::UInt(3))]);
+    assert!(matches!(dup.clone().canonicalize(DupKeys::Reject), Err(EncodeError::DupKey(k)) if k == s("a")));
+    assert!(matches!(Value::decode_with(&dup.encode(), strict).map_err(DecodeError::into_kind), Err(DecodeErrorKind::KeyOrder(_))));
+    dup.canonicalize(DupKeys::KeepLast).unwrap();
+    assert_eq!(dup, map(vec![(s("a"), Value::UInt(3)), (s("b"), Value::UInt(2))]));
+
+    let mut rev = crate::meta::Rev::IterSetAdd(vec![Value::UInt(2), Value::UInt(1), Value::UInt(2)]).serialize();
+    rev.canonicalize(DupKeys::Reject).unwrap();
+    assert_eq!(rev.deserialize_into::<crate::meta::Rev>(), crate::meta::Rev::IterSetAdd(vec![Value::UInt(1), Value::UInt(2)]));
+    let mut rev = crate::meta::Rev::IterListAdd(vec![Value::UInt(2), Value::UInt(2)]).serialize();
+    rev.canonicalize(DupKeys::Reject).unwrap();
+    assert_eq!(rev.deserialize_into::<crate::meta::Rev>(), crate::meta::Rev::IterListAdd(vec![Value::UInt(2), Value::UInt(2)]));
+}
+
+#[test]
+fn diff() {
+    use crate::meta::{Patch, PatchEdit, PatchStep, PatchOp};
+
+    let s = |s: &str| Value::String(s.to_owned());
+    let list = |s: Vec<Value>| Value::List(Type::UInt, s);
+    let map = |s: Vec<(Value, Value)>| Value::Map((Type::String, Type::Unknown), s);
+    let ptr = TypePtr::from_u16_unchecked(0x8000);
+    let a = Value::Struct(ptr, vec![
+        s("a"),
+        list(vec![Value::UInt(1), Value::UInt(2), Value::UInt(3), Value::UInt(4)]),
+        map(vec![(s("x"), Value::UInt(1)), (s("y"), Value::Option(Type::UInt, Box::new(Some(Value::UInt(1))))), (s("z"), Value::Unit)]),
+    ]);
+    let b = Value::Struct(ptr, vec![
+        s("a"),
+        list(vec![Value::UInt(1), Value::UInt(9), Value::UInt(8), Value::UInt(3), Value::UInt(4)]),
+        map(vec![(s("x"), Value::UInt(1)), (s("y"), Value::Option(Type::UInt, Box::new(Some(Value::UInt(2))))), (s("w"), Value::Bool(true))]),
+    ]);
+    let patch = Value::diff(&a, &b);
+    let edit = |path: Vec<PatchStep>, op| PatchEdit { path, op };
+    assert_eq!(patch.edits, [
+        edit(vec![PatchStep::Field(1), PatchStep::Index(1)], PatchOp::Replace(Value::UInt(9))),
+        edit(vec![PatchStep::Field(1), PatchStep::Index(2)], PatchOp::Insert(Value::UInt(8))),
+        edit(vec![PatchStep::Field(2), PatchStep::Key(s("y")), PatchStep::Inner(())], PatchOp::Replace(Value::UInt(2))),
+        edit(vec![PatchStep::Field(2), PatchStep::Key(s("z"))], PatchOp::Remove(())),
+        edit(vec![PatchStep::Field(2), PatchStep::Key(s("w"))], PatchOp::Insert(Value::Bool(true))),
+    ]);
+    let mut c = a.clone();
+    c.apply_patch(&patch).unwrap();
+    assert_eq!(c, b);
+
+    // stored and sent as a value
+    let encoded = patch.clone().serialize().encode();
+    assert_eq!(Value::decode(&encoded).unwrap().deserialize_into::<Patch>(), patch);
+
+    let mut c = b.clone();
+    c.apply_patch(&Value::diff(&b, &a)).unwrap();
+    assert_eq!(c, a);
+    assert!(Value::diff(&a, &a).edits.is_empty());
+    let reordered = map(vec![(s("y"), Value::Unit), (s("x"), Value::Unit)]);
+    let patch = Value::diff(&map(vec![(s("x"), Value::Unit), (s("y"), Value::Unit)]), &reordered);
+    assert_eq!(patch.edits, [edit(vec![], PatchOp::Replace(reordered))]);
+
+    let mut c = a.clone();
+    let bad = Patch { edits: vec![edit(vec![PatchStep::Field(3), PatchStep::Index(0)], PatchOp::Remove(()))] };
+    assert!(matches!(c.apply_patch(&bad), Err(PatchError::Path(0))));
+    let bad = Patch { edits: vec![edit(vec![PatchStep::Field(1), PatchStep::Index(9)], PatchOp::Insert(Value::Unit))] };
+    assert!(matches!(c.apply_patch(&bad), Err(PatchError::Op(0))));
+}
+
+#[test]
+fn visit() {
+    struct ObjectPtrs(Vec<ObjectPtr>);
+
+    impl Visit for ObjectPtrs {
+        fn visit_object_ptr(&mut self, ptr: &ObjectPtr) {
+            self.0.push(*ptr);
+        }
+    }
+
+    struct Rename(TypePtr, TypePtr);
+
+    impl VisitMut for Rename {
+        fn visit_type_ptr_mut(&mut self, ptr: &mut TypePtr) {
+            if *ptr == self.0 {
+                *ptr = self.1;
+            }
+        }
+    }
+
+    // counts strings and replaces them with their length, without descending into maps
+    struct Lengths(usize);
+
+    impl Fold for Lengths {
+        fn fold_value(&mut self, val: Value) -> Value {
+            match &val {
+                Value::String(s) => {
+                    self.0 += 1;
+                    Value::UInt(s.len() as u64)
+                },
+                Value::Map(_, _) => val,
+                _ => walk_fold_value(self, val),
+            }
+        }
+    }
+
+    let (old, new) = (TypePtr::from_u16_unchecked(0x8000), TypePtr::from_u16_unchecked(0x8001));
+    let op = |oid| Value::ObjectPtr(ObjectPtr { ot: 1, oid });
+    let val = Value::Struct(old, vec![
+        op(1),
+        Value::List(Type::Alias(old), vec![Value::Alias(old, Box::new(op(2)))]),
+        Value::Map((Type::String, Type::Unknown), vec![(Value::String("a".to_owned()), op(3))]),
+        Value::Option(Type::String, Box::new(Some(Value::String("bc".to_owned())))),
+        Value::Type(Type::Map(Box::new(Type::Enum(old)), Box::new(Type::UInt))),
+    ]);
+
+    let mut ptrs = ObjectPtrs(Vec::new());
+    ptrs.visit_value(&val);
+    assert_eq!(ptrs.0.iter().map(|p| p.oid).collect::<Vec<_>>(), [1, 2, 3]);
+
+    let mut renamed = val.clone();
+    Rename(old, new).visit_value_mut(&mut renamed);
+    assert_eq!(renamed, Value::Struct(new, vec![
+        op(1),
+        Value::List(Type::Alias(new), vec![Value::Alias(new, Box::new(op(2)))]),
+        Value::Map((Type::String, Type::Unknown), vec![(Value::String("a".to_owned()), op(3))]),
+        Value::Option(Type::String, Box::new(Some(Value::String("bc".to_owned())))),
+        Value::Type(Type::Map(Box::new(Type::Enum(new)), Box::new(Type::UInt))),
+    ]));
+
+    let mut lengths = Lengths(0);
+    let folded = lengths.fold_value(val);
+    assert_eq!(lengths.0, 1);
+    match &folded {
+        Value::Struct(_, s) => assert_eq!(s[3], Value::Option(Type::String, Box::new(Some(Value::UInt(2))))),
+        _ => unreachable!(),
+    }
+
+    // moves one object past the others, so set items have to be sorted and deduped again
+    struct Move(u64, u64);
+
+    impl VisitMut for Move {
+        fn visit_object_ptr_mut(&mut self, ptr: &mut ObjectPtr) {
+            if ptr.oid == self.0 {
+                ptr.oid = self.1;
+            }
+        }
+    }
+
+    impl Fold for Move {
+        fn fold_object_ptr(&mut self, mut ptr: ObjectPtr) -> ObjectPtr {
+            self.visit_object_ptr_mut(&mut ptr);
+            ptr
+        }
+    }
+
+    let set = Value::new_set(Type::ObjectPtr, vec![op(1), op(2), op(3)]);
+    let folded = Move(1, 4).fold_value(set.clone());
+    assert_eq!(folded, Value::Set(Type::ObjectPtr, vec![op(2), op(3), op(4)]));
+    assert_eq!(Value::decode(&folded.encode()).unwrap(), folded);
+    assert_eq!(Move(1, 3).fold_value(set.clone()), Value::Set(Type::ObjectPtr, vec![op(2), op(3)]));
+    let mut moved = set;
+    Move(1, 4).visit_value_mut(&mut moved);
+    assert_eq!(moved, folded);
+}
+
+#[test]
+fn macros() {
+    let rev = TypePtr::from_u16(0x0006);
+    let hash = TypePtr::Hash(hex!("fedcba98765432"));
+    assert_eq!(crate::ty!(map[uint, list[string]]), Type::Map(Box::new(Type::UInt), Box::new(Type::List(Box::new(Type::String)))));
+    assert_eq!(crate::ty!((int, option[type-ptr],)), Type::Tuple(vec![Type::Int, Type::Option(Box::new(Type::TypePtr))]));
+    assert_eq!(crate::ty!(()), Type::Tuple(vec![]));
+    assert_eq!(crate::ty!(struct std:"meta":"commit-ptr"), Type::Struct(TypePtr::from_u16(0x0008)));
+    assert_eq!(crate::ty!(list[alias {hash}]), Type::List(Box::new(Type::Alias(hash))));
+
+    let s = |s: &str| Value::String(s.to_owned());
+    assert_eq!(crate::value!(()), Value::Unit);
+    assert_eq!(crate::value!((,)), Value::Tuple(vec![]));
+    assert_eq!(crate::value!((1, -2, 3u8, 4u16, 5u32, 6u64, -7i64, 1.5, true, "a", b"\0")), Value::Tuple(vec![
+        Value::UInt(1), Value::Int(-2), Value::UInt8(3), Value::UInt16(4), Value::UInt32(5), Value::UInt(6), Value::Int(-7),
+        Value::from_float(1.5), Value::Bool(true), s("a"), Value::Bytes(vec![0]),
+    ]));
+    assert_eq!(crate::value!((none[uint], some[list[uint]](list[uint] [1, 2,]))), Value::Tuple(vec![
+        Value::Option(Type::UInt, Box::new(None)),
+        Value::Option(Type::List(Box::new(Type::UInt)), Box::new(Some(Value::List(Type::UInt, vec![Value::UInt(1), Value::UInt(2)])))),
+    ]));
+    assert_eq!(
+        crate::value!(map[string, map[uint, unit]] {"a" => map[uint, unit] {1 => (), 2 => ()}, "b" => map[uint, unit] {}}),
+        Value::Map((Type::String, Type::Map(Box::new(Type::UInt), Box::new(Type::Unit))), vec![
+            (s("a"), Value::Map((Type::UInt, Type::Unit), vec![(Value::UInt(1), Value::Unit), (Value::UInt(2), Value::Unit)])),
+            (s("b"), Value::Map((Type::UInt, Type::Unit), vec![])),
+        ]),
+    );
+    let secs = 1700000000;
+    assert_eq!(
+        crate::value!(struct std:"meta":"commit" (
+            struct std:"meta":"commit-ptr" (timestamp(secs, 5), object-ptr(1, 2), 0u16),
+            map[struct std:"meta":"rev-ptr", enum std:"meta":"rev"] {
+                struct std:"meta":"rev-ptr" (object-ptr(1, 2), type-ptr(std:"types":"trait"), 0u8) => enum std:"meta":"rev" (1, "a"),
+            },
+        )),
+        Value::Struct(TypePtr::from_u16(0x0009), vec![
+            Value::Struct(TypePtr::from_u16(0x0008), vec![
+                Value::Timestamp(Timestamp { secs, nanos: 5 }),
+                Value::ObjectPtr(ObjectPtr { ot: 1, oid: 2 }),
+                Value::UInt16(0),
+            ]),
+            Value::Map((Type::Struct(TypePtr::from_u16(0x0007)), Type::Enum(rev)), vec![(
+                Value::Struct(TypePtr::from_u16(0x0007), vec![
+                    Value::ObjectPtr(ObjectPtr { ot: 1, oid: 2 }),
+                    Value::TypePtr(TypePtr::from_u16(0x0005)),
+                    Value::UInt8(0),
+                ]),
+                Value::Enum(rev, 1, Box::new(s("a"))),
+            )]),
+        ]),
+    );
+    assert_eq!(
+        crate::value!((alias {hash} ({s("x")}), c-enum std:"types":"commit-attr-type" (2), type(option[bytes]))),
+        Value::Tuple(vec![
+            Value::Alias(hash, Box::new(s("x"))),
+            Value::CEnum(TypePtr::from_u16(0x0003), 2),
+            Value::Type(Type::Option(Box::new(Type::Bytes))),
+        ]),
+    );
+}
+
+#[test]
+fn convert() {
+    use std::collections::BTreeMap;
+    use crate::{meta::CommitPtr, std::codegen::prim::SimpleName};
+
+    let ptrs = vec![
+        CommitPtr { ts: Timestamp { secs: 1, nanos: 2 }, opr: ObjectPtr { ot: 3, oid: 4 }, seq: 5 },
+        CommitPtr { ts: Timestamp { secs: 6, nanos: 7 }, opr: ObjectPtr { ot: 8, oid: 9 }, seq: 10 },
+    ];
+    let val = ptrs.clone().into_value();
+    assert_eq!(Vec::<CommitPtr>::ty(), Type::List(Box::new(Type::Struct(CommitPtr::PTR))));
+    assert_eq!(val, Value::List(Type::Struct(CommitPtr::PTR), ptrs.clone().into_iter().map(Schema::serialize).collect()));
+    assert_eq!(Vec::<CommitPtr>::from_value(val), ptrs);
+    // the fields of a commit pointer under the pointer of another struct
+    let other = Value::Struct(TypePtr::from_u16_unchecked(0x0007), ptrs[0].clone().serialize().try_into_struct().unwrap());
+    assert!(matches!(CommitPtr::try_deserialize(other), Err(DeserializeError::Ptr((expected, _))) if expected == CommitPtr::PTR));
+
+    // aliases are their inner values
+    let names = vec![SimpleName("a".to_owned()), SimpleName("b".to_owned())];
+    let val = names.clone().into_value();
+    assert_eq!(val, Value::List(Type::String, vec![Value::String("a".to_owned()), Value::String("b".to_owned())]));
+    assert_eq!(val.as_type(), Vec::<SimpleName>::ty());
+    assert_eq!(Vec::<SimpleName>::from_value(val), names);
+
+    let map: BTreeMap<String, Option<(u64, i64)>> = [("a".to_owned(), Some((1, -1))), ("b".to_owned(), None)].into_iter().collect();
+    let val = map.clone().into_value();
+    assert_eq!(val.as_type(), <BTreeMap<String, Option<(u64, i64)>>>::ty());
+    assert_eq!(BTreeMap::from_value(val.clone()), map);
+    assert_eq!(try_from_map_value::<String, Option<(u64, i64)>, Vec<_>>(val).unwrap(), map.into_iter().collect::<Vec<_>>());
+
+    let bad = Value::Tuple(vec![Value::UInt(1)]);
+    assert!(matches!(<(u64, u64)>::try_from_value(bad.clone()), Err(DeserializeError::Len((2, 1)))));
+    assert!(matches!(<(String,)>::try_from_value(bad), Err(DeserializeError::Mismatch((Tag::String, Tag::UInt)))));
+    assert_eq!(Value::from_value(Value::Unit), Value::Unit);
+    assert_eq!(Value::ty(), Type::Unknown);
+}
+
+#[test]
+fn big() {
+    let max = BigInt::from(u128::MAX);
+    assert_eq!(max.to_string(), "340282366920938463463374607431768211455");
+    assert_eq!(max.to_string().parse::<BigInt>().unwrap(), max);
+    assert_eq!((max.to_i128(), max.to_u128()), (None, Some(u128::MAX)));
+    assert_eq!(BigInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+    let digits = "-123456789012345678901234567890123456789012345678901234567890";
+    assert_eq!(digits.parse::<BigInt>().unwrap().to_string(), digits);
+    assert_eq!("-0".parse::<BigInt>().unwrap(), BigInt::ZERO);
+    assert!("1_000".parse::<BigInt>().is_err() && "".parse::<BigInt>().is_err());
+    assert!(BigInt::from(-256i64) < BigInt::from(-1i64) && BigInt::from(-1i64) < BigInt::ZERO && BigInt::ZERO < BigInt::from(256u64));
+
+    for (s, mantissa, scale, back) in [
+        ("1.50", 150, 2, "1.50"),
+        ("0.0015", 15, 4, "0.0015"),
+        ("-0.00", 0, 2, "0.00"),
+        ("12.5e1", 125, 0, "125"),
+        ("1e-30", 1, 30, "1e-30"),
+        ("7E+2", 7, -2, "7e2"),
+    ] {
+        let d: Decimal = s.parse().unwrap();
+        assert_eq!(d, Decimal { mantissa: BigInt::from(mantissa as i64), scale });
+        assert_eq!(d.to_string(), back);
+    }
+    assert!("1.".parse::<Decimal>().is_err() && ".5".parse::<Decimal>().is_err());
+
+    // negative zero and leading zero bytes only decode leniently
+    let strict = DecodeOptions { canonical: true, ..DecodeOptions::DEFAULT };
+    for (buf, v) in [(&hex!("c1 01")[..], BigInt::ZERO), (&hex!("c2 0003")[..], BigInt::from(-1i64))] {
+        assert_eq!(Value::decode(buf).unwrap(), Value::BigInt(v));
+        assert!(matches!(Value::decode_with(buf, strict).map_err(DecodeError::into_kind), Err(DecodeErrorKind::NonCanonical(_))));
+    }
+    assert!(matches!(Value::decode(&hex!("d4 20")).map_err(DecodeError::into_kind), Err(DecodeErrorKind::Mantissa(0x20))));
+
+    #[cfg(feature = "num-bigint")]
+    {
+        let n: num_bigint::BigInt = digits.parse().unwrap();
+        assert_eq!(BigInt::from(n.clone()), digits.parse().unwrap());
+        assert_eq!(num_bigint::BigInt::from(BigInt::from(n.clone())), n);
+    }
+    #[cfg(feature = "bigdecimal")]
+    {
+        let d: bigdecimal::BigDecimal = "-1.50".parse().unwrap();
+        assert_eq!(Decimal::from(d.clone()), "-1.50".parse().unwrap());
+        assert_eq!(bigdecimal::BigDecimal::from(Decimal::from(d.clone())), d);
+    }
+}
+
+#[test]
+fn set() {
+    let s = |s: &str| Value::String(s.to_owned());
+    let set = Value::new_set(Type::String, vec![s("b"), s("a"), s("b")]);
+    assert_eq!(set, Value::Set(Type::String, vec![s("a"), s("b")]));
+    assert_eq!(set.to_string(), r#"set<string>["a", "b"]"#);
+    assert_eq!(r#"set<string>["b", "a", "b"]"#.parse::<Value>().unwrap(), set);
+    assert_eq!(crate::value!(set[string] ["b", "a"]), set);
+
+    // out of order or repeated items are an error both ways, whatever the decode options
+    let unsorted = Value::Set(Type::UInt, vec![Value::UInt(3), Value::UInt(1)]);
+    assert!(matches!(unsorted.try_encode(), Err(EncodeError::SetOrder(1))));
+    // items of a set in a set are checked against their own neighbours only
+    let sets = |inner: Value| Value::Set(Type::Set(Box::new(Type::UInt)), vec![Value::Set(Type::UInt, vec![Value::UInt(0)]), inner]);
+    let nested = sets(Value::Set(Type::UInt, vec![Value::UInt(1), Value::UInt(2)]));
+    assert_eq!(Value::decode(&nested.encode()).unwrap(), nested);
+    assert_eq!(nested.encoded_len().unwrap(), nested.encode().len());
+    assert!(matches!(sets(unsorted.clone()).try_encode(), Err(EncodeError::SetOrder(1))));
+    assert!(matches!(sets(unsorted.clone()).encoded_len(), Err(EncodeError::SetOrder(1))));
+    // what was written before the error is taken back
+    let mut buf = vec![0x20];
+    assert!(sets(unsorted.clone()).encode_into(&mut buf).is_err());
+    assert!(sets(unsorted.clone()).encode_into_hashed::<32>(&mut buf).is_err());
+    assert_eq!(buf, [0x20]);
+    assert!(matches!(Value::decode(&hex!("e2 04 23 21")).map_err(DecodeError::into_kind), Err(DecodeErrorKind::SetOrder(1))));
+    assert!(matches!(ValueRef::decode(&hex!("e3 04 21 23 23")).map_err(DecodeError::into_kind), Err(DecodeErrorKind::SetOrder(2))));
+    let mut v = unsorted.clone();
+    v.canonicalize(DupKeys::Reject).unwrap();
+    assert_eq!(v.encode(), hex!("e2 04 21 23"));
+
+    let native: std::collections::BTreeSet<u64> = [3, 1].into_iter().collect();
+    assert_eq!(native.clone().into_value(), v);
+    assert_eq!(std::collections::BTreeSet::<u64>::from_value(v.clone()), native);
+    assert!(std::collections::BTreeSet::<u64>::try_from_value(Value::List(Type::UInt, vec![])).is_err());
+}
+
+#[test]
+fn packed() {
+    let bytes = Value::List(Type::UInt8, (0..=255).map(Value::UInt8).collect());
+    let buf = bytes.encode();
+    assert_eq!(buf.len(), 4 + 256);
+    assert_eq!(Value::decode(&buf).unwrap(), bytes);
+    assert_eq!(ValueRef::decode(&buf).unwrap().into_owned(), bytes);
+    let floats = Value::List(Type::Float32, vec![Value::from_float32(0.1); 100]);
+    assert_eq!(floats.encode().len(), 3 + 4 * 100);
+    let ints = Value::Tuple(vec![Value::List(Type::Int, vec![Value::Int(i64::MIN), Value::Int(i64::MAX)]), Value::Unit]);
+    let buf = ints.encode();
+    assert_eq!(Value::decode(&buf).unwrap(), ints);
+    assert_eq!(ValueCursor::new(&buf).field(1).unwrap().unwrap().decode().unwrap(), Value::Unit);
+    let item = ValueCursor::new(&buf).field(0).unwrap().unwrap().index(1).unwrap().unwrap();
+    assert_eq!(item.decode().unwrap(), Value::Int(i64::MAX));
+    assert!(matches!(item.raw().map_err(DecodeError::into_kind), Err(DecodeErrorKind::PackedItem(0x84))));
+    assert!(Value::decode_at(&buf, &[Step::Field(0), Step::Index(2)]).unwrap().is_none());
+
+    // items of packed lists are stepped to by their position alone
+    let varints = Value::List(Type::Int, [0, -1, 300, -300, i64::MIN, i64::MAX].into_iter().map(Value::Int).collect());
+    let bools = Value::List(Type::Bool, (0..20).map(|i| Value::Bool(i % 3 == 0)).collect());
+    for list in [&bytes, &varints, &bools] {
+        let buf = list.encode();
+        let Value::List(_, items) = list else { unreachable!() };
+        let cur = ValueCursor::new(&buf);
+        for (i, item) in items.iter().enumerate() {
+            assert_eq!(cur.index(i).unwrap().unwrap().decode().unwrap(), *item);
+            assert_eq!(cur.index(i).unwrap().unwrap().decode_ref().unwrap().into_owned(), *item);
+        }
+        assert!(cur.index(items.len()).unwrap().is_none());
+    }
+
+    // what `encode` would not write only decodes leniently
+    let strict = DecodeOptions { canonical: true, ..DecodeOptions::DEFAULT };
+    for buf in [&hex!("61 14 0a 01")[..], &hex!("61 82 c0")[..], &hex!("61 84 8000")[..]] {
+        assert!(Value::decode(buf).is_ok());
+        assert!(Value::decode_with(buf, strict).is_err());
+    }
+    assert!(matches!(Value::decode(&hex!("61 84 ffffffffffffffffff02")).map_err(DecodeError::into_kind), Err(DecodeErrorKind::Varint(10))));
+    assert!(matches!(Value::decode(&hex!("61 86 00")).map_err(DecodeError::into_kind), Err(DecodeErrorKind::PackedTag(0x86))));
+    assert!(matches!(Value::decode(&hex!("6c ff 94 00")).map_err(DecodeError::into_kind), Err(DecodeErrorKind::TooShort(_))));
+}
+
+#[test]
+fn typed() {
+    use crate::{std::init, meta::{Commit, CommitPtr, Rev, RevPtr}};
+
+    let std = init();
+    let roundtrip = |v: &Value, t: &Type| {
+        let buf = v.encode_typed(t, &std);
+        assert_eq!(Value::decode_typed(&buf, t, &std).unwrap(), *v);
+        buf
+    };
+
+    // the bytes a commit saves over the self-describing encoding are mostly headers and pointers
+    let commit = Commit {
+        ptr: CommitPtr {
+            ts: Timestamp { secs: 0x2937b5bf, nanos: 0x05b242d8 },
+            opr: ObjectPtr { ot: 0x1234, oid: 0xabcdef00 },
+            seq: 0,
+        },
+        revs: (0..16).map(|i| (
+            RevPtr { object: ObjectPtr { ot: 1, oid: i }, trait_type: TypePtr::from_u16(0x8001), attr: 0 },
+            Rev::Mut(Value::String(format!("name{}", i))),
+        )).collect(),
+    }.serialize();
+    assert_eq!(roundtrip(&commit, &Type::Struct(Commit::PTR)).len(), 351);
+    assert_eq!(commit.encode().len(), 495);
+    let trait_def = std.traits.get(&0x8001).unwrap().clone().serialize();
+    assert_eq!(roundtrip(&trait_def, &Type::Struct(Trait::PTR)).len(), 13);
+    assert_eq!(trait_def.encode().len(), 32);
+    assert_eq!(roundtrip(&commit, &Type::Unknown), commit.encode());
+
+    // declared types are written only where `t` leaves them open
+    let opt = Value::Option(Type::UInt, Box::new(Some(Value::UInt(300))));
+    assert_eq!(roundtrip(&opt, &Type::Option(Box::new(Type::UInt))), hex!("01 ac02"));
+    assert_eq!(roundtrip(&opt, &Type::Option(Box::new(Type::Unknown))), hex!("04 01 ac02"));
+    let set = Value::new_set(Type::Int, vec![Value::Int(-1), Value::Int(2)]);
+    assert_eq!(roundtrip(&set, &Type::Set(Box::new(Type::Int))), hex!("02 01 04"));
+    // set items keep the order of their self-describing encodings, which is not that of `Ord`
+    let set = Value::new_set(Type::Int, vec![Value::Int(-7), Value::Int(5)]);
+    assert_eq!(set, Value::Set(Type::Int, vec![Value::Int(5), Value::Int(-7)]));
+    assert_eq!(roundtrip(&set, &Type::Set(Box::new(Type::Int))), hex!("02 0a 0d"));
+    assert!(matches!(Value::decode_typed(&hex!("02 0d 0a"), &Type::Set(Box::new(Type::Int)), &std).map_err(DecodeError::into_kind), Err(DecodeErrorKind::SetOrder(1))));
+    let set = Value::new_set(Type::String, vec![Value::String("aa".to_owned()), Value::String("b".to_owned())]);
+    assert_eq!(set, Value::Set(Type::String, vec![Value::String("b".to_owned()), Value::String("aa".to_owned())]));
+    assert_eq!(roundtrip(&set, &Type::Set(Box::new(Type::String))), hex!("02 01 62 02 6161"));
+    assert!(matches!(Value::decode_typed(&hex!("02 02 6161 01 62"), &Type::Set(Box::new(Type::String)), &std).map_err(DecodeError::into_kind), Err(DecodeErrorKind::SetOrder(1))));
+    let map = Value::Map((Type::String, Type::Unknown), vec![(Value::String("a".to_owned()), Value::Bool(true))]);
+    assert_eq!(roundtrip(&map, &Type::Map(Box::new(Type::String), Box::new(Type::Unknown))), hex!("01 00 0161 02"));
+    let simple_name = Type::Alias(TypePtr::from_u16(0x0004));
+    let name = Value::String("name".to_owned());
+    let wrapped = Value::Alias(TypePtr::from_u16(0x0004), Box::new(name.clone()));
+    assert_eq!(roundtrip(&name, &simple_name), hex!("04 6e616d65"));
+    assert!(matches!(wrapped.try_encode_typed(&simple_name, &std), Err(EncodeError::AliasWrapper(_))));
+    // wrappers are kept where the value describes itself
+    let pair = Value::Tuple(vec![wrapped.clone(), name.clone()]);
+    assert_eq!(roundtrip(&pair, &Type::Tuple(vec![Type::Unknown, simple_name.clone()])), [wrapped.encode(), hex!("04 6e616d65").to_vec()].concat());
+
+    assert!(matches!(Value::Int(1).try_encode_typed(&Type::UInt, &std), Err(EncodeError::TypeCheck(_))));
+    assert!(matches!(Value::decode_typed(&hex!("02 00"), &Type::Option(Box::new(Type::UInt)), &std).map_err(DecodeError::into_kind), Err(DecodeErrorKind::Flag(2))));
+    assert!(matches!(Value::decode_typed(&hex!("00 00"), &Type::Bool, &std).map_err(DecodeError::into_kind), Err(DecodeErrorKind::TooLong(1))));
+    assert!(matches!(Value::decode_typed(&hex!("02 04 02"), &Type::Set(Box::new(Type::Int)), &std).map_err(DecodeError::into_kind), Err(DecodeErrorKind::SetOrder(1))));
+    let unsorted = Value::Set(Type::Int, vec![Value::Int(2), Value::Int(1)]);
+    assert!(matches!(unsorted.try_encode_typed(&Type::Set(Box::new(Type::Int)), &std), Err(EncodeError::SetOrder(1))));
+    // nesting is bounded like `decode_typed` bounds it by default
+    let (mut nested, mut t) = (Value::Unit, Type::Unit);
+    for _ in 0..127 {
+        nested = Value::Tuple(vec![nested]);
+        t = Type::Tuple(vec![t]);
+    }
+    assert_eq!(roundtrip(&nested, &t).len(), 0);
+    let nested = Value::Tuple(vec![nested]);
+    assert!(matches!(nested.try_encode_typed(&Type::Tuple(vec![t]), &std), Err(EncodeError::DepthLimit(128))));
+    let user = TypePtr::from_path("user:test:name");
+    assert!(matches!(Value::decode_typed(&[], &Type::Struct(user), &std).map_err(DecodeError::into_kind), Err(DecodeErrorKind::Schema(TypeCheckErrorKind::Unresolved(_)))));
+    assert!(matches!(Value::decode_typed(&hex!("05"), &Type::CEnum(CommitAttrType::PTR), &std).map_err(DecodeError::into_kind), Err(DecodeErrorKind::Schema(TypeCheckErrorKind::Variant(_)))));
+}
+
+#[test]
+fn decode_errors() {
+    use crate::{std::init, meta::{Commit, CommitPtr, Rev, RevPtr}};
+
+    let std = init();
+    let commit = Commit {
+        ptr: CommitPtr {
+            ts: Timestamp { secs: 0x2937b5bf, nanos: 0x05b242d8 },
+            opr: ObjectPtr { ot: 0x1234, oid: 0xabcdef00 },
+            seq: 0,
+        },
+        revs: (0..4).map(|i| (
+            RevPtr { object: ObjectPtr { ot: 1, oid: i }, trait_type: TypePtr::from_u16(0x8001), attr: 0 },
+            Rev::Mut(Value::List(Type::String, vec![Value::String("name".to_owned()); 8])),
+        )).collect(),
+    }.serialize();
+
+    // the 7th item of the last rev's list, which is 5 bytes each
+    let mut buf = commit.encode();
+    let at = buf.len() - 5 * 2;
+    buf[at] = 0xff;
+    let err = Value::decode(&buf).unwrap_err();
+    assert!(matches!(err.kind(), DecodeErrorKind::HTag(0xf)));
+    assert_eq!((err.offset(), err.path_string().as_str()), (at, ".1[3].value.1[6]"));
+    let err = ValueRef::decode(&buf).unwrap_err();
+    assert_eq!((err.offset(), err.path_string().as_str()), (at, ".1[3].value.1[6]"));
+
+    // names come from the definitions, and the offset from the start of `buf` still
+    let t = Type::Struct(Commit::PTR);
+    let mut buf = commit.encode_typed(&t, &std);
+    let at = buf.len() - 5 * 2;
+    buf[at] = 0xff;
+    let err = Value::decode_typed(&buf, &t, &std).unwrap_err();
+    assert_eq!((err.offset(), err.path_string().as_str()), (at, ".revs[3].value.mut[6]"));
+
+    let buf = hex!("82 21 ff");
+    let err = Value::decode(&buf).unwrap_err();
+    assert_eq!(err.render(&buf), "HTag(15) at offset 2 in .1\n00000000  82 21 ff\n                ^^\n");
+    let err = Value::decode(&hex!("82 21 44 6e")).unwrap_err();
+    assert!(matches!(err.kind(), DecodeErrorKind::TooShort(_)));
+    assert_eq!((err.offset(), err.path_string().as_str()), (2, ".1"));
+    let err = ValueCursor::new(&buf).field(1).unwrap().unwrap().decode().unwrap_err();
+    assert_eq!((err.offset(), err.path_string().as_str()), (2, ""));
+    let err = Value::decode(&hex!("21 00")).unwrap_err();
+    assert!(matches!(err.kind(), DecodeErrorKind::TooLong(1)));
+    assert_eq!(err.offset(), 1);
+}
+
+#[test]
+fn deep() {
+    const DEPTH: usize = 300_000;
+    let ptr = TypePtr::from_u16(0x0001);
+
+    // the derived traits recurse, but are fine a thousand levels deep
+    let mut v = Value::Unit;
+    for _ in 0..1000 {
+        v = Value::Option(Type::Unknown, Box::new(Some(v)));
+    }
+    let copy = v.clone();
+    assert_eq!(copy, v);
+    assert_eq!(Value::decode_with(&v.encode(), DecodeOptions::UNLIMITED).unwrap(), copy);
+
+    // values this deep are only ever compared by their encodings, as `==` still recurses
+    let mut v = Value::Unit;
+    for _ in 0..DEPTH {
+        v = Value::Option(Type::Unknown, Box::new(Some(v)));
+    }
+    let buf = v.encode();
+    assert_eq!(buf.len(), DEPTH * 2 + 1);
+    assert_eq!(&buf[..4], hex!("0400 0400"));
+    assert!(matches!(Value::decode(&buf).map_err(DecodeError::into_kind), Err(DecodeErrorKind::DepthLimit(128))));
+    assert_eq!(Value::decode_with(&buf, DecodeOptions::UNLIMITED).unwrap().encode(), buf);
+    assert_eq!(ValueRef::decode_with(&buf, DecodeOptions::UNLIMITED).unwrap().into_owned().encode(), buf);
+    assert_eq!(ValueCursor::with_options(&buf, DecodeOptions::UNLIMITED).raw().unwrap(), &buf[..]);
+    assert!(matches!(v.try_encode_typed(&Type::Unknown, &crate::std::init()), Err(EncodeError::DepthLimit(128))));
+
+    let mut corrupt = buf.clone();
+    *corrupt.last_mut().unwrap() = 0xff;
+    let err = Value::decode_with(&corrupt, DecodeOptions::UNLIMITED).unwrap_err();
+    assert!(matches!(err.kind(), DecodeErrorKind::HTag(15)));
+    assert_eq!((err.offset(), err.path().len()), (DEPTH * 2, DEPTH));
+    let err = ValueRef::decode_with(&corrupt, DecodeOptions::UNLIMITED).unwrap_err();
+    assert!(matches!(err.kind(), DecodeErrorKind::HTag(15)));
+    assert_eq!((err.offset(), err.path().len()), (DEPTH * 2, DEPTH));
+
+    // every kind that nests, in turn
+    let mut v = Value::Unit;
+    for i in 0..DEPTH {
+        v = match i % 8 {
+            0 => Value::List(Type::Unknown, vec![v]),
+            1 => Value::Tuple(vec![Value::Unit, v]),
+            2 => Value::Map((Type::Unknown, Type::Unknown), vec![(Value::Unit, v)]),
+            3 => Value::Set(Type::Unknown, vec![v]),
+            4 => Value::Alias(ptr, Box::new(v)),
+            5 => Value::Enum(ptr, 1, Box::new(v)),
+            6 => Value::Struct(ptr, vec![v, Value::Unit]),
+            _ => Value::Option(Type::Unknown, Box::new(Some(v))),
+        };
+    }
+    let buf = v.encode();
+    assert_eq!(v.encoded_len().unwrap(), buf.len());
+    let strict = DecodeOptions { canonical: true, sorted_keys: true, ..DecodeOptions::UNLIMITED };
+    assert_eq!(Value::decode_with(&buf, strict).unwrap().encode(), buf);
+    assert_eq!(ValueRef::decode_with(&buf, strict).unwrap().into_owned().encode(), buf);
+    assert_eq!(ValueCursor::with_options(&buf, DecodeOptions::UNLIMITED).raw().unwrap(), &buf[..]);
+
+    let mut v = Value::Unit;
+    for _ in 0..DEPTH {
+        v = Value::Tuple(vec![v, Value::Bool(true)]);
+    }
+    let t = v.as_type();
+    let mut inner = &t;
+    for _ in 0..DEPTH {
+        inner = match inner {
+            Type::Tuple(s) if s.len() == 2 && s[1] == Type::Bool => &s[0],
+            _ => panic!(),
+        };
+    }
+    assert_eq!(*inner, Type::Unit);
+
+    // declared types nest as deep as values
+    let mut t = Type::Unit;
+    for i in 0..DEPTH {
+        t = match i % 3 {
+            0 => Type::Option(Box::new(t)),
+            1 => Type::List(Box::new(t)),
+            _ => Type::Map(Box::new(Type::Unit), Box::new(t)),
+        };
+    }
+    let list = Value::List(t.clone(), vec![]);
+    for v in [&list, &Value::Type(t.clone()), &Value::Option(t.clone(), Box::new(None))] {
+        let buf = v.encode();
+        assert!(matches!(Value::decode(&buf).map_err(DecodeError::into_kind), Err(DecodeErrorKind::DepthLimit(128))));
+        assert_eq!(Value::decode_with(&buf, DecodeOptions::UNLIMITED).unwrap().encode(), buf);
+        assert_eq!(ValueCursor::with_options(&buf, DecodeOptions::UNLIMITED).raw().unwrap(), &buf[..]);
+    }
+    assert_eq!(Value::Type(list.as_type()).encode(), Value::Type(Type::List(Box::new(t))).encode());
 }
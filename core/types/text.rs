@@ -0,0 +1,808 @@
+// text notation, e.g.
+// `struct std:meta:commit-ptr(timestamp(2023-01-01T00:00:00Z), object-ptr(1, 2), 0u16)`
+// `map<uint, list<string>>{1: list<string>["a"]}`
+// `enum #fedcba98765432(1, some<int>(-5i))`
+
+use std::{fmt, str::FromStr};
+use crate::{meta::{Timestamp, ObjectPtr, TypePtr}, std::{ptr2path, parts2ptr}};
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextError {
+    pub pos: usize,
+    pub msg: &'static str,
+}
+
+type Result<T> = std::result::Result<T, TextError>;
+
+// region: timestamp
+
+const SECS_PER_DAY: i64 = 86400;
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+// days since 1970-01-01 <-> proleptic gregorian (y, m, d)
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + i64::from(m <= 2), m, d)
+}
+
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let m = i64::from(m);
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(d) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+// contents of `timestamp(..)`: RFC 3339 in UTC when representable, raw `secs, nanos` otherwise
+pub(super) struct TimestampText<'a>(pub &'a Timestamp);
+
+impl fmt::Display for TimestampText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ts = self.0;
+        let unix = match ts.secs.checked_add(Timestamp::EPOCH_AFTER_UNIX_EPOCH_SEC) {
+            Some(unix) if ts.nanos < NANOS_PER_SEC => unix,
+            _ => return write!(f, "{}, {}", ts.secs, ts.nanos),
+        };
+        let (y, mo, d) = civil_from_days(unix.div_euclid(SECS_PER_DAY));
+        let sod = unix.rem_euclid(SECS_PER_DAY);
+        if (0..=9999).contains(&y) {
+            write!(f, "{:04}", y)?;
+        } else {
+            write!(f, "{:+}", y)?;
+        }
+        write!(f, "-{:02}-{:02}T{:02}:{:02}:{:02}", mo, d, sod / 3600, sod / 60 % 60, sod % 60)?;
+        if ts.nanos != 0 {
+            write!(f, ".{:09}", ts.nanos)?;
+        }
+        f.write_str("Z")
+    }
+}
+
+pub(super) fn parse_timestamp_text(s: &str) -> Option<Timestamp> {
+    match s.split_once(',') {
+        Some((secs, nanos)) => Some(Timestamp { secs: secs.trim().parse().ok()?, nanos: nanos.trim().parse().ok()? }),
+        None => parse_rfc3339(s.trim()),
+    }
+}
+
+// endregion
+
+// region: printer
+
+impl fmt::Display for TypePtr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypePtr::Std(stdptr) => match ptr2path(stdptr.to_u16()) {
+                Some(path) => f.write_str(&path.to_path()),
+                None => write!(f, "std#{:04x}", stdptr.to_u16()),
+            },
+            TypePtr::Hash(hash) => {
+                f.write_str("#")?;
+                for b in hash {
+                    write!(f, "{:02x}", b)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+fn fmt_seq<T: fmt::Display>(s: &[T], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, x) in s.iter().enumerate() {
+        if i != 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}", x)?;
+    }
+    Ok(())
+}
+
+// `()` is the unit value, so tuples always carry a comma unless they have two or more items
+fn fmt_tuple<T: fmt::Display>(s: &[T], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("(")?;
+    fmt_seq(s, f)?;
+    if s.len() < 2 {
+        f.write_str(",")?;
+    }
+    f.write_str(")")
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Unknown => f.write_str("unknown"),
+            Type::Unit => f.write_str("unit"),
+            Type::Bool => f.write_str("bool"),
+            Type::Int => f.write_str("int"),
+            Type::UInt => f.write_str("uint"),
+            Type::Float => f.write_str("float"),
+            Type::String => f.write_str("string"),
+            Type::Bytes => f.write_str("bytes"),
+            Type::Option(t) => write!(f, "option<{}>", t),
+            Type::List(t) => write!(f, "list<{}>", t),
+            Type::Map(tk, tv) => write!(f, "map<{}, {}>", tk, tv),
+            // the unit type is spelled `unit`, so `()` is free for the empty tuple
+            Type::Tuple(ts) if ts.is_empty() => f.write_str("()"),
+            Type::Tuple(ts) => fmt_tuple(ts, f),
+            Type::Alias(ptr) => write!(f, "alias {}", ptr),
+            Type::CEnum(ptr) => write!(f, "c-enum {}", ptr),
+            Type::Enum(ptr) => write!(f, "enum {}", ptr),
+            Type::Struct(ptr) => write!(f, "struct {}", ptr),
+            Type::Type => f.write_str("type"),
+            Type::TypePtr => f.write_str("type-ptr"),
+            Type::ObjectPtr => f.write_str("object-ptr"),
+            Type::Timestamp => f.write_str("timestamp"),
+            Type::UInt8 => f.write_str("uint8"),
+            Type::UInt16 => f.write_str("uint16"),
+            Type::UInt32 => f.write_str("uint32"),
+            Type::Int8 => f.write_str("int8"),
+            Type::Int16 => f.write_str("int16"),
+            Type::Int32 => f.write_str("int32"),
+            Type::Float32 => f.write_str("float32"),
+            Type::BigInt => f.write_str("bigint"),
+            Type::Decimal => f.write_str("decimal"),
+            Type::Set(t) => write!(f, "set<{}>", t),
+        }
+    }
+}
+
+fn fmt_float(bits: u64, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let v = f64::from_bits(bits);
+    if v.is_nan() && bits != f64::NAN.to_bits() {
+        return write!(f, "nan(0x{:016x})", bits);
+    }
+    // shortest representation that parses back to the same bits
+    write!(f, "{:?}", v)
+}
+
+fn fmt_float32(bits: u32, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let v = f32::from_bits(bits);
+    if v.is_nan() && bits != f32::NAN.to_bits() {
+        return write!(f, "nan(0x{:08x})", bits);
+    }
+    write!(f, "{:?}", v)
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_negative() {
+            f.write_str("-")?;
+        }
+        f.write_str(&self.digits())
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mantissa.is_negative() {
+            f.write_str("-")?;
+        }
+        let digits = self.mantissa.digits();
+        match usize::try_from(self.scale) {
+            Ok(0) => f.write_str(&digits),
+            // up to 6 zeros after the point before switching to an exponent
+            Ok(scale) if scale <= digits.len() + 6 => {
+                let digits = format!("{:0>1$}", digits, scale + 1);
+                let (int, frac) = digits.split_at(digits.len() - scale);
+                write!(f, "{}.{}", int, frac)
+            },
+            _ => write!(f, "{}e{}", digits, -i128::from(self.scale)),
+        }
+    }
+}
+
+fn fmt_str(s: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{{{:x}}}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Unit => f.write_str("()"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(i) => write!(f, "{}i", i),
+            Value::UInt(u) => write!(f, "{}", u),
+            Value::Float(bits) => fmt_float(*bits, f),
+            Value::String(s) => fmt_str(s, f),
+            Value::Bytes(b) => {
+                f.write_str("x\"")?;
+                for b in b {
+                    write!(f, "{:02x}", b)?;
+                }
+                f.write_str("\"")
+            },
+            Value::Option(t, opt) => match opt.as_ref() {
+                Some(v) => write!(f, "some<{}>({})", t, v),
+                None => write!(f, "none<{}>", t),
+            },
+            Value::List(t, s) => {
+                write!(f, "list<{}>[", t)?;
+                fmt_seq(s, f)?;
+                f.write_str("]")
+            },
+            Value::Map((tk, tv), s) => {
+                write!(f, "map<{}, {}>{{", tk, tv)?;
+                for (i, (k, v)) in s.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", k, v)?;
+                }
+                f.write_str("}")
+            },
+            Value::Tuple(s) => fmt_tuple(s, f),
+            Value::Alias(ptr, v) => write!(f, "alias {}({})", ptr, v),
+            Value::CEnum(ptr, ev) => write!(f, "c-enum {}({})", ptr, ev),
+            Value::Enum(ptr, ev, v) => write!(f, "enum {}({}, {})", ptr, ev, v),
+            Value::Struct(ptr, s) => {
+                write!(f, "struct {}(", ptr)?;
+                fmt_seq(s, f)?;
+                f.write_str(")")
+            },
+            Value::Type(t) => write!(f, "type({})", t),
+            Value::TypePtr(ptr) => write!(f, "type-ptr({})", ptr),
+            Value::ObjectPtr(ObjectPtr { ot, oid }) => write!(f, "object-ptr({}, {})", ot, oid),
+            Value::Timestamp(ts) => write!(f, "timestamp({})", TimestampText(ts)),
+            Value::UInt8(u) => write!(f, "{}u8", u),
+            Value::UInt16(u) => write!(f, "{}u16", u),
+            Value::UInt32(u) => write!(f, "{}u32", u),
+            Value::Int8(i) => write!(f, "{}i8", i),
+            Value::Int16(i) => write!(f, "{}i16", i),
+            Value::Int32(i) => write!(f, "{}i32", i),
+            // wrapped rather than suffixed, as inf and nan are words
+            Value::Float32(bits) => {
+                f.write_str("float32(")?;
+                fmt_float32(*bits, f)?;
+                f.write_str(")")
+            },
+            Value::BigInt(b) => write!(f, "{}n", b),
+            Value::Decimal(d) => write!(f, "{}d", d),
+            Value::Set(t, s) => {
+                write!(f, "set<{}>[", t)?;
+                fmt_seq(s, f)?;
+                f.write_str("]")
+            },
+        }
+    }
+}
+
+// endregion
+
+// region: parser
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn err<T>(&self, msg: &'static str) -> Result<T> {
+        Err(TextError { pos: self.pos, msg })
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    fn ws(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.ws();
+        self.rest().chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char, msg: &'static str) -> Result<()> {
+        if !self.eat(c) {
+            return self.err(msg);
+        }
+        Ok(())
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        self.ws();
+        let rest = self.rest();
+        let len = rest.find(|c| !f(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn word(&mut self) -> &'a str {
+        self.take_while(|c| c.is_ascii_alphanumeric() || c == '-')
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if self.peek().is_some() {
+            return self.err("unexpected trailing input");
+        }
+        Ok(())
+    }
+
+    fn ptr(&mut self) -> Result<TypePtr> {
+        self.ws();
+        let start = self.pos;
+        let s = self.take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | ':' | '#'));
+        let ptr = if let Some(hex) = s.strip_prefix("std#") {
+            u16::from_str_radix(hex, 16).ok().filter(|_| hex.len() == 4).map(TypePtr::from_u16_unchecked)
+        } else if let Some(hex) = s.strip_prefix('#') {
+            hex_bytes(hex).and_then(|b| b.try_into().ok()).map(TypePtr::Hash)
+        } else if let Some(path) = s.strip_prefix("std:") {
+            path.rsplit_once(':').and_then(|(p, n)| parts2ptr(p, n)).map(TypePtr::from_u16_unchecked)
+        } else {
+            None
+        };
+        ptr.ok_or(TextError { pos: start, msg: "invalid type pointer" })
+    }
+
+    fn seq<T>(&mut self, end: char, mut item: impl FnMut(&mut Self) -> Result<T>) -> Result<(Vec<T>, bool)> {
+        let mut s = Vec::new();
+        let mut trailing = false;
+        while !self.eat(end) {
+            if !s.is_empty() && !trailing {
+                return self.err("expected `,`");
+            }
+            s.push(item(self)?);
+            trailing = self.eat(',');
+        }
+        Ok((s, trailing))
+    }
+
+    fn tuple<T>(&mut self, mut item: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        if self.eat(',') {
+            self.expect(')', "expected `)`")?;
+            return Ok(Vec::new());
+        }
+        let (s, trailing) = self.seq(')', &mut item)?;
+        if s.len() == 1 && !trailing {
+            // point at the closing `)`
+            return Err(TextError { pos: self.pos - 1, msg: "single-item tuple needs a trailing `,`" });
+        }
+        Ok(s)
+    }
+
+    fn inner_ty(&mut self) -> Result<Type> {
+        self.expect('<', "expected `<`")?;
+        let t = self.ty()?;
+        self.expect('>', "expected `>`")?;
+        Ok(t)
+    }
+
+    fn ty(&mut self) -> Result<Type> {
+        if self.eat('(') {
+            if self.eat(')') {
+                return Ok(Type::Tuple(Vec::new()));
+            }
+            return Ok(Type::Tuple(self.tuple(Self::ty)?));
+        }
+        self.ws();
+        let start = self.pos;
+        Ok(match self.word() {
+            "unknown" => Type::Unknown,
+            "unit" => Type::Unit,
+            "bool" => Type::Bool,
+            "int" => Type::Int,
+            "uint" => Type::UInt,
+            "float" => Type::Float,
+            "string" => Type::String,
+            "bytes" => Type::Bytes,
+            "option" => Type::Option(Box::new(self.inner_ty()?)),
+            "list" => Type::List(Box::new(self.inner_ty()?)),
+            "map" => {
+                self.expect('<', "expected `<`")?;
+                let tk = self.ty()?;
+                self.expect(',', "expected `,`")?;
+                let tv = self.ty()?;
+                self.expect('>', "expected `>`")?;
+                Type::Map(Box::new(tk), Box::new(tv))
+            },
+            "alias" => Type::Alias(self.ptr()?),
+            "c-enum" => Type::CEnum(self.ptr()?),
+            "enum" => Type::Enum(self.ptr()?),
+            "struct" => Type::Struct(self.ptr()?),
+            "type" => Type::Type,
+            "type-ptr" => Type::TypePtr,
+            "object-ptr" => Type::ObjectPtr,
+            "timestamp" => Type::Timestamp,
+            "uint8" => Type::UInt8,
+            "uint16" => Type::UInt16,
+            "uint32" => Type::UInt32,
+            "int8" => Type::Int8,
+            "int16" => Type::Int16,
+            "int32" => Type::Int32,
+            "float32" => Type::Float32,
+            "bigint" => Type::BigInt,
+            "decimal" => Type::Decimal,
+            "set" => Type::Set(Box::new(self.inner_ty()?)),
+            _ => return Err(TextError { pos: start, msg: "unknown type" }),
+        })
+    }
+
+    fn num<T: FromStr>(&mut self) -> Result<T> {
+        self.ws();
+        let start = self.pos;
+        let s = self.take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.'));
+        s.parse().map_err(|_| TextError { pos: start, msg: "invalid number" })
+    }
+
+    fn number(&mut self) -> Result<Value> {
+        self.ws();
+        let start = self.pos;
+        let rest = self.rest();
+        // sign, digits, fraction and exponent, then an optional suffix
+        let mut len = usize::from(rest.starts_with('-'));
+        let mut prev = ' ';
+        for c in rest[len..].chars() {
+            if !(c.is_ascii_alphanumeric() || c == '.' || (matches!(c, '+' | '-') && prev == 'e')) {
+                break;
+            }
+            prev = c;
+            len += 1;
+        }
+        let s = &rest[..len];
+        self.pos += len;
+        let err = TextError { pos: start, msg: "invalid number" };
+        macro_rules! suffixed {
+            ($($suffix:literal => $variant:ident,)*) => {
+                $(if let Some(n) = s.strip_suffix($suffix) {
+                    return n.parse().map(Value::$variant).map_err(|_| err);
+                })*
+            };
+        }
+        suffixed! {
+            "u8" => UInt8,
+            "u16" => UInt16,
+            "u32" => UInt32,
+            "i8" => Int8,
+            "i16" => Int16,
+            "i32" => Int32,
+            "n" => BigInt,
+            "d" => Decimal,
+            "i" => Int,
+        }
+        if s.contains(['.', 'e', 'E']) || s.ends_with("inf") {
+            return s.parse::<f64>().map(|f| Value::Float(f.to_bits())).map_err(|_| err);
+        }
+        s.parse().map(Value::UInt).map_err(|_| err)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.expect('"', "expected string")?;
+        let mut s = String::new();
+        let mut chars = self.rest().char_indices();
+        let base = self.pos;
+        loop {
+            let (i, c) = match chars.next() {
+                Some(x) => x,
+                None => return self.err("unterminated string"),
+            };
+            match c {
+                '"' => {
+                    self.pos = base + i + 1;
+                    return Ok(s);
+                },
+                '\\' => {
+                    let c = match chars.next() {
+                        Some((_, '"')) => '"',
+                        Some((_, '\\')) => '\\',
+                        Some((_, 'n')) => '\n',
+                        Some((_, 'r')) => '\r',
+                        Some((_, 't')) => '\t',
+                        Some((_, 'u')) => {
+                            let rest = chars.as_str();
+                            let code = rest.strip_prefix('{').and_then(|r| r.split_once('}'));
+                            let c = code.and_then(|(hex, _)| u32::from_str_radix(hex, 16).ok()).and_then(char::from_u32);
+                            match (c, code) {
+                                (Some(c), Some((hex, _))) => {
+                                    for _ in 0..hex.len() + 2 {
+                                        chars.next();
+                                    }
+                                    c
+                                },
+                                _ => return Err(TextError { pos: base + i, msg: "invalid unicode escape" }),
+                            }
+                        },
+                        _ => return Err(TextError { pos: base + i, msg: "invalid escape" }),
+                    };
+                    s.push(c);
+                },
+                c => s.push(c),
+            }
+        }
+    }
+
+    fn timestamp(&mut self) -> Result<Timestamp> {
+        self.expect('(', "expected `(`")?;
+        self.ws();
+        let start = self.pos;
+        let s = self.take_while(|c| c != ')');
+        let ts = parse_timestamp_text(s).ok_or(TextError { pos: start, msg: "invalid timestamp" })?;
+        self.expect(')', "expected `)`")?;
+        Ok(ts)
+    }
+
+    fn val(&mut self) -> Result<Value> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                if self.eat(')') {
+                    return Ok(Value::Unit);
+                }
+                return Ok(Value::Tuple(self.tuple(Self::val)?));
+            },
+            Some('"') => return Ok(Value::String(self.string()?)),
+            Some(c) if c.is_ascii_digit() || c == '-' => return self.number(),
+            _ => {},
+        }
+        let start = self.pos;
+        Ok(match self.word() {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "inf" => Value::Float(f64::INFINITY.to_bits()),
+            "NaN" => Value::Float(f64::NAN.to_bits()),
+            "nan" => {
+                self.expect('(', "expected `(`")?;
+                self.ws();
+                let start = self.pos;
+                let s = self.take_while(|c| c.is_ascii_alphanumeric());
+                let bits = s.strip_prefix("0x").and_then(|hex| u64::from_str_radix(hex, 16).ok());
+                let bits = bits.ok_or(TextError { pos: start, msg: "invalid nan bits" })?;
+                self.expect(')', "expected `)`")?;
+                Value::Float(bits)
+            },
+            "float32" => {
+                self.expect('(', "expected `(`")?;
+                self.ws();
+                let start = self.pos;
+                let s = self.take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.'));
+                let bits = if s == "nan" {
+                    self.expect('(', "expected `(`")?;
+                    self.ws();
+                    let start = self.pos;
+                    let s = self.take_while(|c| c.is_ascii_alphanumeric());
+                    let bits = s.strip_prefix("0x").and_then(|hex| u32::from_str_radix(hex, 16).ok());
+                    let bits = bits.ok_or(TextError { pos: start, msg: "invalid nan bits" })?;
+                    self.expect(')', "expected `)`")?;
+                    bits
+                } else {
+                    s.parse::<f32>().map_err(|_| TextError { pos: start, msg: "invalid number" })?.to_bits()
+                };
+                self.expect(')', "expected `)`")?;
+                Value::Float32(bits)
+            },
+            "x" => {
+                let start = self.pos;
+                let s = self.string()?;
+                Value::Bytes(hex_bytes(&s).ok_or(TextError { pos: start, msg: "invalid hex bytes" })?)
+            },
+            "none" => Value::Option(self.inner_ty()?, Box::new(None)),
+            "some" => {
+                let t = self.inner_ty()?;
+                self.expect('(', "expected `(`")?;
+                let v = self.val()?;
+                self.expect(')', "expected `)`")?;
+                Value::Option(t, Box::new(Some(v)))
+            },
+            "list" => {
+                let t = self.inner_ty()?;
+                self.expect('[', "expected `[`")?;
+                Value::List(t, self.seq(']', Self::val)?.0)
+            },
+            // the items may come in any order and repeat, as in `Value::new_set`
+            "set" => {
+                let t = self.inner_ty()?;
+                self.expect('[', "expected `[`")?;
+                let start = self.pos;
+                let s = self.seq(']', Self::val)?.0;
+                Value::try_new_set(t, s).map_err(|_| TextError { pos: start, msg: "unencodable set item" })?
+            },
+            "map" => {
+                self.expect('<', "expected `<`")?;
+                let tk = self.ty()?;
+                self.expect(',', "expected `,`")?;
+                let tv = self.ty()?;
+                self.expect('>', "expected `>`")?;
+                self.expect('{', "expected `{`")?;
+                let (s, _) = self.seq('}', |p| {
+                    let k = p.val()?;
+                    p.expect(':', "expected `:`")?;
+                    Ok((k, p.val()?))
+                })?;
+                Value::Map((tk, tv), s)
+            },
+            "alias" => {
+                let ptr = self.ptr()?;
+                self.expect('(', "expected `(`")?;
+                let v = self.val()?;
+                self.expect(')', "expected `)`")?;
+                Value::Alias(ptr, Box::new(v))
+            },
+            "c-enum" => {
+                let ptr = self.ptr()?;
+                self.expect('(', "expected `(`")?;
+                let ev = self.num()?;
+                self.expect(')', "expected `)`")?;
+                Value::CEnum(ptr, ev)
+            },
+            "enum" => {
+                let ptr = self.ptr()?;
+                self.expect('(', "expected `(`")?;
+                let ev = self.num()?;
+                self.expect(',', "expected `,`")?;
+                let v = self.val()?;
+                self.expect(')', "expected `)`")?;
+                Value::Enum(ptr, ev, Box::new(v))
+            },
+            "struct" => {
+                let ptr = self.ptr()?;
+                self.expect('(', "expected `(`")?;
+                Value::Struct(ptr, self.seq(')', Self::val)?.0)
+            },
+            "type" => {
+                self.expect('(', "expected `(`")?;
+                let t = self.ty()?;
+                self.expect(')', "expected `)`")?;
+                Value::Type(t)
+            },
+            "type-ptr" => {
+                self.expect('(', "expected `(`")?;
+                let ptr = self.ptr()?;
+                self.expect(')', "expected `)`")?;
+                Value::TypePtr(ptr)
+            },
+            "object-ptr" => {
+                self.expect('(', "expected `(`")?;
+                let ot = self.num()?;
+                self.expect(',', "expected `,`")?;
+                let oid = self.num()?;
+                self.expect(')', "expected `)`")?;
+                Value::ObjectPtr(ObjectPtr { ot, oid })
+            },
+            "timestamp" => Value::Timestamp(self.timestamp()?),
+            _ => return Err(TextError { pos: start, msg: "unknown value" }),
+        })
+    }
+}
+
+fn hex_bytes(s: &str) -> Option<Vec<u8>> {
+    s.as_bytes().chunks(2).map(|b| match b {
+        [h, l] => Some((char::from(*h).to_digit(16)? << 4 | char::from(*l).to_digit(16)?) as u8),
+        _ => None,
+    }).collect()
+}
+
+// `[+-]YYYY-MM-DDTHH:MM:SS[.fraction]Z`
+fn parse_rfc3339(s: &str) -> Option<Timestamp> {
+    let s = s.strip_suffix('Z')?;
+    let (date, time) = s.split_once('T')?;
+    // the year may carry a sign, so split after its first character
+    let (y, md) = date.get(1..)?.split_once('-')?;
+    let y = &date[..y.len() + 1];
+    let (mo, d) = md.split_once('-')?;
+    let (time, frac) = match time.split_once('.') {
+        Some((time, frac)) => (time, Some(frac)),
+        None => (time, None),
+    };
+    let mut hms = time.splitn(3, ':');
+    let (h, mi, sec) = (hms.next()?, hms.next()?, hms.next()?);
+    let (y, mo, d): (i64, u32, u32) = (y.parse().ok()?, mo.parse().ok()?, d.parse().ok()?);
+    let (h, mi, sec): (i64, i64, i64) = (h.parse().ok()?, mi.parse().ok()?, sec.parse().ok()?);
+    if !(1..=12).contains(&mo) || !(0..24).contains(&h) || !(0..60).contains(&mi) || !(0..60).contains(&sec) {
+        return None;
+    }
+    let days = days_from_civil(y, mo, d);
+    if civil_from_days(days) != (y, mo, d) {
+        return None;
+    }
+    let nanos = match frac {
+        Some(frac) if (1..=9).contains(&frac.len()) && frac.bytes().all(|b| b.is_ascii_digit()) => {
+            frac.parse::<u32>().ok()? * 10u32.pow(9 - frac.len() as u32)
+        },
+        Some(_) => return None,
+        None => 0,
+    };
+    let unix = days.checked_mul(SECS_PER_DAY)?.checked_add(h * 3600 + mi * 60 + sec)?;
+    Some(Timestamp { secs: unix.checked_sub(Timestamp::EPOCH_AFTER_UNIX_EPOCH_SEC)?, nanos })
+}
+
+impl FromStr for Value {
+    type Err = TextError;
+
+    fn from_str(s: &str) -> Result<Value> {
+        let mut p = Parser { s, pos: 0 };
+        let v = p.val()?;
+        p.finish()?;
+        Ok(v)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = TextError;
+
+    fn from_str(s: &str) -> Result<BigInt> {
+        let (neg, digits) = s.strip_prefix('-').map_or((false, s), |s| (true, s));
+        BigInt::from_digits(neg, digits).ok_or(TextError { pos: 0, msg: "invalid number" })
+    }
+}
+
+// digits with an optional fraction and exponent, the scale is the number of fraction digits less
+// the exponent
+impl FromStr for Decimal {
+    type Err = TextError;
+
+    fn from_str(s: &str) -> Result<Decimal> {
+        let err = TextError { pos: 0, msg: "invalid number" };
+        let (neg, s) = s.strip_prefix('-').map_or((false, s), |s| (true, s));
+        let (s, exp) = match s.split_once(['e', 'E']) {
+            Some((s, exp)) => (s, exp.parse::<i64>().map_err(|_| err)?),
+            None => (s, 0),
+        };
+        let (int, frac) = match s.split_once('.') {
+            Some((_, "")) => return Err(err),
+            Some((int, frac)) => (int, frac),
+            None => (s, ""),
+        };
+        if int.is_empty() {
+            return Err(err);
+        }
+        let mantissa = BigInt::from_digits(neg, &format!("{}{}", int, frac)).ok_or(err)?;
+        let scale = i64::try_from(frac.len()).ok().and_then(|n| n.checked_sub(exp)).ok_or(err)?;
+        Ok(Decimal { mantissa, scale })
+    }
+}
+
+impl FromStr for Type {
+    type Err = TextError;
+
+    fn from_str(s: &str) -> Result<Type> {
+        let mut p = Parser { s, pos: 0 };
+        let t = p.ty()?;
+        p.finish()?;
+        Ok(t)
+    }
+}
+
+impl FromStr for TypePtr {
+    type Err = TextError;
+
+    fn from_str(s: &str) -> Result<TypePtr> {
+        let mut p = Parser { s, pos: 0 };
+        let ptr = p.ptr()?;
+        p.finish()?;
+        Ok(ptr)
+    }
+}
+
+// endregion
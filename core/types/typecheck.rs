@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+use foundations::usize_casting::*;
+use super::*;
+
+pub trait Registry {
+    fn get(&self, ptr: TypePtr) -> Option<&DefType>;
+}
+
+impl Registry for crate::std::Std {
+    fn get(&self, ptr: TypePtr) -> Option<&DefType> {
+        self.types.get(&ptr.as_std_inner()?)
+    }
+}
+
+impl Registry for BTreeMap<TypePtr, DefType> {
+    fn get(&self, ptr: TypePtr) -> Option<&DefType> {
+        BTreeMap::get(self, &ptr)
+    }
+}
+
+impl<R: Registry> Registry for &R {
+    fn get(&self, ptr: TypePtr) -> Option<&DefType> {
+        R::get(self, ptr)
+    }
+}
+
+// looked up in order, e.g. `(std::init(), user_types)`
+impl<A: Registry, B: Registry> Registry for (A, B) {
+    fn get(&self, ptr: TypePtr) -> Option<&DefType> {
+        self.0.get(ptr).or_else(|| self.1.get(ptr))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSeg {
+    Field(String),
+    Variant(String),
+    Item(usize),
+    Index(usize),
+    MapKey(usize),
+    MapValue(usize),
+    Some,
+}
+
+#[derive(Debug)]
+pub enum TypeCheckErrorKind {
+    // expected, actual
+    Mismatch((Tag, Tag)),
+    // declared inner type of a list, map or option: expected, actual
+    InnerType((Type, Type)),
+    // expected, actual
+    Ptr((TypePtr, TypePtr)),
+    Unresolved(TypePtr),
+    // pointer resolves to a definition of another kind than the referring type
+    DefKind((TypePtr, Tag)),
+    AliasLoop(TypePtr),
+    // struct fields or tuple items: expected, actual
+    Len((usize, usize)),
+    Variant((TypePtr, EnumVariantId)),
+}
+
+#[derive(Debug)]
+pub struct TypeCheckError {
+    pub path: Vec<PathSeg>,
+    pub kind: TypeCheckErrorKind,
+}
+
+impl TypeCheckError {
+    pub fn path_string(&self) -> String {
+        path_string(&self.path)
+    }
+}
+
+pub(super) fn path_string(path: &[PathSeg]) -> String {
+    let mut s = String::new();
+    for seg in path {
+        match seg {
+            PathSeg::Field(name) | PathSeg::Variant(name) => {
+                s.push('.');
+                s.push_str(name);
+            },
+            PathSeg::Item(i) => s.push_str(&format!(".{}", i)),
+            PathSeg::Index(i) => s.push_str(&format!("[{}]", i)),
+            PathSeg::MapKey(i) => s.push_str(&format!("[{}].key", i)),
+            PathSeg::MapValue(i) => s.push_str(&format!("[{}].value", i)),
+            PathSeg::Some => s.push_str(".some"),
+        }
+    }
+    s
+}
+
+type Result = std::result::Result<(), TypeCheckError>;
+
+fn err(kind: TypeCheckErrorKind) -> Result {
+    Err(TypeCheckError { path: Vec::new(), kind })
+}
+
+// the path is collected in reverse while unwinding and flipped once at the top
+fn at(seg: PathSeg) -> impl FnOnce(TypeCheckError) -> TypeCheckError {
+    move |mut e| {
+        e.path.push(seg);
+        e
+    }
+}
+
+struct Checker<'r, R: Registry> {
+    reg: &'r R,
+}
+
+impl<'r, R: Registry> Checker<'r, R> {
+    fn def(&self, ptr: TypePtr) -> std::result::Result<&'r DefType, TypeCheckError> {
+        self.reg.get(ptr).ok_or(TypeCheckError { path: Vec::new(), kind: TypeCheckErrorKind::Unresolved(ptr) })
+    }
+
+    fn ptr(&self, expected: TypePtr, actual: TypePtr) -> Result {
+        if expected != actual {
+            return err(TypeCheckErrorKind::Ptr((expected, actual)));
+        }
+        Ok(())
+    }
+
+    // `Unknown` as the expected inner type accepts whatever the value declares
+    fn inner<'t>(&self, expected: &'t Type, actual: &'t Type) -> std::result::Result<&'t Type, TypeCheckError> {
+        if let Type::Unknown = expected {
+            return Ok(actual);
+        }
+        if expected != actual {
+            return Err(TypeCheckError {
+                path: Vec::new(),
+                kind: TypeCheckErrorKind::InnerType((expected.clone(), actual.clone())),
+            });
+        }
+        Ok(expected)
+    }
+
+    fn seq(&self, vals: &[Value], t: &Type, seg: fn(usize) -> PathSeg) -> Result {
+        for (i, v) in vals.iter().enumerate() {
+            self.val(v, t).map_err(at(seg(i)))?;
+        }
+        Ok(())
+    }
+
+    // an alias accepts the aliased value directly or wrapped in `Value::Alias` of any pointer in its chain
+    fn alias(&self, val: &Value, ptr: TypePtr) -> Result {
+        let (chain, t) = resolve_alias(ptr, self.reg).map_err(|kind| TypeCheckError { path: Vec::new(), kind })?;
+        if let Value::Alias(vptr, v) = val {
+            if let Some(i) = chain.iter().position(|ptr| ptr == vptr) {
+                return match chain.get(i + 1) {
+                    Some(next) => self.alias(v, *next),
+                    None => self.val(v, t),
+                };
+            }
+        }
+        self.val(val, t)
+    }
+
+    fn val(&self, val: &Value, t: &Type) -> Result {
+        match (t, val) {
+            (Type::Unknown, val) => self.val(val, &val.as_type()),
+            (Type::Alias(ptr), val) => self.alias(val, *ptr),
+
+            (Type::Option(t), Value::Option(vt, opt)) => {
+                let t = self.inner(t, vt)?;
+                if let Some(v) = opt.as_ref() {
+                    self.val(v, t).map_err(at(PathSeg::Some))?;
+                }
+                Ok(())
+            },
+            (Type::List(t), Value::List(vt, s)) => {
+                let t = self.inner(t, vt)?;
+                self.seq(s, t, PathSeg::Index)
+            },
+            (Type::Set(t), Value::Set(vt, s)) => {
+                let t = self.inner(t, vt)?;
+                self.seq(s, t, PathSeg::Index)
+            },
+            (Type::Map(tk, tv), Value::Map((vtk, vtv), s)) => {
+                let tk = self.inner(tk, vtk)?;
+                let tv = self.inner(tv, vtv)?;
+                for (i, (k, v)) in s.iter().enumerate() {
+                    self.val(k, tk).map_err(at(PathSeg::MapKey(i)))?;
+                    self.val(v, tv).map_err(at(PathSeg::MapValue(i)))?;
+                }
+                Ok(())
+            },
+            (Type::Tuple(ts), Value::Tuple(s)) => {
+                if ts.len() != s.len() {
+                    return err(TypeCheckErrorKind::Len((ts.len(), s.len())));
+                }
+                for (i, (t, v)) in ts.iter().zip(s).enumerate() {
+                    self.val(v, t).map_err(at(PathSeg::Item(i)))?;
+                }
+                Ok(())
+            },
+
+            (Type::CEnum(ptr), Value::CEnum(vptr, ev)) => {
+                self.ptr(*ptr, *vptr)?;
+                let names = match self.def(*ptr)? {
+                    DefType::CEnum(names) => names,
+                    _ => return err(TypeCheckErrorKind::DefKind((*ptr, Tag::CEnum))),
+                };
+                if *ev >= usize_u64(names.len()) {
+                    return err(TypeCheckErrorKind::Variant((*ptr, *ev)));
+                }
+                Ok(())
+            },
+            (Type::Enum(ptr), Value::Enum(vptr, ev, v)) => {
+                self.ptr(*ptr, *vptr)?;
+                let variants = match self.def(*ptr)? {
+                    DefType::Enum(variants) => variants,
+                    _ => return err(TypeCheckErrorKind::DefKind((*ptr, Tag::Enum))),
+                };
+                let (name, t) = match variants.get(u64_usize(*ev)) {
+                    Some(variant) => variant,
+                    None => return err(TypeCheckErrorKind::Variant((*ptr, *ev))),
+                };
+                self.val(v, t).map_err(at(PathSeg::Variant(name.clone())))
+            },
+            (Type::Struct(ptr), Value::Struct(vptr, s)) => {
+                self.ptr(*ptr, *vptr)?;
+                let fields = match self.def(*ptr)? {
+                    DefType::Struct(fields) => fields,
+                    _ => return err(TypeCheckErrorKind::DefKind((*ptr, Tag::Struct))),
+                };
+                if fields.len() != s.len() {
+                    return err(TypeCheckErrorKind::Len((fields.len(), s.len())));
+                }
+                for ((name, t), v) in fields.iter().zip(s) {
+                    self.val(v, t).map_err(at(PathSeg::Field(name.clone())))?;
+                }
+                Ok(())
+            },
+
+            (t, val) => {
+                if t.as_tag() != val.as_tag() {
+                    return err(TypeCheckErrorKind::Mismatch((t.as_tag(), val.as_tag())));
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+// the pointers an alias goes through and the type it finally stands for
+pub(super) fn resolve_alias<R: Registry>(ptr: TypePtr, reg: &R) -> std::result::Result<(Vec<TypePtr>, &Type), TypeCheckErrorKind> {
+    let mut chain = vec![ptr];
+    let mut ptr = ptr;
+    loop {
+        match reg.get(ptr) {
+            Some(DefType::Alias(Type::Alias(next))) => {
+                if chain.contains(next) {
+                    return Err(TypeCheckErrorKind::AliasLoop(*next));
+                }
+                chain.push(*next);
+                ptr = *next;
+            },
+            Some(DefType::Alias(t)) => return Ok((chain, t)),
+            Some(_) => return Err(TypeCheckErrorKind::DefKind((ptr, Tag::Alias))),
+            None => return Err(TypeCheckErrorKind::Unresolved(ptr)),
+        }
+    }
+}
+
+pub fn typecheck<R: Registry>(val: &Value, t: &Type, reg: &R) -> std::result::Result<(), TypeCheckError> {
+    Checker { reg }.val(val, t).map_err(|mut e| {
+        e.path.reverse();
+        e
+    })
+}
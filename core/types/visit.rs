@@ -0,0 +1,225 @@
+// each method defaults to walking into the children through the matching `walk_*` function, so an
+// override that still wants to descend calls it itself. pointers are leaves, reached from values as
+// well as from types. the walks that change values sort and dedup set items again afterwards, as
+// `Value::try_new_set` does, unless an item no longer encodes.
+
+use std::mem;
+use crate::meta::{ObjectPtr, TypePtr};
+use super::{*, canonical::sort_set_items};
+
+pub trait Visit {
+    fn visit_value(&mut self, val: &Value) {
+        walk_value(self, val)
+    }
+
+    fn visit_type(&mut self, ty: &Type) {
+        walk_type(self, ty)
+    }
+
+    fn visit_type_ptr(&mut self, _ptr: &TypePtr) {}
+
+    fn visit_object_ptr(&mut self, _ptr: &ObjectPtr) {}
+}
+
+pub fn walk_value<V: Visit + ?Sized>(v: &mut V, val: &Value) {
+    match val {
+        Value::Option(t, opt) => {
+            v.visit_type(t);
+            if let Some(val) = opt.as_ref() {
+                v.visit_value(val);
+            }
+        },
+        Value::List(t, s) | Value::Set(t, s) => {
+            v.visit_type(t);
+            s.iter().for_each(|val| v.visit_value(val));
+        },
+        Value::Map((tk, tv), s) => {
+            v.visit_type(tk);
+            v.visit_type(tv);
+            for (key, val) in s {
+                v.visit_value(key);
+                v.visit_value(val);
+            }
+        },
+        Value::Tuple(s) => s.iter().for_each(|val| v.visit_value(val)),
+        Value::Alias(ptr, val) | Value::Enum(ptr, _, val) => {
+            v.visit_type_ptr(ptr);
+            v.visit_value(val);
+        },
+        Value::CEnum(ptr, _) => v.visit_type_ptr(ptr),
+        Value::Struct(ptr, s) => {
+            v.visit_type_ptr(ptr);
+            s.iter().for_each(|val| v.visit_value(val));
+        },
+        Value::Type(t) => v.visit_type(t),
+        Value::TypePtr(ptr) => v.visit_type_ptr(ptr),
+        Value::ObjectPtr(ptr) => v.visit_object_ptr(ptr),
+        _ => {},
+    }
+}
+
+pub fn walk_type<V: Visit + ?Sized>(v: &mut V, ty: &Type) {
+    match ty {
+        Type::Option(t) | Type::List(t) | Type::Set(t) => v.visit_type(t),
+        Type::Map(tk, tv) => {
+            v.visit_type(tk);
+            v.visit_type(tv);
+        },
+        Type::Tuple(s) => s.iter().for_each(|t| v.visit_type(t)),
+        Type::Alias(ptr) | Type::CEnum(ptr) | Type::Enum(ptr) | Type::Struct(ptr) => v.visit_type_ptr(ptr),
+        _ => {},
+    }
+}
+
+pub trait VisitMut {
+    fn visit_value_mut(&mut self, val: &mut Value) {
+        walk_value_mut(self, val)
+    }
+
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        walk_type_mut(self, ty)
+    }
+
+    fn visit_type_ptr_mut(&mut self, _ptr: &mut TypePtr) {}
+
+    fn visit_object_ptr_mut(&mut self, _ptr: &mut ObjectPtr) {}
+}
+
+pub fn walk_value_mut<V: VisitMut + ?Sized>(v: &mut V, val: &mut Value) {
+    match val {
+        Value::Option(t, opt) => {
+            v.visit_type_mut(t);
+            if let Some(val) = opt.as_mut() {
+                v.visit_value_mut(val);
+            }
+        },
+        Value::List(t, s) => {
+            v.visit_type_mut(t);
+            s.iter_mut().for_each(|val| v.visit_value_mut(val));
+        },
+        Value::Set(t, s) => {
+            v.visit_type_mut(t);
+            s.iter_mut().for_each(|val| v.visit_value_mut(val));
+            let _ = sort_set_items(s);
+        },
+        Value::Map((tk, tv), s) => {
+            v.visit_type_mut(tk);
+            v.visit_type_mut(tv);
+            for (key, val) in s {
+                v.visit_value_mut(key);
+                v.visit_value_mut(val);
+            }
+        },
+        Value::Tuple(s) => s.iter_mut().for_each(|val| v.visit_value_mut(val)),
+        Value::Alias(ptr, val) | Value::Enum(ptr, _, val) => {
+            v.visit_type_ptr_mut(ptr);
+            v.visit_value_mut(val);
+        },
+        Value::CEnum(ptr, _) => v.visit_type_ptr_mut(ptr),
+        Value::Struct(ptr, s) => {
+            v.visit_type_ptr_mut(ptr);
+            s.iter_mut().for_each(|val| v.visit_value_mut(val));
+        },
+        Value::Type(t) => v.visit_type_mut(t),
+        Value::TypePtr(ptr) => v.visit_type_ptr_mut(ptr),
+        Value::ObjectPtr(ptr) => v.visit_object_ptr_mut(ptr),
+        _ => {},
+    }
+}
+
+pub fn walk_type_mut<V: VisitMut + ?Sized>(v: &mut V, ty: &mut Type) {
+    match ty {
+        Type::Option(t) | Type::List(t) | Type::Set(t) => v.visit_type_mut(t),
+        Type::Map(tk, tv) => {
+            v.visit_type_mut(tk);
+            v.visit_type_mut(tv);
+        },
+        Type::Tuple(s) => s.iter_mut().for_each(|t| v.visit_type_mut(t)),
+        Type::Alias(ptr) | Type::CEnum(ptr) | Type::Enum(ptr) | Type::Struct(ptr) => v.visit_type_ptr_mut(ptr),
+        _ => {},
+    }
+}
+
+// like `VisitMut`, but by value, so a node can be replaced with one of another kind
+pub trait Fold {
+    fn fold_value(&mut self, val: Value) -> Value {
+        walk_fold_value(self, val)
+    }
+
+    fn fold_type(&mut self, ty: Type) -> Type {
+        walk_fold_type(self, ty)
+    }
+
+    fn fold_type_ptr(&mut self, ptr: TypePtr) -> TypePtr {
+        ptr
+    }
+
+    fn fold_object_ptr(&mut self, ptr: ObjectPtr) -> ObjectPtr {
+        ptr
+    }
+}
+
+fn fold_seq<F: Fold + ?Sized>(f: &mut F, s: Vec<Value>) -> Vec<Value> {
+    s.into_iter().map(|val| f.fold_value(val)).collect()
+}
+
+pub fn walk_fold_value<F: Fold + ?Sized>(f: &mut F, mut val: Value) -> Value {
+    match &mut val {
+        Value::Option(t, opt) => {
+            let t = f.fold_type(t.take());
+            Value::Option(t, Box::new(opt.take().map(|val| f.fold_value(val))))
+        },
+        Value::List(t, s) => {
+            let t = f.fold_type(t.take());
+            Value::List(t, fold_seq(f, mem::take(s)))
+        },
+        Value::Set(t, s) => {
+            let t = f.fold_type(t.take());
+            let mut s = fold_seq(f, mem::take(s));
+            let _ = sort_set_items(&mut s);
+            Value::Set(t, s)
+        },
+        Value::Map((tk, tv), s) => {
+            let tk = f.fold_type(tk.take());
+            let tv = f.fold_type(tv.take());
+            let s = mem::take(s).into_iter().map(|(key, val)| (f.fold_value(key), f.fold_value(val))).collect();
+            Value::Map((tk, tv), s)
+        },
+        Value::Tuple(s) => Value::Tuple(fold_seq(f, mem::take(s))),
+        Value::Alias(ptr, val) => {
+            let ptr = f.fold_type_ptr(*ptr);
+            Value::Alias(ptr, Box::new(f.fold_value(val.take())))
+        },
+        Value::CEnum(ptr, ev) => Value::CEnum(f.fold_type_ptr(*ptr), *ev),
+        Value::Enum(ptr, ev, val) => {
+            let ptr = f.fold_type_ptr(*ptr);
+            Value::Enum(ptr, *ev, Box::new(f.fold_value(val.take())))
+        },
+        Value::Struct(ptr, s) => {
+            let ptr = f.fold_type_ptr(*ptr);
+            Value::Struct(ptr, fold_seq(f, mem::take(s)))
+        },
+        Value::Type(t) => Value::Type(f.fold_type(t.take())),
+        Value::TypePtr(ptr) => Value::TypePtr(f.fold_type_ptr(*ptr)),
+        Value::ObjectPtr(ptr) => Value::ObjectPtr(f.fold_object_ptr(*ptr)),
+        _ => val,
+    }
+}
+
+pub fn walk_fold_type<F: Fold + ?Sized>(f: &mut F, mut ty: Type) -> Type {
+    match &mut ty {
+        Type::Option(t) => Type::Option(Box::new(f.fold_type(t.take()))),
+        Type::List(t) => Type::List(Box::new(f.fold_type(t.take()))),
+        Type::Set(t) => Type::Set(Box::new(f.fold_type(t.take()))),
+        Type::Map(tk, tv) => {
+            let tk = f.fold_type(tk.take());
+            Type::Map(Box::new(tk), Box::new(f.fold_type(tv.take())))
+        },
+        Type::Tuple(s) => Type::Tuple(mem::take(s).into_iter().map(|t| f.fold_type(t)).collect()),
+        Type::Alias(ptr) => Type::Alias(f.fold_type_ptr(*ptr)),
+        Type::CEnum(ptr) => Type::CEnum(f.fold_type_ptr(*ptr)),
+        Type::Enum(ptr) => Type::Enum(f.fold_type_ptr(*ptr)),
+        Type::Struct(ptr) => Type::Struct(f.fold_type_ptr(*ptr)),
+        _ => ty,
+    }
+}
@@ -32,7 +32,7 @@ fn ptr2tokens(ptr: TypePtr) -> TokenStream {
     }
 }
 
-fn type2tokens(ty: Type) -> TokenStream {
+fn type2tokens(ty: &Type) -> TokenStream {
     match ty {
         Type::Unknown => quote!(Type::Unknown),
         Type::Unit => quote!(Type::Unit),
@@ -49,45 +49,55 @@ fn type2tokens(ty: Type) -> TokenStream {
         Type::UInt8 => quote!(Type::UInt8),
         Type::UInt16 => quote!(Type::UInt16),
         Type::UInt32 => quote!(Type::UInt32),
+        Type::Int8 => quote!(Type::Int8),
+        Type::Int16 => quote!(Type::Int16),
+        Type::Int32 => quote!(Type::Int32),
+        Type::Float32 => quote!(Type::Float32),
+        Type::BigInt => quote!(Type::BigInt),
+        Type::Decimal => quote!(Type::Decimal),
 
         Type::Option(sty) => {
-            let sty = type2tokens(*sty);
+            let sty = type2tokens(sty);
             quote!(Type::Option(Box::new(#sty)))
         },
         Type::List(sty) => {
-            let sty = type2tokens(*sty);
+            let sty = type2tokens(sty);
             quote!(Type::List(Box::new(#sty)))
         },
+        Type::Set(sty) => {
+            let sty = type2tokens(sty);
+            quote!(Type::Set(Box::new(#sty)))
+        },
         Type::Map(styk, styv) => {
-            let styk = type2tokens(*styk);
-            let styv = type2tokens(*styv);
+            let styk = type2tokens(styk);
+            let styv = type2tokens(styv);
             quote!(Type::Map(Box::new(#styk), Box::new(#styv)))
         },
         Type::Tuple(stys) => {
-            let stys = stys.into_iter().map(type2tokens);
+            let stys = stys.iter().map(type2tokens);
             quote!(Type::Tuple(vec![#(#stys,)*]))
         },
 
         Type::Alias(ptr) => {
-            let ptr = ptr2tokens(ptr);
+            let ptr = ptr2tokens(*ptr);
             quote!(Type::Alias(#ptr))
         },
         Type::CEnum(ptr) => {
-            let ptr = ptr2tokens(ptr);
+            let ptr = ptr2tokens(*ptr);
             quote!(Type::CEnum(#ptr))
         },
         Type::Enum(ptr) => {
-            let ptr = ptr2tokens(ptr);
+            let ptr = ptr2tokens(*ptr);
             quote!(Type::Enum(#ptr))
         },
         Type::Struct(ptr) => {
-            let ptr = ptr2tokens(ptr);
+            let ptr = ptr2tokens(*ptr);
             quote!(Type::Struct(#ptr))
         },
     }
 }
 
-fn type2type(ty: Type) -> TokenStream {
+fn type2type(ty: &Type) -> TokenStream {
     match ty {
         Type::Unknown => quote!(Value),
         Type::Unit => quote!(()),
@@ -104,133 +114,169 @@ fn type2type(ty: Type) -> TokenStream {
         Type::UInt8 => quote!(u8),
         Type::UInt16 => quote!(u16),
         Type::UInt32 => quote!(u32),
+        Type::Int8 => quote!(i8),
+        Type::Int16 => quote!(i16),
+        Type::Int32 => quote!(i32),
+        Type::Float32 => quote!(f32),
+        Type::BigInt => quote!(BigInt),
+        Type::Decimal => quote!(Decimal),
 
         Type::Option(sty) => {
-            let sty = type2type(*sty);
+            let sty = type2type(sty);
             quote!(Option<#sty>)
         },
         Type::List(sty) => {
-            let sty = type2type(*sty);
+            let sty = type2type(sty);
             quote!(Vec<#sty>)
         },
+        Type::Set(sty) => {
+            let sty = type2type(sty);
+            quote!(BTreeSet<#sty>)
+        },
         Type::Map(styk, styv) => {
-            let styk = type2type(*styk);
-            let styv = type2type(*styv);
+            let styk = type2type(styk);
+            let styv = type2type(styv);
             quote!(Vec<(#styk, #styv)>)
         },
         Type::Tuple(stys) => {
-            let stys = stys.into_iter().map(type2type);
+            let stys = stys.iter().map(type2type);
             quote!((#(#stys,)*))
         },
         
         Type::Alias(ptr) |
         Type::CEnum(ptr) |
         Type::Enum(ptr) |
-        Type::Struct(ptr) => ptr2rustpath(ptr),
+        Type::Struct(ptr) => ptr2rustpath(*ptr),
+    }
+}
+
+// whether the rust type of `ty` converts through `IntoValue` and `FromValue`, which read `Vec<u8>`
+// as a list and `Vec<(K, V)>` as a list of tuples
+fn native(ty: &Type) -> bool {
+    match ty {
+        Type::Bytes | Type::Map(_, _) => false,
+        Type::Option(sty) | Type::List(sty) | Type::Set(sty) => native(sty),
+        Type::Tuple(stys) => stys.iter().all(native),
+        _ => true,
     }
 }
 
-fn type2de(ty: Type, v: TokenStream) -> TokenStream {
+fn type2de(ty: &Type, v: TokenStream) -> TokenStream {
+    if native(ty) {
+        return quote!(FromValue::try_from_value(#v)?);
+    }
     match ty {
-        Type::Unknown => quote!(#v),
-        Type::Unit => quote!(#v.into_unit()),
-        Type::Bool => quote!(#v.into_bool()),
-        Type::Int => quote!(#v.into_int()),
-        Type::UInt => quote!(#v.into_uint()),
-        Type::Float => quote!(#v.into_float()),
-        Type::String => quote!(#v.into_string()),
-        Type::Bytes => quote!(#v.into_bytes()),
-        Type::Type => quote!(#v.into_type()),
-        Type::TypePtr => quote!(#v.into_type_ptr()),
-        Type::ObjectPtr => quote!(#v.into_object_ptr()),
-        Type::Timestamp => quote!(#v.into_timestamp()),
-        Type::UInt8 => quote!(#v.into_uint8()),
-        Type::UInt16 => quote!(#v.into_uint16()),
-        Type::UInt32 => quote!(#v.into_uint32()),
+        Type::Bytes => quote!(#v.try_into_bytes()?),
 
         Type::Option(sty) => {
-            let sty = type2de(*sty, quote!(sv));
-            quote!(#v.into_option().map(|sv| #sty))
+            let sty = type2de(sty, quote!(sv));
+            quote!(#v.try_into_option()?.map(|sv| -> DeserializeResult<_> { Ok(#sty) }).transpose()?)
         },
         Type::List(sty) => {
-            let sty = type2de(*sty, quote!(sv));
-            quote!(#v.into_list().into_iter().map(|sv| #sty).collect())
+            let sty = type2de(sty, quote!(sv));
+            quote!(#v.try_into_list()?.into_iter().map(|sv| -> DeserializeResult<_> { Ok(#sty) }).collect::<DeserializeResult<_>>()?)
+        },
+        Type::Set(sty) => {
+            let sty = type2de(sty, quote!(sv));
+            quote!(#v.try_into_set()?.into_iter().map(|sv| -> DeserializeResult<_> { Ok(#sty) }).collect::<DeserializeResult<_>>()?)
         },
+        Type::Map(styk, styv) if native(styk) && native(styv) => quote!(try_from_map_value(#v)?),
         Type::Map(styk, styv) => {
-            let styk = type2de(*styk, quote!(sk));
-            let styv = type2de(*styv, quote!(sv));
-            quote!(#v.into_map().into_iter().map(|(sk, sv)| (#styk, #styv)).collect())
+            let styk = type2de(styk, quote!(sk));
+            let styv = type2de(styv, quote!(sv));
+            quote!(#v.try_into_map()?.into_iter().map(|(sk, sv)| -> DeserializeResult<_> { Ok((#styk, #styv)) }).collect::<DeserializeResult<_>>()?)
         },
         Type::Tuple(stys) => {
-            let stys = stys.into_iter().enumerate().map(|(i, sty)| type2de(sty, quote!(sv[#i])));
+            let len = stys.len();
+            let names = (0..len).map(|i| ident(concat_string!("sv", i.to_string())));
+            let stys = stys.iter().enumerate().map(|(i, sty)| type2de(sty, ident(concat_string!("sv", i.to_string()))));
             quote!({
-                let sv = #v.into_tuple();
+                let [#(#names,)*]: [Value; #len] = #v.try_into_tuple_sized()?;
                 (#(#stys,)*)
             })
         }
 
-        Type::Alias(_) |
-        Type::CEnum(_) |
-        Type::Enum(_) |
-        Type::Struct(_) => quote!(#v.deserialize_into()),
+        _ => unreachable!(), // native
     }
 }
 
-fn type2ser(ty: Type, v: TokenStream) -> TokenStream {
+fn type2ser(ty: &Type, v: TokenStream) -> TokenStream {
+    if native(ty) {
+        return quote!(#v.into_value());
+    }
     match ty {
-        Type::Unknown => quote!(#v),
-        Type::Unit => quote!({ let _ = #v; Value::Unit }),
-        Type::Bool => quote!(Value::Bool(#v)),
-        Type::Int => quote!(Value::Int(#v)),
-        Type::UInt => quote!(Value::UInt(#v)),
-        Type::Float => quote!(Value::from_float(#v)),
-        Type::String => quote!(Value::String(#v)),
         Type::Bytes => quote!(Value::Bytes(#v)),
-        Type::Type => quote!(Value::Type(#v)),
-        Type::TypePtr => quote!(Value::TypePtr(#v)),
-        Type::ObjectPtr => quote!(Value::ObjectPtr(#v)),
-        Type::Timestamp => quote!(Value::Timestamp(#v)),
-        Type::UInt8 => quote!(Value::UInt8(#v)),
-        Type::UInt16 => quote!(Value::UInt16(#v)),
-        Type::UInt32 => quote!(Value::UInt32(#v)),
 
         Type::Option(sty) => {
-            let sty_ty = type2tokens(*sty.clone());
-            let sty = type2ser(*sty, quote!(sv));
+            let sty_ty = type2tokens(sty);
+            let sty = type2ser(sty, quote!(sv));
             quote!(Value::Option(#sty_ty, Box::new(#v.map(|sv| #sty))))
         },
         Type::List(sty) => {
-            let sty_ty = type2tokens(*sty.clone());
-            let sty = type2ser(*sty, quote!(sv));
+            let sty_ty = type2tokens(sty);
+            let sty = type2ser(sty, quote!(sv));
             quote!(Value::List(#sty_ty, #v.into_iter().map(|sv| #sty).collect()))
         },
+        Type::Set(sty) => {
+            let sty_ty = type2tokens(sty);
+            let sty = type2ser(sty, quote!(sv));
+            quote!(Value::new_set(#sty_ty, #v.into_iter().map(|sv| #sty).collect()))
+        },
+        Type::Map(styk, styv) if native(styk) && native(styv) => quote!(into_map_value(#v)),
         Type::Map(styk, styv) => {
-            let styk_ty = type2tokens(*styk.clone());
-            let styv_ty = type2tokens(*styv.clone());
-            let styk = type2ser(*styk, quote!(sk));
-            let styv = type2ser(*styv, quote!(sv));
+            let styk_ty = type2tokens(styk);
+            let styv_ty = type2tokens(styv);
+            let styk = type2ser(styk, quote!(sk));
+            let styv = type2ser(styv, quote!(sv));
             quote!(Value::Map((#styk_ty, #styv_ty), #v.into_iter().map(|(sk, sv)| (#styk, #styv)).collect()))
         },
         Type::Tuple(stys) => {
-            let stys = stys.into_iter().enumerate().map(|(i, sty)| type2ser(sty, quote!(sv.#i)));
+            let stys = stys.iter().enumerate().map(|(i, sty)| type2ser(sty, quote!(sv.#i)));
             quote!(Value::Tuple(vec![#(#stys,)*]))
         },
 
-        Type::Alias(_) |
-        Type::CEnum(_) |
-        Type::Enum(_) |
-        Type::Struct(_) => quote!(#v.serialize()),
+        _ => unreachable!(), // native
     }
 }
 
 fn derive_def(ptr: u16, dt: DefType) -> TokenStream {
+    let name = ptr2rustname(TypePtr::from_u16_unchecked(ptr));
+    // an alias serializes to the bare inner value, so that is its type too
+    let ty = match &dt {
+        DefType::Alias(ty) => type2tokens(ty),
+        DefType::CEnum(_) => quote!(Type::CEnum(Self::PTR)),
+        DefType::Enum(_) => quote!(Type::Enum(Self::PTR)),
+        DefType::Struct(_) => quote!(Type::Struct(Self::PTR)),
+    };
+    let mut out = derive_schema(ptr, dt);
+    out.extend(quote!(
+        impl IntoValue for #name {
+            fn ty() -> Type {
+                #ty
+            }
+
+            fn into_value(self) -> Value {
+                self.serialize()
+            }
+        }
+
+        impl FromValue for #name {
+            fn try_from_value(val: Value) -> DeserializeResult<Self> {
+                Self::try_deserialize(val)
+            }
+        }
+    ));
+    out
+}
+
+fn derive_schema(ptr: u16, dt: DefType) -> TokenStream {
     let ptr_literal = Literal::u16_unsuffixed(ptr);
     match dt {
         DefType::Alias(ty) => {
             let name = ptr2rustname(TypePtr::from_u16_unchecked(ptr));
-            let ser = type2ser(ty.clone(), quote!(self.0));
-            let de = type2de(ty.clone(), quote!(val));
-            let ty = type2type(ty);
+            let ser = type2ser(&ty, quote!(self.0));
+            let de = type2de(&ty, quote!(val));
+            let ty = type2type(&ty);
             quote!(
                 #[derive(Clone, Debug, PartialEq, Eq)]
                 pub struct #name(pub #ty);
@@ -242,8 +288,8 @@ fn derive_def(ptr: u16, dt: DefType) -> TokenStream {
                         #ser
                     }
 
-                    fn deserialize(val: Value) -> Self {
-                        Self(#de)
+                    fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+                        Ok(Self(#de))
                     }
                 }
 
@@ -287,12 +333,13 @@ fn derive_def(ptr: u16, dt: DefType) -> TokenStream {
                         )
                     }
 
-                    fn deserialize(val: Value) -> Self {
-                        let variant = val.into_c_enum();
-                        match variant {
+                    fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+                        val.check_def(Self::PTR)?;
+                        let variant = val.try_into_c_enum()?;
+                        Ok(match variant {
                             #(#i2 => Self::#names4,)*
-                            _ => unreachable!(),
-                        }
+                            _ => return Err(DeserializeError::Variant(variant)),
+                        })
                     }
                 }
             )
@@ -307,9 +354,9 @@ fn derive_def(ptr: u16, dt: DefType) -> TokenStream {
             let names4 = names.clone();
             let i = (0..len).map(Literal::u64_unsuffixed);
             let i2 = i.clone();
-            let sers = tys.clone().into_iter().map(|ty| type2ser(ty, quote!(val)));
-            let des = tys.clone().into_iter().map(|ty| type2de(ty, quote!(val)));
-            let tys = tys.into_iter().map(type2type);
+            let sers = tys.iter().map(|ty| type2ser(ty, quote!(val)));
+            let des = tys.iter().map(|ty| type2de(ty, quote!(val)));
+            let tys = tys.iter().map(type2type);
 
             quote!(
                 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -332,12 +379,13 @@ fn derive_def(ptr: u16, dt: DefType) -> TokenStream {
                         )
                     }
 
-                    fn deserialize(val: Value) -> Self {
-                        let (variant, val) = val.into_enum();
-                        match variant {
+                    fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+                        val.check_def(Self::PTR)?;
+                        let (variant, val) = val.try_into_enum()?;
+                        Ok(match variant {
                             #(#i2 => Self::#names4(#des),)*
-                            _ => unreachable!(),
-                        }
+                            _ => return Err(DeserializeError::Variant(variant)),
+                        })
                     }
                 }
             )
@@ -349,16 +397,16 @@ fn derive_def(ptr: u16, dt: DefType) -> TokenStream {
             let names = names.into_iter().map(|name| ident(to_snake_case(&name)));
             let names2 = names.clone();
             let names3 = names.clone();
-            let sers = fields.clone().into_iter().map(|(name, ty)| type2ser(ty, ident(concat_string!("self.", to_snake_case(&name)))));
-            let des = fields.into_iter().map(|(name, ty)| type2de(ty, ident(to_snake_case(&name))));
-            let tys = tys.into_iter().map(type2type);
+            let sers = fields.iter().map(|(name, ty)| type2ser(ty, ident(concat_string!("self.", to_snake_case(name)))));
+            let des = fields.iter().map(|(name, ty)| type2de(ty, ident(to_snake_case(name))));
+            let tys = tys.iter().map(type2type);
 
             let ord = if [
                 0x0007, // std:meta:rev-ptr
                 0x0008, // std:meta:commit-ptr
-                0x000D, // std:meta:state-rev-ptr
+                0x000C, // std:meta:state-rev-ptr
             ].contains(&ptr) {
-                quote!(#[derive(PartialOrd, Ord)])
+                quote!(#[derive(PartialOrd, Ord, Hash)])
             } else {
                 quote!()
             };
@@ -379,11 +427,12 @@ fn derive_def(ptr: u16, dt: DefType) -> TokenStream {
                         ])
                     }
 
-                    fn deserialize(val: Value) -> Self {
-                        let [#(#names2,)*]: [Value; #len] = val.into_struct().try_into().unwrap();
-                        Self {
+                    fn try_deserialize(val: Value) -> DeserializeResult<Self> {
+                        val.check_def(Self::PTR)?;
+                        let [#(#names2,)*]: [Value; #len] = val.try_into_struct_sized()?;
+                        Ok(Self {
                             #(#names3: #des,)*
-                        }
+                        })
                     }
                 }
             )
@@ -413,11 +462,13 @@ fn derive_file() -> TokenStream {
         clippy::redundant_closure, // Value::List(Type::SimpleType, self.field.into_iter().map(|sv| Value::SimpleType(sv)).collect()),
         clippy::redundant_field_names, // (Type::Unknown) Struct { field: field }
         clippy::map_identity, // (Type::List(Type::Unknown)) val.into_list().into_iter().map(|sv| sv).collect(),
+        clippy::needless_question_mark, // .map(|sv| -> DeserializeResult<_> { Ok(sv.try_into_string()?) })
     )]);
     file.extend(map.into_iter().map(|(path, outs)| {
         let path = ident(path);
         quote!(
             pub mod #path {
+                use std::collections::BTreeSet;
                 use crate::{types::*, meta::{Timestamp, ObjectPtr, TypePtr}};
                 #(#outs)*
             }